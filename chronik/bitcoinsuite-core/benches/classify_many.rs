@@ -0,0 +1,83 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+//! Benchmark of classifying many scripts with [`classify_many`] vs. a loop
+//! over [`ScriptVariant::from_script`].
+//!
+//! Uses a plain timing loop, as the workspace has no benchmark framework.
+
+use std::time::{Duration, Instant};
+
+use bitcoinsuite_core::{
+    hash::ShaRmd160,
+    script::{
+        classify_many, PubKey, Script, ScriptType, ScriptVariant,
+        UncompressedPubKey,
+    },
+};
+
+const NUM_SCRIPTS: usize = 10_000;
+const NUM_ROUNDS: usize = 100;
+
+fn make_scripts() -> Vec<Script> {
+    (0..NUM_SCRIPTS)
+        .map(|idx| {
+            let byte = idx as u8;
+            match idx % 5 {
+                0 => Script::p2pkh(&ShaRmd160([byte; 20])),
+                1 => Script::p2sh(&ShaRmd160([byte; 20])),
+                2 => Script::p2pk(&PubKey([byte; 33])),
+                3 => Script::p2pk_uncompressed(&UncompressedPubKey([byte; 65])),
+                // OP_RETURN with a push
+                _ => Script::new([&[0x6a, 0x04][..], b"test"].concat().into()),
+            }
+        })
+        .collect()
+}
+
+/// Run `f` over all scripts `NUM_ROUNDS` times, returning the fastest round
+/// and a checksum of the results, so the work can't be optimized away.
+fn bench(
+    scripts: &[Script],
+    f: impl Fn(&[Script]) -> Vec<ScriptType>,
+) -> (Duration, usize) {
+    let mut fastest = Duration::MAX;
+    let mut checksum = 0;
+    for _ in 0..NUM_ROUNDS {
+        let start = Instant::now();
+        let script_types = f(scripts);
+        fastest = fastest.min(start.elapsed());
+        checksum += script_types
+            .iter()
+            .enumerate()
+            .map(|(idx, &script_type)| idx * script_type as usize)
+            .sum::<usize>();
+    }
+    (fastest, checksum)
+}
+
+fn main() {
+    let scripts = make_scripts();
+    let (naive_time, naive_checksum) = bench(&scripts, |scripts| {
+        let mut script_types = Vec::new();
+        for script in scripts {
+            script_types.push(match ScriptVariant::from_script(script) {
+                ScriptVariant::P2PKH(_) => ScriptType::P2PKH,
+                ScriptVariant::P2SH(_) => ScriptType::P2SH,
+                ScriptVariant::P2PK(_) => ScriptType::P2PK,
+                ScriptVariant::Other(_) => ScriptType::Other,
+            });
+        }
+        script_types
+    });
+    let (many_time, many_checksum) = bench(&scripts, classify_many);
+    assert_eq!(naive_checksum, many_checksum);
+    println!("{NUM_SCRIPTS} scripts, fastest of {NUM_ROUNDS} rounds:");
+    println!("ScriptVariant::from_script loop: {naive_time:?}");
+    println!("classify_many:                   {many_time:?}");
+    println!(
+        "speedup:                         {:.2}x",
+        naive_time.as_secs_f64() / many_time.as_secs_f64(),
+    );
+}
@@ -0,0 +1,91 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+//! Benchmark of iterating the ops of many scripts with [`Script::iter_ops`]
+//! vs. [`Script::iter_ops_ref`].
+//!
+//! Uses a plain timing loop, as the workspace has no benchmark framework.
+
+use std::time::{Duration, Instant};
+
+use bitcoinsuite_core::{
+    hash::ShaRmd160,
+    script::{Op, OpRef, Script},
+};
+
+const NUM_SCRIPTS: usize = 10_000;
+const NUM_ROUNDS: usize = 100;
+
+fn make_scripts() -> Vec<Script> {
+    (0..NUM_SCRIPTS)
+        .map(|idx| {
+            let hash = ShaRmd160([idx as u8; 20]);
+            match idx % 3 {
+                0 => Script::p2pkh(&hash),
+                1 => Script::p2sh(&hash),
+                // OP_RETURN with a few pushes of various sizes
+                _ => Script::new(
+                    [
+                        &[0x6a, 0x04][..],
+                        b"test",
+                        &[0x4c, 80],
+                        &[idx as u8; 80],
+                        &[0x4d, 0x00, 0x01],
+                        &[idx as u8; 0x100],
+                    ]
+                    .concat()
+                    .into(),
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Run `f` over all scripts `NUM_ROUNDS` times, returning the fastest round
+/// and a checksum of the results, so the work can't be optimized away.
+fn bench(
+    scripts: &[Script],
+    f: impl Fn(&Script) -> usize,
+) -> (Duration, usize) {
+    let mut fastest = Duration::MAX;
+    let mut checksum = 0;
+    for _ in 0..NUM_ROUNDS {
+        let start = Instant::now();
+        for script in scripts {
+            checksum += f(script);
+        }
+        fastest = fastest.min(start.elapsed());
+    }
+    (fastest, checksum)
+}
+
+fn main() {
+    let scripts = make_scripts();
+    let (owned_time, owned_checksum) = bench(&scripts, |script| {
+        script
+            .iter_ops()
+            .map(|op| match op.unwrap() {
+                Op::Code(opcode) => opcode.number() as usize,
+                Op::Push(_, data) => data.len(),
+            })
+            .sum()
+    });
+    let (ref_time, ref_checksum) = bench(&scripts, |script| {
+        script
+            .iter_ops_ref()
+            .map(|op| match op.unwrap() {
+                OpRef::Code(opcode) => opcode.number() as usize,
+                OpRef::Push(_, data) => data.len(),
+            })
+            .sum()
+    });
+    assert_eq!(owned_checksum, ref_checksum);
+    println!("{NUM_SCRIPTS} scripts, fastest of {NUM_ROUNDS} rounds:");
+    println!("iter_ops:     {owned_time:?}");
+    println!("iter_ops_ref: {ref_time:?}");
+    println!(
+        "speedup:      {:.2}x",
+        owned_time.as_secs_f64() / ref_time.as_secs_f64(),
+    );
+}
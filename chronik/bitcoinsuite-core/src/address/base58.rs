@@ -0,0 +1,321 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Display, Formatter};
+
+use crate::{
+    hash::{Hashed, Sha256d, ShaRmd160},
+    script::ScriptVariant,
+};
+
+/// Characters of the Base58 encoding, indexed by their value.
+const ALPHABET: &[u8; 58] =
+    b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Number of checksum bytes at the end of Base58Check data.
+const CHECKSUM_LEN: usize = 4;
+
+/// Errors indicating a string is not valid Base58Check data or address.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Base58Error {
+    /// String has a character that's not in the Base58 alphabet.
+    InvalidChar(char),
+    /// Decoded data is too short to have a checksum, or it doesn't match.
+    InvalidChecksum,
+    /// Address has a payload of the wrong size.
+    InvalidLength {
+        /// Expected number of bytes, version byte plus hash.
+        expected: usize,
+        /// Actual number of bytes.
+        actual: usize,
+    },
+    /// Address has a version byte that's neither P2PKH nor P2SH.
+    UnknownVersion(u8),
+}
+
+impl Display for Base58Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        use self::Base58Error::*;
+        match self {
+            InvalidChar(chr) => write!(f, "Invalid Base58 character {chr:?}"),
+            InvalidChecksum => write!(f, "Invalid Base58Check checksum"),
+            InvalidLength { expected, actual } => write!(
+                f,
+                "Invalid Base58 address length, expected {expected} bytes but \
+                 got {actual}"
+            ),
+            UnknownVersion(version) => {
+                write!(f, "Unknown Base58 address version {version}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Base58Error {}
+
+use self::Base58Error::*;
+
+/// Encode `payload` as Base58, with a 4-byte double-SHA256 checksum.
+/// ```
+/// # use bitcoinsuite_core::address::encode_base58check;
+/// assert_eq!(encode_base58check(&[]), "3QJmnh");
+/// assert_eq!(encode_base58check(&[0, 0, 1]), "11BwW2qR");
+/// ```
+pub fn encode_base58check(payload: &[u8]) -> String {
+    let checksum = Sha256d::digest(payload);
+    let mut data = Vec::with_capacity(payload.len() + CHECKSUM_LEN);
+    data.extend_from_slice(payload);
+    data.extend_from_slice(&checksum.as_le_bytes()[..CHECKSUM_LEN]);
+    encode_base58(&data)
+}
+
+/// Decode Base58Check data, verifying and removing the checksum.
+/// ```
+/// # use bitcoinsuite_core::address::{decode_base58check, Base58Error};
+/// assert_eq!(decode_base58check("11BwW2qR"), Ok(vec![0, 0, 1]));
+/// assert_eq!(
+///     decode_base58check("11BwW2qS"),
+///     Err(Base58Error::InvalidChecksum),
+/// );
+/// ```
+pub fn decode_base58check(string: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut data = decode_base58(string)?;
+    if data.len() < CHECKSUM_LEN {
+        return Err(InvalidChecksum);
+    }
+    let checksum = data.split_off(data.len() - CHECKSUM_LEN);
+    if checksum != Sha256d::digest(&data).as_le_bytes()[..CHECKSUM_LEN] {
+        return Err(InvalidChecksum);
+    }
+    Ok(data)
+}
+
+/// Encode a P2PKH or P2SH [`ScriptVariant`] as legacy Base58Check address
+/// with the given version byte, or [`None`] for other variants.
+///
+/// The version byte tells which kind of script the address is for, so it
+/// has to be picked for the variant, e.g. 0 for P2PKH and 5 for P2SH on
+/// mainnet.
+/// ```
+/// # use bitcoinsuite_core::{
+/// #     address::encode_base58_address,
+/// #     hash::ShaRmd160,
+/// #     script::ScriptVariant,
+/// # };
+/// # use hex_literal::hex;
+/// let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+/// assert_eq!(
+///     encode_base58_address(&ScriptVariant::P2PKH(hash), 0).as_deref(),
+///     Some("1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu"),
+/// );
+/// assert_eq!(
+///     encode_base58_address(&ScriptVariant::P2SH(hash), 5).as_deref(),
+///     Some("3CWFddi6m4ndiGyKqzYvsFYagqDLPVMTzC"),
+/// );
+/// ```
+pub fn encode_base58_address(
+    variant: &ScriptVariant,
+    version: u8,
+) -> Option<String> {
+    let hash = match variant {
+        ScriptVariant::P2PKH(hash) | ScriptVariant::P2SH(hash) => hash,
+        _ => return None,
+    };
+    let mut payload = Vec::with_capacity(1 + ShaRmd160::SIZE);
+    payload.push(version);
+    payload.extend_from_slice(hash.as_le_bytes());
+    Some(encode_base58check(&payload))
+}
+
+/// Decode a legacy Base58Check address into the P2PKH or P2SH
+/// [`ScriptVariant`] it encodes, depending on whether its version byte is
+/// `p2pkh_version` or `p2sh_version`.
+/// ```
+/// # use bitcoinsuite_core::{
+/// #     address::decode_base58_address,
+/// #     hash::ShaRmd160,
+/// #     script::ScriptVariant,
+/// # };
+/// # use hex_literal::hex;
+/// let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+/// assert_eq!(
+///     decode_base58_address("1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu", 0, 5),
+///     Ok(ScriptVariant::P2PKH(hash)),
+/// );
+/// assert_eq!(
+///     decode_base58_address("3CWFddi6m4ndiGyKqzYvsFYagqDLPVMTzC", 0, 5),
+///     Ok(ScriptVariant::P2SH(hash)),
+/// );
+/// ```
+pub fn decode_base58_address(
+    addr: &str,
+    p2pkh_version: u8,
+    p2sh_version: u8,
+) -> Result<ScriptVariant, Base58Error> {
+    let payload = decode_base58check(addr)?;
+    if payload.len() != 1 + ShaRmd160::SIZE {
+        return Err(InvalidLength {
+            expected: 1 + ShaRmd160::SIZE,
+            actual: payload.len(),
+        });
+    }
+    let hash = ShaRmd160(payload[1..].try_into().unwrap());
+    match payload[0] {
+        version if version == p2pkh_version => Ok(ScriptVariant::P2PKH(hash)),
+        version if version == p2sh_version => Ok(ScriptVariant::P2SH(hash)),
+        version => Err(UnknownVersion(version)),
+    }
+}
+
+/// Encode `data` as Base58, each leading zero byte is encoded as "1".
+fn encode_base58(data: &[u8]) -> String {
+    let num_zeros = data.iter().take_while(|&&byte| byte == 0).count();
+    // Base58 digits of the rest, least significant first
+    let mut digits = Vec::<u8>::with_capacity(data.len() * 138 / 100 + 1);
+    for &byte in &data[num_zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in &mut digits {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut string = String::with_capacity(num_zeros + digits.len());
+    string.extend(core::iter::repeat('1').take(num_zeros));
+    string.extend(
+        digits
+            .iter()
+            .rev()
+            .map(|&digit| char::from(ALPHABET[usize::from(digit)])),
+    );
+    string
+}
+
+/// Decode Base58 `string`, each leading "1" is decoded as a zero byte.
+fn decode_base58(string: &str) -> Result<Vec<u8>, Base58Error> {
+    let num_zeros = string.chars().take_while(|&chr| chr == '1').count();
+    // Bytes of the rest, least significant first
+    let mut bytes = Vec::<u8>::with_capacity(string.len() * 733 / 1000 + 1);
+    for chr in string.chars().skip(num_zeros) {
+        let value = ALPHABET
+            .iter()
+            .position(|&alphabet_chr| char::from(alphabet_chr) == chr)
+            .ok_or(InvalidChar(chr))?;
+        let mut carry = value as u32;
+        for byte in &mut bytes {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut data = Vec::with_capacity(num_zeros + bytes.len());
+    data.resize(num_zeros, 0);
+    data.extend(bytes.iter().rev());
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use hex_literal::hex;
+
+    use crate::{
+        address::{
+            decode_base58_address, decode_base58check, encode_base58_address,
+            encode_base58check, Base58Error,
+        },
+        hash::ShaRmd160,
+        script::{Script, ScriptVariant},
+    };
+
+    #[test]
+    fn test_base58check_roundtrip() {
+        for payload in [
+            vec![],
+            vec![0],
+            vec![0, 0, 0],
+            vec![0xff; 20],
+            vec![0, 0, 0xff, 0, 1],
+            (0..=255).collect(),
+        ] {
+            let string = encode_base58check(&payload);
+            assert_eq!(decode_base58check(&string), Ok(payload));
+        }
+    }
+
+    #[test]
+    fn test_base58_address() {
+        let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+        let p2pkh = ScriptVariant::P2PKH(hash);
+        let p2sh = ScriptVariant::P2SH(hash);
+        let vectors = [
+            (&p2pkh, 0x00, "1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu"),
+            (&p2sh, 0x05, "3CWFddi6m4ndiGyKqzYvsFYagqDLPVMTzC"),
+            (&p2pkh, 0x6f, "mrLC19Je2BuWQDkWSTriGYPyQJXKkkBmCx"),
+            (&p2sh, 0xc4, "2N44ThNe8NXHyv4bsX8AoVCXquBRW94Ls7W"),
+        ];
+        for (variant, version, addr) in vectors {
+            assert_eq!(
+                encode_base58_address(variant, version).as_deref(),
+                Some(addr),
+            );
+            let is_p2pkh = matches!(variant, ScriptVariant::P2PKH(_));
+            let (p2pkh_version, p2sh_version) = match is_p2pkh {
+                true => (version, version.wrapping_add(1)),
+                false => (version.wrapping_add(1), version),
+            };
+            assert_eq!(
+                decode_base58_address(addr, p2pkh_version, p2sh_version),
+                Ok(variant.clone()),
+            );
+        }
+        assert_eq!(
+            encode_base58_address(
+                &ScriptVariant::P2PKH(ShaRmd160([0; 20])),
+                0,
+            )
+            .as_deref(),
+            Some("1111111111111111111114oLvT2"),
+        );
+        let other = ScriptVariant::Other(Script::default());
+        assert_eq!(encode_base58_address(&other, 0), None);
+    }
+
+    #[test]
+    fn test_base58_address_failure() {
+        let decode = |addr| decode_base58_address(addr, 0, 5);
+        assert_eq!(
+            decode("1BpEi6DfDAUFd7GtittLSdBeYJvcoaVgg0"),
+            Err(Base58Error::InvalidChar('0')),
+        );
+        assert_eq!(
+            decode("1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggv"),
+            Err(Base58Error::InvalidChecksum),
+        );
+        assert_eq!(decode(""), Err(Base58Error::InvalidChecksum));
+        // Testnet address with mainnet versions
+        assert_eq!(
+            decode("mrLC19Je2BuWQDkWSTriGYPyQJXKkkBmCx"),
+            Err(Base58Error::UnknownVersion(0x6f)),
+        );
+        assert_eq!(
+            decode(&encode_base58check(&[0; 20])),
+            Err(Base58Error::InvalidLength {
+                expected: 21,
+                actual: 20,
+            }),
+        );
+    }
+}
@@ -0,0 +1,432 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Display, Formatter};
+
+use crate::{
+    hash::{Hashed, ShaRmd160},
+    script::ScriptVariant,
+};
+
+/// Characters of the CashAddr base32 encoding, indexed by their value.
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Number of 5-bit groups of the checksum at the end of the address.
+const CHECKSUM_LEN: usize = 8;
+
+/// CashAddr type for P2PKH addresses.
+const TYPE_P2PKH: u8 = 0;
+
+/// CashAddr type for P2SH addresses.
+const TYPE_P2SH: u8 = 1;
+
+/// Errors indicating a string is not a valid CashAddr address.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CashAddrError {
+    /// Address has both lowercase and uppercase characters.
+    MixedCase,
+    /// Address has a character that's not in the CashAddr charset.
+    InvalidChar(char),
+    /// Address has a different prefix than expected.
+    InvalidPrefix {
+        /// Expected prefix, e.g. "ecash".
+        expected: String,
+        /// Prefix of the address.
+        actual: String,
+    },
+    /// Checksum of the address is invalid.
+    InvalidChecksum,
+    /// Payload of the address has non-zero padding bits or too many of them.
+    InvalidPadding,
+    /// Payload is empty, i.e. it doesn't even have a version byte.
+    EmptyPayload,
+    /// Reserved bit of the version byte is set.
+    InvalidVersion(u8),
+    /// Hash size encoded in the version byte doesn't match the payload.
+    InvalidHashSize {
+        /// Hash size encoded in the version byte.
+        expected: usize,
+        /// Size of the hash in the payload.
+        actual: usize,
+    },
+    /// Address is valid, but encodes a hash that's not 160 bits.
+    UnsupportedHashSize(usize),
+    /// Address is valid, but has a type other than P2PKH or P2SH.
+    UnsupportedType(u8),
+}
+
+impl Display for CashAddrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        use self::CashAddrError::*;
+        match self {
+            MixedCase => write!(f, "Address has mixed case"),
+            InvalidChar(chr) => write!(f, "Invalid CashAddr character {chr:?}"),
+            InvalidPrefix { expected, actual } => write!(
+                f,
+                "Invalid CashAddr prefix, expected {expected:?} but got \
+                 {actual:?}"
+            ),
+            InvalidChecksum => write!(f, "Invalid CashAddr checksum"),
+            InvalidPadding => write!(f, "Invalid CashAddr padding"),
+            EmptyPayload => write!(f, "Empty CashAddr payload"),
+            InvalidVersion(version) => {
+                write!(f, "Invalid CashAddr version byte {version:#04x}")
+            }
+            InvalidHashSize { expected, actual } => write!(
+                f,
+                "Invalid CashAddr hash size, expected {expected} bytes but \
+                 got {actual}"
+            ),
+            UnsupportedHashSize(size) => {
+                write!(f, "Unsupported CashAddr hash size of {size} bytes")
+            }
+            UnsupportedType(addr_type) => {
+                write!(f, "Unsupported CashAddr type {addr_type}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CashAddrError {}
+
+use self::CashAddrError::*;
+
+/// Decode a CashAddr address into the P2PKH or P2SH [`ScriptVariant`] it
+/// encodes.
+///
+/// The address may omit the prefix (e.g. "ecash:"), in which case `hrp` is
+/// assumed; otherwise, its prefix must be `hrp`. Only 160-bit hashes and the
+/// P2PKH and P2SH types are supported, as no other kinds of addresses are
+/// standard.
+///
+/// ```
+/// # use bitcoinsuite_core::{
+/// #     address::decode_cashaddr, hash::ShaRmd160, script::ScriptVariant,
+/// # };
+/// # use hex_literal::hex;
+/// let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+/// let addr = "ecash:qpm2qsznhks23z7629mms6s4cwef74vcwva87rkuu2";
+/// assert_eq!(
+///     decode_cashaddr(addr, "ecash"),
+///     Ok(ScriptVariant::P2PKH(hash)),
+/// );
+/// let addr = "ppm2qsznhks23z7629mms6s4cwef74vcwv2zrv3l8h";
+/// assert_eq!(decode_cashaddr(addr, "ecash"), Ok(ScriptVariant::P2SH(hash)));
+/// ```
+pub fn decode_cashaddr(
+    addr: &str,
+    hrp: &str,
+) -> Result<ScriptVariant, CashAddrError> {
+    let has_lower = addr.bytes().any(|byte| byte.is_ascii_lowercase());
+    let has_upper = addr.bytes().any(|byte| byte.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err(MixedCase);
+    }
+    let (prefix, payload) = match addr.split_once(':') {
+        Some((prefix, payload)) => (prefix, payload),
+        None => (hrp, addr),
+    };
+    if !prefix.eq_ignore_ascii_case(hrp) {
+        return Err(InvalidPrefix {
+            expected: hrp.into(),
+            actual: prefix.into(),
+        });
+    }
+    let values = payload
+        .chars()
+        .map(|chr| {
+            let lower = chr.to_ascii_lowercase();
+            CHARSET
+                .iter()
+                .position(|&charset_chr| char::from(charset_chr) == lower)
+                .map(|value| value as u8)
+                .ok_or(InvalidChar(chr))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let prefix_values = prefix.bytes().map(|byte| byte & 0x1f);
+    let checksum_input = prefix_values.chain([0]).chain(values.iter().copied());
+    if values.len() < CHECKSUM_LEN || polymod(checksum_input) != 0 {
+        return Err(InvalidChecksum);
+    }
+    let data =
+        convert_bits(&values[..values.len() - CHECKSUM_LEN], 5, 8, false)
+            .ok_or(InvalidPadding)?;
+    let (&version, hash) = data.split_first().ok_or(EmptyPayload)?;
+    if version & 0x80 != 0 {
+        return Err(InvalidVersion(version));
+    }
+    let size_bits = usize::from(version & 0x07);
+    let hash_size = [20, 24, 28, 32, 40, 48, 56, 64][size_bits];
+    if hash.len() != hash_size {
+        return Err(InvalidHashSize {
+            expected: hash_size,
+            actual: hash.len(),
+        });
+    }
+    let hash = match hash.try_into() {
+        Ok(hash) => ShaRmd160(hash),
+        Err(_) => return Err(UnsupportedHashSize(hash_size)),
+    };
+    match version >> 3 {
+        TYPE_P2PKH => Ok(ScriptVariant::P2PKH(hash)),
+        TYPE_P2SH => Ok(ScriptVariant::P2SH(hash)),
+        addr_type => Err(UnsupportedType(addr_type)),
+    }
+}
+
+/// Encode a P2PKH or P2SH [`ScriptVariant`] as CashAddr address with the
+/// prefix `hrp` (e.g. "ecash"), or [`None`] for other variants.
+///
+/// The address is lowercase and includes the prefix.
+/// ```
+/// # use bitcoinsuite_core::{
+/// #     address::encode_cashaddr, hash::ShaRmd160, script::ScriptVariant,
+/// # };
+/// # use hex_literal::hex;
+/// let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+/// assert_eq!(
+///     encode_cashaddr(&ScriptVariant::P2PKH(hash), "ecash").as_deref(),
+///     Some("ecash:qpm2qsznhks23z7629mms6s4cwef74vcwva87rkuu2"),
+/// );
+/// assert_eq!(
+///     encode_cashaddr(&ScriptVariant::P2SH(hash), "ecash").as_deref(),
+///     Some("ecash:ppm2qsznhks23z7629mms6s4cwef74vcwv2zrv3l8h"),
+/// );
+/// ```
+pub fn encode_cashaddr(variant: &ScriptVariant, hrp: &str) -> Option<String> {
+    let (addr_type, hash) = match variant {
+        ScriptVariant::P2PKH(hash) => (TYPE_P2PKH, hash),
+        ScriptVariant::P2SH(hash) => (TYPE_P2SH, hash),
+        _ => return None,
+    };
+    // Size bits are 0 for 160-bit hashes
+    let version = addr_type << 3;
+    let mut data = Vec::with_capacity(1 + ShaRmd160::SIZE);
+    data.push(version);
+    data.extend_from_slice(hash.as_le_bytes());
+    let mut values = convert_bits(&data, 8, 5, true)?;
+    let prefix_values = hrp.bytes().map(|byte| byte & 0x1f);
+    let checksum_input = prefix_values
+        .chain([0])
+        .chain(values.iter().copied())
+        .chain([0; CHECKSUM_LEN]);
+    let checksum = polymod(checksum_input);
+    values.extend((0..CHECKSUM_LEN).map(|idx| {
+        let shift = 5 * (CHECKSUM_LEN - 1 - idx);
+        ((checksum >> shift) & 0x1f) as u8
+    }));
+    let mut addr = String::with_capacity(hrp.len() + 1 + values.len());
+    addr.push_str(&hrp.to_ascii_lowercase());
+    addr.push(':');
+    addr.extend(
+        values
+            .iter()
+            .map(|&value| char::from(CHARSET[usize::from(value)])),
+    );
+    Some(addr)
+}
+
+/// BCH code checksum of the CashAddr spec, computed over 5-bit values.
+fn polymod(values: impl IntoIterator<Item = u8>) -> u64 {
+    const GENERATORS: [u64; 5] = [
+        0x98_f2bc_8e61,
+        0x79_b76d_99e2,
+        0xf3_3e5f_b3c4,
+        0xae_2eab_e2a8,
+        0x1e_4f43_e470,
+    ];
+    let mut checksum = 1u64;
+    for value in values {
+        let top = checksum >> 35;
+        checksum = ((checksum & 0x07_ffff_ffff) << 5) ^ u64::from(value);
+        for (bit, generator) in GENERATORS.iter().enumerate() {
+            if (top >> bit) & 1 != 0 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum ^ 1
+}
+
+/// Regroup `data` from groups of `from_bits` into groups of `to_bits`.
+///
+/// If `pad` is set, the remaining bits are padded with zeros; otherwise,
+/// remaining bits must be zero and fewer than `from_bits`, or [`None`] is
+/// returned.
+fn convert_bits(
+    data: &[u8],
+    from_bits: u32,
+    to_bits: u32,
+    pad: bool,
+) -> Option<Vec<u8>> {
+    let mut acc = 0u32;
+    let mut num_bits = 0;
+    let max_value = (1 << to_bits) - 1;
+    let max_acc = (1 << (from_bits + to_bits - 1)) - 1;
+    let mut result =
+        Vec::with_capacity(data.len() * from_bits as usize / to_bits as usize);
+    for &value in data {
+        acc = ((acc << from_bits) | u32::from(value)) & max_acc;
+        num_bits += from_bits;
+        while num_bits >= to_bits {
+            num_bits -= to_bits;
+            result.push(((acc >> num_bits) & max_value) as u8);
+        }
+    }
+    if pad {
+        if num_bits > 0 {
+            result.push(((acc << (to_bits - num_bits)) & max_value) as u8);
+        }
+    } else if num_bits >= from_bits
+        || (acc << (to_bits - num_bits)) & max_value != 0
+    {
+        return None;
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+
+    use crate::{
+        address::{decode_cashaddr, encode_cashaddr, CashAddrError},
+        hash::ShaRmd160,
+        script::ScriptVariant,
+    };
+
+    #[test]
+    fn test_decode_cashaddr() {
+        let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+        let p2pkh = Ok(ScriptVariant::P2PKH(hash));
+        let p2sh = Ok(ScriptVariant::P2SH(hash));
+        // Test vectors from the CashAddr spec
+        let addr = "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a";
+        assert_eq!(decode_cashaddr(addr, "bitcoincash"), p2pkh);
+        let addr = "bitcoincash:ppm2qsznhks23z7629mms6s4cwef74vcwvn0h829pq";
+        assert_eq!(decode_cashaddr(addr, "bitcoincash"), p2sh);
+        // eCash prefixes, upper case and missing prefix
+        let addr = "ecash:qpm2qsznhks23z7629mms6s4cwef74vcwva87rkuu2";
+        assert_eq!(decode_cashaddr(addr, "ecash"), p2pkh);
+        let addr = "ECASH:QPM2QSZNHKS23Z7629MMS6S4CWEF74VCWVA87RKUU2";
+        assert_eq!(decode_cashaddr(addr, "ecash"), p2pkh);
+        let addr = "ppm2qsznhks23z7629mms6s4cwef74vcwv2zrv3l8h";
+        assert_eq!(decode_cashaddr(addr, "ecash"), p2sh);
+        let addr = "ecregtest:qpm2qsznhks23z7629mms6s4cwef74vcwv46z2xur8";
+        assert_eq!(decode_cashaddr(addr, "ecregtest"), p2pkh);
+        let addr = "ecash:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqs7ratqfx";
+        assert_eq!(
+            decode_cashaddr(addr, "ecash"),
+            Ok(ScriptVariant::P2PKH(ShaRmd160([0; 20]))),
+        );
+    }
+
+    #[test]
+    fn test_encode_cashaddr() {
+        let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+        let p2pkh = ScriptVariant::P2PKH(hash);
+        let p2sh = ScriptVariant::P2SH(hash);
+        assert_eq!(
+            encode_cashaddr(&p2pkh, "bitcoincash").as_deref(),
+            Some("bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a"),
+        );
+        assert_eq!(
+            encode_cashaddr(&p2sh, "bitcoincash").as_deref(),
+            Some("bitcoincash:ppm2qsznhks23z7629mms6s4cwef74vcwvn0h829pq"),
+        );
+        assert_eq!(
+            encode_cashaddr(&p2pkh, "ecregtest").as_deref(),
+            Some("ecregtest:qpm2qsznhks23z7629mms6s4cwef74vcwv46z2xur8"),
+        );
+        assert_eq!(
+            encode_cashaddr(&ScriptVariant::P2PKH(ShaRmd160([0; 20])), "ECASH")
+                .as_deref(),
+            Some("ecash:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqs7ratqfx"),
+        );
+        let other = ScriptVariant::Other(Default::default());
+        assert_eq!(encode_cashaddr(&other, "ecash"), None);
+
+        // Roundtrip
+        for hash in [[0; 20], [0xff; 20], [0x5a; 20]] {
+            for variant in [
+                ScriptVariant::P2PKH(ShaRmd160(hash)),
+                ScriptVariant::P2SH(ShaRmd160(hash)),
+            ] {
+                let addr = encode_cashaddr(&variant, "ectest").unwrap();
+                assert_eq!(decode_cashaddr(&addr, "ectest"), Ok(variant));
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_cashaddr_failure() {
+        let decode = |addr| decode_cashaddr(addr, "ecash");
+        assert_eq!(
+            decode("ecash:qpm2qsznhks23z7629mms6s4cwef74vcwvA87rkuu2"),
+            Err(CashAddrError::MixedCase),
+        );
+        assert_eq!(
+            decode("bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a"),
+            Err(CashAddrError::InvalidPrefix {
+                expected: "ecash".into(),
+                actual: "bitcoincash".into(),
+            }),
+        );
+        // Prefix is part of the checksum
+        assert_eq!(
+            decode_cashaddr(
+                "ecash:qpm2qsznhks23z7629mms6s4cwef74vcwva87rkuu2",
+                "ectest",
+            ),
+            Err(CashAddrError::InvalidPrefix {
+                expected: "ectest".into(),
+                actual: "ecash".into(),
+            }),
+        );
+        assert_eq!(
+            decode_cashaddr(
+                "qpm2qsznhks23z7629mms6s4cwef74vcwva87rkuu2",
+                "ectest",
+            ),
+            Err(CashAddrError::InvalidChecksum),
+        );
+        assert_eq!(
+            decode("ecash:qpm2qsznhks23z7629mms6s4cwef74vcwvb87rkuu2"),
+            Err(CashAddrError::InvalidChar('b')),
+        );
+        assert_eq!(
+            decode("ecash:qpm2qsznhks23z7629mms6s4cwef74vcwva87rkuu1"),
+            Err(CashAddrError::InvalidChar('1')),
+        );
+        assert_eq!(
+            decode("ecash:qpm2qsznhks23z7629mms6s4cwef74vcwva87rkuuq"),
+            Err(CashAddrError::InvalidChecksum),
+        );
+        assert_eq!(decode("ecash:"), Err(CashAddrError::InvalidChecksum));
+        // Valid addresses, but type 2 and 256-bit hashes are not supported
+        assert_eq!(
+            decode("ecash:zpm2qsznhks23z7629mms6s4cwef74vcwv6ddac6re"),
+            Err(CashAddrError::UnsupportedType(2)),
+        );
+        assert_eq!(
+            decode(
+                "ecash:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq\
+                 a3t5e90w"
+            ),
+            Err(CashAddrError::InvalidHashSize {
+                expected: 20,
+                actual: 32,
+            }),
+        );
+        assert_eq!(
+            decode(
+                "ecash:qvqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq\
+                 wextky44"
+            ),
+            Err(CashAddrError::UnsupportedHashSize(32)),
+        );
+    }
+}
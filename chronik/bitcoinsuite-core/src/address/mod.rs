@@ -0,0 +1,11 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+//! Module for address formats, which encode output scripts as strings.
+
+mod base58;
+mod cashaddr;
+
+pub use self::base58::*;
+pub use self::cashaddr::*;
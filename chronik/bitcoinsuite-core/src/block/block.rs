@@ -0,0 +1,187 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+
+use crate::{
+    block::BlockHash,
+    error::DataError,
+    hash::{Hashed, Sha256d},
+    ser::{BitcoinSer, BitcoinSerializer},
+    tx::Tx,
+};
+
+/// Header of a block, which is hashed to get the block's [`BlockHash`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BlockHeader {
+    /// nVersion of the block.
+    pub version: i32,
+    /// Hash of the previous block.
+    pub prev_hash: BlockHash,
+    /// Merkle root of the txids of the block, see [`crate::hash::merkle_root`].
+    pub merkle_root: Sha256d,
+    /// Timestamp of the block.
+    pub timestamp: u32,
+    /// nBits of the block, the compact encoding of the target.
+    pub n_bits: u32,
+    /// Nonce of the block, to satisfy the proof-of-work.
+    pub nonce: u32,
+}
+
+/// Block, with header and txs, as serialized in the node's blk?????.dat
+/// files and the network protocol.
+///
+/// ```
+/// # use bitcoinsuite_core::{block::Block, error::DataError, ser::BitcoinSer};
+/// # use bytes::Bytes;
+/// let block = Block::default();
+/// let mut data = block.ser();
+/// assert_eq!(data.len(), 81);
+/// assert_eq!(Block::deser(&mut data)?, block);
+/// # Ok::<_, DataError>(())
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Block {
+    /// Header of the block.
+    pub header: BlockHeader,
+    /// Txs of the block, the first one is the coinbase tx.
+    pub txs: Vec<Tx>,
+}
+
+impl BlockHeader {
+    /// Size of a serialized block header in bytes.
+    pub const SIZE: usize = 80;
+
+    /// Hash of the block, i.e. the [`Sha256d`] of the serialized header.
+    pub fn hash(&self) -> BlockHash {
+        BlockHash::from(Sha256d::digest(self.ser()))
+    }
+}
+
+impl BitcoinSer for BlockHeader {
+    fn ser_to<S: BitcoinSerializer>(&self, bytes: &mut S) {
+        self.version.ser_to(bytes);
+        self.prev_hash.to_bytes().ser_to(bytes);
+        self.merkle_root.to_le_bytes().ser_to(bytes);
+        self.timestamp.ser_to(bytes);
+        self.n_bits.ser_to(bytes);
+        self.nonce.ser_to(bytes);
+    }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        Ok(BlockHeader {
+            version: BitcoinSer::deser(data)?,
+            prev_hash: BlockHash::from(<[u8; 32]>::deser(data)?),
+            merkle_root: Sha256d(BitcoinSer::deser(data)?),
+            timestamp: BitcoinSer::deser(data)?,
+            n_bits: BitcoinSer::deser(data)?,
+            nonce: BitcoinSer::deser(data)?,
+        })
+    }
+}
+
+impl BitcoinSer for Block {
+    fn ser_to<S: BitcoinSerializer>(&self, bytes: &mut S) {
+        self.header.ser_to(bytes);
+        self.txs.ser_to(bytes);
+    }
+
+    /// Deserialize the header, a CompactSize tx count and that many txs.
+    ///
+    /// The txids are computed from the consumed bytes, see [`Tx::deser`].
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        Ok(Block {
+            header: BitcoinSer::deser(data)?,
+            txs: BitcoinSer::deser(data)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec::Vec};
+
+    use bytes::Bytes;
+
+    use crate::{
+        block::{Block, BlockHash, BlockHeader},
+        error::DataError,
+        hash::{merkle_root, Hashed, Sha256d},
+        ser::BitcoinSer,
+        tx::TxId,
+    };
+
+    const GENESIS_BLOCK_HEX: &str =
+        "0100000000000000000000000000000000000000000000000000000000000000\
+         000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa\
+         4b1e5e4a29ab5f49ffff001d1dac2b7c01010000000100000000000000000000\
+         00000000000000000000000000000000000000000000ffffffff4d04ffff001d\
+         0104455468652054696d65732030332f4a616e2f32303039204368616e63656c\
+         6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f75742066\
+         6f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe554827\
+         1967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4\
+         f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+
+    #[test]
+    fn test_deser_genesis_block() -> Result<(), DataError> {
+        let raw_block = Bytes::from(
+            hex::decode(GENESIS_BLOCK_HEX).map_err(DataError::InvalidHex)?,
+        );
+        let mut data = raw_block.clone();
+        let block = Block::deser(&mut data)?;
+        assert!(data.is_empty());
+
+        let merkle_root = Sha256d::from_be_hex(
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+        )?;
+        assert_eq!(
+            block.header,
+            BlockHeader {
+                version: 1,
+                prev_hash: BlockHash::from([0; 32]),
+                merkle_root,
+                timestamp: 1231006505,
+                n_bits: 0x1d00ffff,
+                nonce: 2083236893,
+            },
+        );
+        assert_eq!(
+            block.header.hash().to_string(),
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+        );
+        assert_eq!(block.txs.len(), 1);
+        assert_eq!(block.txs[0].txid(), TxId::from(merkle_root));
+        let txids = block
+            .txs
+            .iter()
+            .map(|tx| Sha256d(tx.txid().to_bytes()))
+            .collect::<Vec<_>>();
+        assert_eq!(merkle_root(&txids), block.header.merkle_root);
+
+        // Roundtrip
+        assert_eq!(block.ser(), raw_block);
+        assert_eq!(block.header.ser_len(), BlockHeader::SIZE);
+
+        // Truncated blocks fail
+        let mut data = raw_block.slice(..BlockHeader::SIZE - 1);
+        assert_eq!(
+            Block::deser(&mut data),
+            Err(DataError::InvalidLength {
+                expected: 4,
+                actual: 3,
+            }),
+        );
+        let mut data = raw_block.slice(..raw_block.len() - 1);
+        assert_eq!(
+            Block::deser(&mut data),
+            Err(DataError::InvalidLength {
+                expected: 4,
+                actual: 3,
+            }),
+        );
+        Ok(())
+    }
+}
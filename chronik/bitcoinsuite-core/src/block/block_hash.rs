@@ -2,6 +2,8 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
+use alloc::vec::Vec;
+
 use crate::{
     error::DataError,
     hash::{Hashed, Sha256d},
@@ -13,14 +15,14 @@ use crate::{
 #[derive(Clone, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct BlockHash(Sha256d);
 
-impl std::fmt::Debug for BlockHash {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for BlockHash {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "BlockHash({})", self.0.hex_be())
     }
 }
 
-impl std::fmt::Display for BlockHash {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for BlockHash {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.0.hex_be().fmt(f)
     }
 }
@@ -57,7 +59,7 @@ impl BlockHash {
     }
 }
 
-impl std::str::FromStr for BlockHash {
+impl core::str::FromStr for BlockHash {
     type Err = DataError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -93,6 +95,8 @@ impl AsRef<[u8]> for BlockHash {
 
 #[cfg(test)]
 mod tests {
+    use alloc::{format, string::ToString};
+
     use hex_literal::hex;
 
     use crate::{
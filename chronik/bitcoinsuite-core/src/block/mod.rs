@@ -4,6 +4,9 @@
 
 //! Module for data refering to blocks, e.g. [`BlockHash`].
 
+#[allow(clippy::module_inception)]
+mod block;
 mod block_hash;
 
+pub use self::block::*;
 pub use self::block_hash::*;
@@ -4,15 +4,15 @@
 
 //! Module for errors in this crate.
 
-use thiserror::Error;
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Display, Formatter};
+
+use crate::script::opcode::Opcode;
 
 /// Errors indicating some data doesn't map to some object.
-#[derive(Debug, Error, PartialEq)]
+#[derive(Debug, PartialEq)]
 pub enum DataError {
     /// Expect a fixed length which was not met.
-    #[error(
-        "Invalid length, expected {expected} bytes but got {actual} bytes"
-    )]
     InvalidLength {
         /// Expected number of bytes.
         expected: usize,
@@ -21,9 +21,6 @@ pub enum DataError {
     },
 
     /// Expected bytes with multiple allowed lengths, none of which were met.
-    #[error(
-        "Invalid length, expected one of {expected:?} but got {actual} bytes"
-    )]
     InvalidLengthMulti {
         /// List of expected number of bytes.
         expected: Vec<usize>,
@@ -31,7 +28,147 @@ pub enum DataError {
         actual: usize,
     },
 
+    /// Data ended before a length prefix could be read in full.
+    UnexpectedEnd {
+        /// Number of bytes of the length prefix.
+        expected: usize,
+        /// Number of bytes that were remaining.
+        actual: usize,
+    },
+
     /// Hex contains invalid characters, odd length, etc.
-    #[error("Invalid hex: {0}")]
     InvalidHex(hex::FromHexError),
+
+    /// Encountered an opcode that isn't allowed in this context, e.g. a
+    /// non-push opcode in a script expected to only contain pushes.
+    UnexpectedOpcode(Opcode),
+
+    /// Compact size was encoded with more bytes than necessary.
+    NonCanonicalCompactSize(u64),
+
+    /// Encountered an opcode not defined by the node, see
+    /// [`crate::script::opcode::FIRST_UNDEFINED_OP_VALUE`].
+    InvalidOpcode(u8),
+
+    /// Outpoint string isn't of the form `<txid>:<out_idx>`.
+    InvalidOutPoint(String),
+
+    /// Token in a script's asm is neither an opcode nor a `<hex>` push.
+    InvalidAsmToken(String),
+
+    /// Script number is longer than allowed, see
+    /// [`crate::script::decode_script_num`].
+    ScriptNumTooLong {
+        /// Maximum number of bytes allowed.
+        max_size: usize,
+        /// Actual number of bytes.
+        actual: usize,
+    },
+
+    /// Script number is encoded with more bytes than necessary, e.g. has
+    /// trailing zeros or a negative zero.
+    NonMinimalScriptNum(Vec<u8>),
+
+    /// Network name isn't one of [`crate::network::Network::name`].
+    UnknownNetwork(String),
+
+    /// Public key starts with a byte that's not valid for its type, e.g.
+    /// 0x04 for a compressed pubkey.
+    InvalidPubKeyPrefix {
+        /// List of allowed prefix bytes.
+        expected: Vec<u8>,
+        /// Actual first byte.
+        actual: u8,
+    },
 }
+
+impl Display for DataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        use self::DataError::*;
+        match self {
+            InvalidLength { expected, actual } => write!(
+                f,
+                "Invalid length, expected {expected} bytes but got {actual} \
+                 bytes",
+            ),
+            InvalidLengthMulti { expected, actual } => write!(
+                f,
+                "Invalid length, expected one of {expected:?} but got \
+                 {actual} bytes",
+            ),
+            UnexpectedEnd { expected, actual } => write!(
+                f,
+                "Unexpected end of data, expected {expected} bytes for the \
+                 length prefix but got {actual} bytes",
+            ),
+            InvalidHex(err) => write!(f, "Invalid hex: {err}"),
+            UnexpectedOpcode(opcode) => write!(
+                f,
+                "Unexpected opcode {opcode} (0x{:02x})",
+                opcode.number(),
+            ),
+            NonCanonicalCompactSize(size) => {
+                write!(f, "Non-canonical compact size {size}")
+            }
+            InvalidOpcode(number) => write!(f, "Invalid opcode 0x{number:02x}"),
+            InvalidOutPoint(outpoint) => write!(
+                f,
+                "Invalid outpoint {outpoint:?}, expected <txid>:<out_idx>",
+            ),
+            InvalidAsmToken(token) => write!(f, "Invalid asm token {token:?}"),
+            ScriptNumTooLong { max_size, actual } => write!(
+                f,
+                "Script number too long, expected at most {max_size} bytes but \
+                 got {actual} bytes",
+            ),
+            NonMinimalScriptNum(data) => write!(
+                f,
+                "Non-minimally encoded script number {}",
+                hex::encode(data),
+            ),
+            UnknownNetwork(name) => write!(f, "Unknown network {name:?}"),
+            InvalidPubKeyPrefix { expected, actual } => {
+                write!(f, "Invalid pubkey prefix 0x{actual:02x}, expected")?;
+                for (idx, prefix) in expected.iter().enumerate() {
+                    let sep = if idx == 0 { " " } else { " or " };
+                    write!(f, "{sep}0x{prefix:02x}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DataError {}
+
+/// Errors when computing a Merkle root, see
+/// [`crate::hash::merkle_root_checked`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum MerkleError {
+    /// Two hashes paired in the tree are identical, so the same root can be
+    /// obtained from a different list of leaves (CVE-2012-2459).
+    DuplicatedHashes {
+        /// Level of the tree, 0 for the leaves.
+        level: usize,
+        /// Index of the first hash of the pair within the level.
+        index: usize,
+    },
+}
+
+impl Display for MerkleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        use self::MerkleError::*;
+        match self {
+            DuplicatedHashes { level, index } => write!(
+                f,
+                "Mutated Merkle tree, hashes {index} and {} at level {level} \
+                 are identical",
+                index + 1,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MerkleError {}
@@ -0,0 +1,214 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+//! Module for building BIP158-style compact block filters, see
+//! [`build_block_filter`].
+
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use bytes::BytesMut;
+
+use crate::{
+    block::BlockHash,
+    script::Script,
+    ser::{write_compact_size, BitcoinSerializer},
+};
+
+/// Golomb-Rice coding parameter P of basic BIP158 filters.
+pub const BASIC_FILTER_P: u8 = 19;
+
+/// Inverse false positive rate M of basic BIP158 filters.
+pub const BASIC_FILTER_M: u64 = 784931;
+
+/// Build a basic BIP158 compact block filter for the block with the given
+/// hash, containing the given scripts.
+///
+/// `scripts` should contain the output scripts of the block and the scripts
+/// of the outputs spent by it. Empty scripts and OP_RETURN scripts are
+/// excluded, duplicates are only added once.
+///
+/// The block hash is required, as the first 16 bytes of it (little-endian)
+/// are used as the SipHash key of the filter.
+///
+/// The result is serialized the same way as by the node, i.e. the number of
+/// items as compact size, followed by the Golomb-Rice coded set.
+///
+/// ```
+/// # use bitcoinsuite_core::{
+/// #     block::BlockHash, filter::build_block_filter, script::Script,
+/// # };
+/// // Testnet genesis block, see the BIP158 test vectors
+/// let block_hash: BlockHash =
+///     "000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943"
+///         .parse()
+///         .unwrap();
+/// let script = Script::from_hex(
+///     "4104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61de\
+///      b649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac",
+/// )
+/// .unwrap();
+/// let filter = build_block_filter(&block_hash, [&script].into_iter());
+/// assert_eq!(hex::encode(filter), "019dfca8");
+/// ```
+pub fn build_block_filter<'a>(
+    block_hash: &BlockHash,
+    scripts: impl Iterator<Item = &'a Script>,
+) -> Vec<u8> {
+    let items = scripts
+        .filter(|script| !script.bytecode().is_empty() && !script.is_opreturn())
+        .map(|script| script.bytecode().as_ref())
+        .collect::<BTreeSet<&[u8]>>();
+    let num_items = items.len() as u64;
+    let range = num_items * BASIC_FILTER_M;
+
+    let key = block_hash.to_bytes();
+    let k0 = u64::from_le_bytes(key[..8].try_into().unwrap());
+    let k1 = u64::from_le_bytes(key[8..16].try_into().unwrap());
+    let mut values = items
+        .into_iter()
+        .map(|item| map_into_range(siphash24(k0, k1, item), range))
+        .collect::<Vec<_>>();
+    values.sort_unstable();
+
+    let mut writer = BitWriter::default();
+    let mut last_value = 0;
+    for value in values {
+        let delta = value - last_value;
+        writer.write_golomb_rice(delta, BASIC_FILTER_P);
+        last_value = value;
+    }
+
+    let mut filter = BytesMut::new();
+    write_compact_size(&mut filter, num_items);
+    filter.put(&writer.bytes);
+    filter.to_vec()
+}
+
+/// Map a hash uniformly into `[0, range)`, as specified by BIP158.
+fn map_into_range(hash: u64, range: u64) -> u64 {
+    ((hash as u128 * range as u128) >> 64) as u64
+}
+
+/// Writes bits MSB first, padding the last byte with zeros.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    num_bits: usize,
+}
+
+impl BitWriter {
+    fn write_bit(&mut self, bit: bool) {
+        if self.num_bits % 8 == 0 {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 0x80 >> (self.num_bits % 8);
+        }
+        self.num_bits += 1;
+    }
+
+    fn write_golomb_rice(&mut self, value: u64, p: u8) {
+        // Quotient in unary, terminated by a 0 bit
+        for _ in 0..(value >> p) {
+            self.write_bit(true);
+        }
+        self.write_bit(false);
+        // Remainder as p-bit big-endian integer
+        for bit_idx in (0..p).rev() {
+            self.write_bit((value >> bit_idx) & 1 == 1);
+        }
+    }
+}
+
+/// SipHash-2-4 of `data`, keyed with (k0, k1).
+fn siphash24(k0: u64, k1: u64, data: &[u8]) -> u64 {
+    let mut v = [
+        k0 ^ 0x736f6d6570736575,
+        k1 ^ 0x646f72616e646f6d,
+        k0 ^ 0x6c7967656e657261,
+        k1 ^ 0x7465646279746573,
+    ];
+    fn sip_round(v: &mut [u64; 4]) {
+        v[0] = v[0].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(13) ^ v[0];
+        v[0] = v[0].rotate_left(32);
+        v[2] = v[2].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(16) ^ v[2];
+        v[0] = v[0].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(21) ^ v[0];
+        v[2] = v[2].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(17) ^ v[2];
+        v[2] = v[2].rotate_left(32);
+    }
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        let m = u64::from_le_bytes(chunk.try_into().unwrap());
+        v[3] ^= m;
+        sip_round(&mut v);
+        sip_round(&mut v);
+        v[0] ^= m;
+    }
+    let mut last = (data.len() as u64 & 0xff) << 56;
+    for (idx, &byte) in chunks.remainder().iter().enumerate() {
+        last |= (byte as u64) << (8 * idx);
+    }
+    v[3] ^= last;
+    sip_round(&mut v);
+    sip_round(&mut v);
+    v[0] ^= last;
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        block::BlockHash,
+        filter::{build_block_filter, siphash24},
+        hash::ShaRmd160,
+        script::Script,
+    };
+
+    #[test]
+    fn test_siphash24() {
+        // Test vector from the SipHash paper
+        let k0 = u64::from_le_bytes([0, 1, 2, 3, 4, 5, 6, 7]);
+        let k1 = u64::from_le_bytes([8, 9, 10, 11, 12, 13, 14, 15]);
+        let data = (0..15).collect::<Vec<u8>>();
+        assert_eq!(siphash24(k0, k1, &data), 0xa129ca6149be45e5);
+    }
+
+    #[test]
+    fn test_build_block_filter() {
+        let mut hash = [0; 32];
+        for (idx, byte) in hash.iter_mut().enumerate() {
+            *byte = idx as u8;
+        }
+        let block_hash = BlockHash::from(hash);
+        let p2pkh = Script::p2pkh(&ShaRmd160([7; 20]));
+        let p2sh = Script::p2sh(&ShaRmd160([8; 20]));
+        let empty = Script::default();
+        let opreturn = Script::new(vec![0x6a, 0x01, 0x02].into());
+
+        // Empty and OP_RETURN scripts are skipped, duplicates ignored
+        let scripts = [&p2pkh, &p2sh, &p2pkh, &empty, &opreturn];
+        assert_eq!(
+            hex::encode(build_block_filter(&block_hash, scripts.into_iter())),
+            "027e194b531c80",
+        );
+
+        // Filter without items
+        let scripts = [&empty, &opreturn];
+        assert_eq!(
+            build_block_filter(&block_hash, scripts.into_iter()),
+            vec![0],
+        );
+        assert_eq!(build_block_filter(&block_hash, [].into_iter()), vec![0]);
+    }
+}
@@ -5,9 +5,10 @@
 //! Module containing a [`Hashed`] trait to abstract over different hashes, plus
 //! implementations for a bunch of hashes.
 
-use std::{cmp::Ordering, fmt::Debug, hash::Hash};
+use alloc::{string::String, vec::Vec};
+use core::{cmp::Ordering, fmt::Debug, hash::Hash};
 
-use crate::error::DataError;
+use crate::error::{DataError, MerkleError};
 
 /// Trait for structs containing the result of a cryptographic hash function,
 /// like SHA-256, RIPEMD-160 etc. With this trait, we can abstractly write code
@@ -348,8 +349,8 @@ macro_rules! hash_algo {
         impl Debug for $ALGO_NAME {
             fn fmt(
                 &self,
-                fmt: &mut std::fmt::Formatter<'_>,
-            ) -> std::result::Result<(), std::fmt::Error> {
+                fmt: &mut core::fmt::Formatter<'_>,
+            ) -> core::result::Result<(), core::fmt::Error> {
                 write!(
                     fmt,
                     "{}(hex!({:?}))",
@@ -405,6 +406,185 @@ hash_algo! {
     pub struct Sha256d(pub [u8; 32]);
 }
 
+impl Sha256 {
+    /// Hash the given data using SHA-256.
+    ///
+    /// ```
+    /// # use bitcoinsuite_core::hash::{Hashed, Sha256};
+    /// assert_eq!(
+    ///     Sha256::digest(b"abc").hex_le(),
+    ///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    /// );
+    /// ```
+    pub fn digest(data: impl AsRef<[u8]>) -> Self {
+        use sha2::Digest;
+        Sha256(sha2::Sha256::digest(data.as_ref()).into())
+    }
+}
+
+impl Sha256d {
+    /// Hash the given data using SHA-256 twice, i.e. sha256(sha256(data)).
+    ///
+    /// ```
+    /// # use bitcoinsuite_core::hash::{Hashed, Sha256d};
+    /// assert_eq!(
+    ///     Sha256d::digest(b"abc").hex_le(),
+    ///     "4f8b42c22dd3729b519ba6f68d2da7cc5b2d606d05daed5ad5128cc03e6c6358",
+    /// );
+    /// ```
+    pub fn digest(data: impl AsRef<[u8]>) -> Self {
+        use sha2::Digest;
+        let hash = sha2::Sha256::digest(data.as_ref());
+        Sha256d(sha2::Sha256::digest(hash).into())
+    }
+
+    /// Start hashing data incrementally, e.g. to hash a large buffer in
+    /// chunks without having to concatenate it first.
+    ///
+    /// Gives the same hash as [`Sha256d::digest`] of all the data passed to
+    /// [`Sha256dBuilder::update`] concatenated.
+    /// ```
+    /// # use bitcoinsuite_core::hash::Sha256d;
+    /// let mut builder = Sha256d::builder();
+    /// builder.update(b"a");
+    /// builder.update(b"");
+    /// builder.update(b"bc");
+    /// assert_eq!(builder.finalize(), Sha256d::digest(b"abc"));
+    /// assert_eq!(Sha256d::builder().finalize(), Sha256d::digest(b""));
+    /// ```
+    pub fn builder() -> Sha256dBuilder {
+        Sha256dBuilder(sha2::Sha256::default())
+    }
+}
+
+/// Incremental hasher for [`Sha256d`], see [`Sha256d::builder`].
+#[derive(Clone, Default)]
+pub struct Sha256dBuilder(sha2::Sha256);
+
+impl Sha256dBuilder {
+    /// Append `data` to the data to be hashed.
+    pub fn update(&mut self, data: &[u8]) {
+        use sha2::Digest;
+        self.0.update(data);
+    }
+
+    /// Finish hashing, giving the [`Sha256d`] of all the data appended.
+    pub fn finalize(self) -> Sha256d {
+        use sha2::Digest;
+        Sha256d(sha2::Sha256::digest(self.0.finalize()).into())
+    }
+}
+
+impl Debug for Sha256dBuilder {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Sha256dBuilder").finish_non_exhaustive()
+    }
+}
+
+/// Merkle root of the given leaves, as computed by Bitcoin for the txids of a
+/// block (in block order).
+///
+/// Each level of the tree hashes pairs of hashes using [`Sha256d`]; if a level
+/// has an odd number of hashes, the last one is paired with itself. The root
+/// of a single leaf is the leaf itself, and the root of no leaves is all
+/// zeros.
+///
+/// Note: Because of the duplication, different lists of leaves can have the
+/// same root (CVE-2012-2459), so this alone can't be used to tell whether a
+/// block has been mutated, see [`merkle_root_checked`].
+///
+/// ```
+/// # use bitcoinsuite_core::hash::{merkle_root, Hashed, Sha256d};
+/// let a = Sha256d::digest(b"a");
+/// let b = Sha256d::digest(b"b");
+/// assert_eq!(merkle_root(&[]), Sha256d::default());
+/// assert_eq!(merkle_root(&[a]), a);
+/// let ab = Sha256d::digest([a.as_le_bytes(), b.as_le_bytes()].concat());
+/// assert_eq!(merkle_root(&[a, b]), ab);
+/// let bb = Sha256d::digest([b.as_le_bytes(), b.as_le_bytes()].concat());
+/// let ab_bb = Sha256d::digest([ab.0, bb.0].concat());
+/// assert_eq!(merkle_root(&[a, b, b]), ab_bb);
+/// assert_eq!(merkle_root(&[a, b, b, b]), ab_bb);
+/// ```
+pub fn merkle_root(leaves: &[Sha256d]) -> Sha256d {
+    let mut level = match leaves {
+        [] => return Sha256d::default(),
+        [leaf] => return *leaf,
+        _ => merkle_level(leaves),
+    };
+    while level.len() > 1 {
+        level = merkle_level(&level);
+    }
+    level[0]
+}
+
+/// Like [`merkle_root`], but fails if two hashes paired at any level of the
+/// tree are identical, like the node does when checking blocks.
+///
+/// Such a tree has the same root as the tree with the duplicated hashes
+/// removed (CVE-2012-2459), so e.g. a block with duplicated txs could pass as
+/// a valid block. A list of leaves that is valid never has identical pairs,
+/// as txids are unique.
+///
+/// ```
+/// # use bitcoinsuite_core::{
+/// #     error::MerkleError,
+/// #     hash::{merkle_root, merkle_root_checked, Sha256d},
+/// # };
+/// let [a, b, c] = [b"a", b"b", b"c"].map(Sha256d::digest);
+/// assert_eq!(merkle_root_checked(&[a, b, c]), Ok(merkle_root(&[a, b, c])));
+/// // Same root, but mutated
+/// assert_eq!(merkle_root(&[a, b, c, c]), merkle_root(&[a, b, c]));
+/// assert_eq!(
+///     merkle_root_checked(&[a, b, c, c]),
+///     Err(MerkleError::DuplicatedHashes { level: 0, index: 2 }),
+/// );
+/// ```
+pub fn merkle_root_checked(leaves: &[Sha256d]) -> Result<Sha256d, MerkleError> {
+    let mut level = match leaves {
+        [] => return Ok(Sha256d::default()),
+        [leaf] => return Ok(*leaf),
+        _ => {
+            check_merkle_level(leaves, 0)?;
+            merkle_level(leaves)
+        }
+    };
+    let mut depth = 1;
+    while level.len() > 1 {
+        check_merkle_level(&level, depth)?;
+        level = merkle_level(&level);
+        depth += 1;
+    }
+    Ok(level[0])
+}
+
+/// Fail if two hashes that would be paired in this level are identical.
+fn check_merkle_level(
+    hashes: &[Sha256d],
+    level: usize,
+) -> Result<(), MerkleError> {
+    let is_duplicate = |pair: &[Sha256d]| pair.len() == 2 && pair[0] == pair[1];
+    match hashes.chunks(2).position(is_duplicate) {
+        Some(pair_idx) => Err(MerkleError::DuplicatedHashes {
+            level,
+            index: pair_idx * 2,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Hash pairs of the given level, giving the next level of the Merkle tree.
+fn merkle_level(hashes: &[Sha256d]) -> Vec<Sha256d> {
+    hashes
+        .chunks(2)
+        .map(|pair| {
+            let left = &pair[0];
+            let right = pair.get(1).unwrap_or(left);
+            Sha256d::digest([left.0, right.0].concat())
+        })
+        .collect()
+}
+
 hash_algo! {
     /// Hash of the RIPEMD-160 algorithm as certified by CRYPTREC. See
     /// [`Hashed`].
@@ -420,14 +600,52 @@ hash_algo! {
     ///
     /// Bitcoin uses public keys hashed by SHA-256 followed by RIPEMD-160 to
     /// generate addresses.
+    ///
+    /// Endianness: The wrapped array is the raw digest, in exactly the order
+    /// it is embedded in P2PKH and P2SH scripts and in the payload of
+    /// addresses, which [`Hashed`] calls "little-endian". Unlike txids and
+    /// block hashes, these hashes are also *displayed* in that order, so use
+    /// [`Hashed::as_le_bytes`] and [`Hashed::hex_le`] for scripts, addresses
+    /// and explorers alike.
+    ///
+    /// [`Hashed::to_be_bytes`] and [`Hashed::hex_be`] return the reversed
+    /// bytes, which are only meaningful for protocols that explicitly store
+    /// this hash reversed; mixing the two up results in wrong addresses. There
+    /// is deliberately no `as_be_bytes`, as the reversed bytes don't exist in
+    /// memory (see [`Hashed`]); [`Hashed::from_be_bytes`] is the inverse of
+    /// [`Hashed::to_be_bytes`].
+    ///
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::{Hashed, ShaRmd160},
+    /// #     script::Script,
+    /// # };
+    /// # use hex_literal::hex;
+    /// let hash = ShaRmd160(hex!("00112233445566778899aabbccddeeff01234567"));
+    /// // Embedded in the script as-is
+    /// assert_eq!(
+    ///     Script::p2pkh(&hash).hex(),
+    ///     "76a91400112233445566778899aabbccddeeff0123456788ac",
+    /// );
+    /// assert_eq!(hash.hex_le(), "00112233445566778899aabbccddeeff01234567");
+    /// assert_eq!(hash.hex_be(), "67452301ffeeddccbbaa99887766554433221100");
+    /// ```
     pub struct ShaRmd160(pub [u8; 20]);
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::{format, vec::Vec};
+
     use hex_literal::hex;
 
-    use crate::hash::Ripemd160;
+    use crate::{
+        error::MerkleError,
+        hash::{
+            merkle_root, merkle_root_checked, Hashed, Ripemd160, Sha256d,
+            ShaRmd160,
+        },
+    };
 
     #[test]
     fn test_ord() {
@@ -444,4 +662,74 @@ mod tests {
             "Ripemd160(hex!(\"0123456789012345678901234567890123456789\"))",
         );
     }
+
+    #[test]
+    fn test_sha_rmd160_endianness_roundtrip() {
+        let arr = hex!("00112233445566778899aabbccddeeff01234567");
+        let hash = ShaRmd160(arr);
+        assert_eq!(hash.as_le_bytes(), &arr);
+        let arr_be = hash.to_be_bytes();
+        let mut arr_reversed = arr;
+        arr_reversed.reverse();
+        assert_eq!(arr_be, arr_reversed);
+        assert_eq!(ShaRmd160::from_be_bytes(arr_be), hash);
+        assert_eq!(ShaRmd160::from_le_bytes(*hash.as_le_bytes()), hash);
+        assert_eq!(ShaRmd160::from_be_hex(&hash.hex_be()).unwrap(), hash);
+        assert_eq!(ShaRmd160::from_le_hex(&hash.hex_le()).unwrap(), hash);
+    }
+
+    #[test]
+    fn test_merkle_root() {
+        let hash = |hex: &str| Sha256d::from_be_hex(hex).unwrap();
+        // Genesis block: Merkle root is the txid of the only tx
+        let genesis_txid = hash(
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+        );
+        assert_eq!(merkle_root(&[genesis_txid]), genesis_txid);
+        // Block 100000 on BTC
+        let txids = [
+            "8c14f0db3df150123e6f3dbbf30f8b955a8249b62ac1d1ff16284aefa3d06d87",
+            "fff2525b8931402dd09222c50775608f75787bd2b87e56995a7bdd30f79702c4",
+            "6359f0868171b1d194cbee1af2f16ea598ae8fad666d9b012c8ed2b79a236ec4",
+            "e9a66845e05d5abc0ad04ec80f774a7e585c6e8db975962d069a522137b80c1d",
+        ]
+        .map(hash);
+        let expected_root = hash(
+            "f3e94742aca4b5ef85488dc37c06c3282295ffec960994b2c0d5ac2a25a95766",
+        );
+        assert_eq!(merkle_root(&txids), expected_root);
+        // Odd number of leaves: last one is paired with itself
+        assert_eq!(
+            merkle_root(&txids[..3]),
+            merkle_root(&[txids[0], txids[1], txids[2], txids[2]]),
+        );
+    }
+
+    #[test]
+    fn test_merkle_root_checked() {
+        let leaves = (0..8u8).map(|i| Sha256d::digest([i])).collect::<Vec<_>>();
+        for num_leaves in 0..=leaves.len() {
+            let leaves = &leaves[..num_leaves];
+            assert_eq!(merkle_root_checked(leaves), Ok(merkle_root(leaves)));
+        }
+        // Duplicating the last pair of 6 leaves gives the same root, but the
+        // duplicated pair is detected one level up
+        let mut mutated = leaves[..6].to_vec();
+        mutated.extend_from_slice(&leaves[4..6]);
+        assert_eq!(merkle_root(&mutated), merkle_root(&leaves[..6]));
+        assert_eq!(
+            merkle_root_checked(&mutated),
+            Err(MerkleError::DuplicatedHashes { level: 1, index: 2 }),
+        );
+        // Identical leaves anywhere are detected, if they are paired
+        let [a, b] = [leaves[0], leaves[1]];
+        assert_eq!(
+            merkle_root_checked(&[a, a, b]),
+            Err(MerkleError::DuplicatedHashes { level: 0, index: 0 }),
+        );
+        assert_eq!(
+            merkle_root_checked(&[a, b, b]),
+            Ok(merkle_root(&[a, b, b])),
+        );
+    }
 }
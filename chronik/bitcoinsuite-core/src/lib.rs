@@ -6,12 +6,29 @@
 //!
 //! Note: This is a general purpose library, but has been optimized for the
 //! usage in Chronik, an indexer for Bitcoin ABC.
+//!
+//! The `std` feature is enabled by default; without it, the crate builds as
+//! `no_std` (but still requires `alloc`), e.g. for use in wasm.
+//!
+//! This crate doesn't depend on the node: it has no dependency on
+//! `chronik-bridge` or `cxx`, so wallets and other pure-Rust tools can use its
+//! script, serialization and hash types without building any C++.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+// Some tests need std, e.g. for HashSet, also when testing without it
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
 
 abc_rust_lint::lint! {
+    pub mod address;
     pub mod block;
     pub mod bytes;
     pub mod error;
+    pub mod filter;
     pub mod hash;
+    pub mod network;
     pub mod script;
     pub mod ser;
     pub mod tx;
@@ -0,0 +1,120 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+//! Module for [`Network`], with the parameters that differ between networks,
+//! e.g. address prefixes.
+
+use alloc::string::ToString;
+use core::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use crate::error::DataError;
+
+/// Network the node is running on, e.g. as selected with `-chain`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Network {
+    /// The main eCash network.
+    Mainnet,
+    /// The public test network.
+    Testnet,
+    /// Local regression test network.
+    Regtest,
+}
+
+impl Network {
+    /// All supported networks.
+    pub const ALL: [Network; 3] =
+        [Network::Mainnet, Network::Testnet, Network::Regtest];
+
+    /// Name of the network, as used by the node, e.g. for `-chain`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Network::Mainnet => "main",
+            Network::Testnet => "test",
+            Network::Regtest => "regtest",
+        }
+    }
+
+    /// Parse the network from the name used by the node, see
+    /// [`Network::name`].
+    /// ```
+    /// # use bitcoinsuite_core::network::Network;
+    /// assert_eq!(Network::from_name("regtest"), Some(Network::Regtest));
+    /// assert_eq!(Network::from_name("mainnet"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Network> {
+        Network::ALL
+            .into_iter()
+            .find(|network| network.name() == name)
+    }
+
+    /// Prefix of CashAddr addresses on this network.
+    pub fn cashaddr_prefix(self) -> &'static str {
+        match self {
+            Network::Mainnet => "ecash",
+            Network::Testnet => "ectest",
+            Network::Regtest => "ecregtest",
+        }
+    }
+
+    /// Version byte of legacy Base58Check P2PKH addresses on this network.
+    pub fn p2pkh_version(self) -> u8 {
+        match self {
+            Network::Mainnet => 0,
+            Network::Testnet | Network::Regtest => 111,
+        }
+    }
+
+    /// Version byte of legacy Base58Check P2SH addresses on this network.
+    pub fn p2sh_version(self) -> u8 {
+        match self {
+            Network::Mainnet => 5,
+            Network::Testnet | Network::Regtest => 196,
+        }
+    }
+
+    /// Magic bytes at the start of P2P messages on this network.
+    pub fn magic(self) -> [u8; 4] {
+        match self {
+            Network::Mainnet => [0xe3, 0xe1, 0xf3, 0xe8],
+            Network::Testnet => [0xf4, 0xe5, 0xf3, 0xf4],
+            Network::Regtest => [0xda, 0xb5, 0xbf, 0xfa],
+        }
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Network {
+    type Err = DataError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Network::from_name(s)
+            .ok_or_else(|| DataError::UnknownNetwork(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{error::DataError, network::Network};
+
+    #[test]
+    fn test_network_names() {
+        for network in Network::ALL {
+            assert_eq!(network.to_string().parse(), Ok(network));
+        }
+        assert_eq!(
+            "ecash".parse::<Network>(),
+            Err(DataError::UnknownNetwork("ecash".to_string())),
+        );
+    }
+}
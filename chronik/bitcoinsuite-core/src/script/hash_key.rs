@@ -0,0 +1,125 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use crate::script::Script;
+
+/// [`Script`] with a precomputed 64-bit hash, to be used as the key of hash
+/// maps and sets.
+///
+/// Hashing a [`Script`] hashes its entire bytecode, which is wasteful if the
+/// same script is looked up repeatedly. [`ScriptHashKey`] hashes the bytecode
+/// once on construction and only feeds the cached 64-bit value to hashers.
+///
+/// The hash is computed using a [`BuildHasher`], which should be the hasher
+/// of the map the key is used in, e.g. `HashMap::hasher`. This keeps the hash
+/// keyed (e.g. with `RandomState`), so attacker-chosen scripts can't be used
+/// to provoke collisions.
+///
+/// Keys built with different [`BuildHasher`]s compare unequal, even for the
+/// same script, so all keys of a map have to be built with the same one.
+/// ```
+/// # use std::collections::HashMap;
+/// # use bitcoinsuite_core::{
+/// #     hash::ShaRmd160,
+/// #     script::{Script, ScriptHashKey},
+/// # };
+/// let mut balances = HashMap::new();
+/// let script = Script::p2pkh(&ShaRmd160([1; 20]));
+/// let key = ScriptHashKey::new(script.clone(), balances.hasher());
+/// balances.insert(key, 1000);
+///
+/// let key = ScriptHashKey::new(script.clone(), balances.hasher());
+/// *balances.get_mut(&key).unwrap() += 500;
+/// assert_eq!(balances[&key], 1500);
+/// assert_eq!(key.script(), &script);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ScriptHashKey {
+    hash: u64,
+    script: Script,
+}
+
+impl ScriptHashKey {
+    /// Hash the bytecode of `script` using `build_hasher` and cache the
+    /// result.
+    pub fn new(script: Script, build_hasher: &impl BuildHasher) -> Self {
+        let mut hasher = build_hasher.build_hasher();
+        script.hash(&mut hasher);
+        ScriptHashKey {
+            hash: hasher.finish(),
+            script,
+        }
+    }
+
+    /// The precomputed 64-bit hash of the script.
+    pub fn precomputed_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// The wrapped script.
+    pub fn script(&self) -> &Script {
+        &self.script
+    }
+
+    /// Unwrap the key, returning the wrapped script.
+    pub fn into_script(self) -> Script {
+        self.script
+    }
+}
+
+impl Hash for ScriptHashKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl PartialEq for ScriptHashKey {
+    fn eq(&self, other: &Self) -> bool {
+        // Comparing the hashes first makes most mismatches cheap
+        self.hash == other.hash && self.script == other.script
+    }
+}
+
+impl Eq for ScriptHashKey {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use std::{
+        collections::{hash_map::RandomState, HashSet},
+        hash::{BuildHasher, Hash, Hasher},
+    };
+
+    use crate::script::{Script, ScriptHashKey};
+
+    #[test]
+    fn test_script_hash_key() {
+        let state = RandomState::new();
+        let script_a = Script::new(vec![0x51, 0x52].into());
+        let script_b = Script::new(vec![0x53].into());
+        let a1 = ScriptHashKey::new(script_a.clone(), &state);
+        let a2 = ScriptHashKey::new(script_a.clone(), &state);
+        let b = ScriptHashKey::new(script_b.clone(), &state);
+
+        // Cached hash is the hash of the script
+        let mut hasher = state.build_hasher();
+        script_a.hash(&mut hasher);
+        assert_eq!(a1.precomputed_hash(), hasher.finish());
+
+        // Only the cached hash is fed to the hasher
+        let mut key_hasher = state.build_hasher();
+        a1.hash(&mut key_hasher);
+        let mut u64_hasher = state.build_hasher();
+        u64_hasher.write_u64(a1.precomputed_hash());
+        assert_eq!(key_hasher.finish(), u64_hasher.finish());
+
+        assert_eq!(a1, a2);
+        assert_ne!(a1, b);
+        let set = [a1, b].into_iter().collect::<HashSet<_>>();
+        assert!(set.contains(&a2));
+        assert_eq!(a2.into_script(), script_a);
+    }
+}
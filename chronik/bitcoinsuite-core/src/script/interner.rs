@@ -0,0 +1,87 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+use alloc::collections::BTreeSet;
+
+use crate::script::Script;
+
+/// Pool that deduplicates [`Script`]s, so identical scripts share the same
+/// [`bytes::Bytes`] allocation.
+///
+/// Useful when holding many scripts that are likely the same, e.g. outputs
+/// paying to the same address within a block.
+/// ```
+/// # use bitcoinsuite_core::{
+/// #     hash::ShaRmd160,
+/// #     script::{Script, ScriptInterner},
+/// # };
+/// let mut interner = ScriptInterner::default();
+/// let first = interner.intern(Script::p2pkh(&ShaRmd160([1; 20])));
+/// let second = interner.intern(Script::p2pkh(&ShaRmd160([1; 20])));
+/// assert_eq!(first, second);
+/// assert!(first.shares_allocation_with(&second));
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ScriptInterner {
+    scripts: BTreeSet<Script>,
+}
+
+impl ScriptInterner {
+    /// Return a script equal to `script`, sharing its allocation with all
+    /// previously interned equal scripts. The first script interned for a
+    /// given bytecode is the one that will be shared.
+    pub fn intern(&mut self, script: Script) -> Script {
+        if let Some(interned) = self.scripts.get(&script) {
+            return interned.clone();
+        }
+        self.scripts.insert(script.clone());
+        script
+    }
+
+    /// Number of distinct scripts in the pool.
+    pub fn len(&self) -> usize {
+        self.scripts.len()
+    }
+
+    /// Whether no script has been interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Remove all scripts from the pool, releasing its references to them.
+    pub fn clear(&mut self) {
+        self.scripts.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use bytes::Bytes;
+
+    use crate::script::{Script, ScriptInterner};
+
+    #[test]
+    fn test_script_interner() {
+        let mut interner = ScriptInterner::default();
+        assert!(interner.is_empty());
+
+        let buffer = Bytes::from(vec![0x51, 0x52, 0x51, 0x52]);
+        let a1 = interner.intern(Script::new(buffer.slice(..2)));
+        let a2 = interner.intern(Script::new(buffer.slice(2..)));
+        let b = interner.intern(Script::new(vec![0x53].into()));
+        assert_eq!(a1, a2);
+        assert!(a1.shares_allocation_with(&a2));
+        assert_eq!(a2.bytecode().as_ptr(), buffer.as_ptr());
+        assert_ne!(a1, b);
+        assert_eq!(interner.len(), 2);
+
+        interner.clear();
+        assert!(interner.is_empty());
+        let a3 = interner.intern(Script::new(buffer.slice(2..)));
+        assert!(!a1.shares_allocation_with(&a3));
+    }
+}
@@ -4,7 +4,10 @@
 
 use bytes::Bytes;
 
-use crate::{error::DataError, script::Op};
+use crate::{
+    error::DataError,
+    script::{Op, OpRef},
+};
 
 /// Iterate over the [`Op`]s in a Script.
 ///
@@ -41,3 +44,100 @@ impl Iterator for ScriptOpIter {
         }
     }
 }
+
+/// Iterate over the [`OpRef`]s in a Script, borrowing the pushed data.
+///
+/// Stops after the first [`Err`], just like [`ScriptOpIter`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ScriptOpRefIter<'a> {
+    remaining_bytecode: &'a [u8],
+}
+
+impl<'a> ScriptOpRefIter<'a> {
+    pub(crate) fn new(bytecode: &'a [u8]) -> Self {
+        ScriptOpRefIter {
+            remaining_bytecode: bytecode,
+        }
+    }
+}
+
+impl<'a> Iterator for ScriptOpRefIter<'a> {
+    type Item = Result<OpRef<'a>, DataError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_bytecode.is_empty() {
+            return None;
+        }
+        match OpRef::read_op(&mut self.remaining_bytecode) {
+            Ok(op) => Some(Ok(op)),
+            Err(err) => {
+                // Stop iteration by dropping the remaining bytecode
+                self.remaining_bytecode = &[];
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Iterate over the [`Op`]s in a Script, failing on undefined opcodes.
+///
+/// Like [`ScriptOpIter`], but yields [`DataError::InvalidOpcode`] for opcodes
+/// that aren't defined by the node (see
+/// [`crate::script::opcode::Opcode::is_defined`]), and then
+/// stops yielding items.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ScriptOpStrictIter {
+    iter: ScriptOpIter,
+}
+
+impl ScriptOpStrictIter {
+    pub(crate) fn new(bytecode: Bytes) -> Self {
+        ScriptOpStrictIter {
+            iter: ScriptOpIter::new(bytecode),
+        }
+    }
+}
+
+impl Iterator for ScriptOpStrictIter {
+    type Item = Result<Op, DataError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next()? {
+            Ok(Op::Code(opcode)) if !opcode.is_defined() => {
+                // Stop iteration by truncating the remaining bytecode
+                self.iter.remaining_bytecode.truncate(0);
+                Some(Err(DataError::InvalidOpcode(opcode.number())))
+            }
+            result => Some(result),
+        }
+    }
+}
+
+/// Iterate over the [`Op`]s in a Script, together with the byte offset at
+/// which each op starts in the bytecode.
+///
+/// Stops after the first [`Err`], just like [`ScriptOpIter`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ScriptOpIndexedIter {
+    iter: ScriptOpIter,
+    bytecode_len: usize,
+}
+
+impl ScriptOpIndexedIter {
+    pub(crate) fn new(bytecode: Bytes) -> Self {
+        ScriptOpIndexedIter {
+            bytecode_len: bytecode.len(),
+            iter: ScriptOpIter::new(bytecode),
+        }
+    }
+}
+
+impl Iterator for ScriptOpIndexedIter {
+    type Item = (usize, Result<Op, DataError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.bytecode_len - self.iter.remaining_bytecode.len();
+        let op = self.iter.next()?;
+        Some((offset, op))
+    }
+}
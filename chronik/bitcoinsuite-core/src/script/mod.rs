@@ -4,7 +4,10 @@
 
 //! Module for structs and definitions regarding Script.
 
+mod hash_key;
+mod interner;
 mod iter;
+mod num;
 mod op;
 pub mod opcode;
 mod pubkey;
@@ -15,7 +18,10 @@ mod script_mut;
 mod uncompressed_pubkey;
 mod variant;
 
+pub use self::hash_key::*;
+pub use self::interner::*;
 pub use self::iter::*;
+pub use self::num::*;
 pub use self::op::*;
 pub use self::pubkey::*;
 pub use self::pubkey_variant::*;
@@ -0,0 +1,177 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+use alloc::vec::Vec;
+
+use crate::error::DataError;
+
+/// Maximum number of bytes of script numbers used as operands of arithmetic
+/// opcodes, e.g. OP_ADD.
+pub const MAX_SCRIPT_NUM_SIZE: usize = 4;
+
+/// Largest `max_size` supported by [`decode_script_num_max_size`], as the
+/// result has to fit an [`i64`].
+pub const MAX_SCRIPT_NUM_SIZE_I64: usize = 8;
+
+/// Decode a number pushed in a script, encoded little-endian in
+/// sign-magnitude, i.e. the highest bit of the last byte is the sign bit.
+///
+/// The encoding must be minimal, and at most [`MAX_SCRIPT_NUM_SIZE`] bytes
+/// long, like the node requires for operands of arithmetic opcodes.
+/// ```
+/// # use bitcoinsuite_core::{error::DataError, script::decode_script_num};
+/// assert_eq!(decode_script_num(&[]), Ok(0));
+/// assert_eq!(decode_script_num(&[0x81]), Ok(-1));
+/// assert_eq!(decode_script_num(&[0xff, 0x00]), Ok(255));
+/// assert_eq!(
+///     decode_script_num(&[0x01, 0x00]),
+///     Err(DataError::NonMinimalScriptNum(vec![0x01, 0x00])),
+/// );
+/// ```
+pub fn decode_script_num(data: &[u8]) -> Result<i64, DataError> {
+    decode_script_num_max_size(data, MAX_SCRIPT_NUM_SIZE)
+}
+
+/// Like [`decode_script_num`], but allow numbers up to `max_size` bytes, e.g.
+/// 5 for the locktime of OP_CHECKLOCKTIMEVERIFY.
+///
+/// `max_size` is capped at [`MAX_SCRIPT_NUM_SIZE_I64`].
+pub fn decode_script_num_max_size(
+    data: &[u8],
+    max_size: usize,
+) -> Result<i64, DataError> {
+    let max_size = max_size.min(MAX_SCRIPT_NUM_SIZE_I64);
+    if data.len() > max_size {
+        return Err(DataError::ScriptNumTooLong {
+            max_size,
+            actual: data.len(),
+        });
+    }
+    let (&last, rest) = match data.split_last() {
+        Some(split) => split,
+        None => return Ok(0),
+    };
+    // If the only bits set in the last byte are (at most) the sign bit, the
+    // number is non-minimal, unless the sign bit is needed because the
+    // previous byte already has its highest bit set.
+    if last & 0x7f == 0 && rest.last().map_or(true, |&byte| byte & 0x80 == 0) {
+        return Err(DataError::NonMinimalScriptNum(data.to_vec()));
+    }
+    let mut magnitude = 0u64;
+    for (idx, &byte) in data.iter().enumerate() {
+        magnitude |= u64::from(byte) << (8 * idx);
+    }
+    let sign_bit = 0x80u64 << (8 * (data.len() - 1));
+    if last & 0x80 != 0 {
+        Ok(-((magnitude & !sign_bit) as i64))
+    } else {
+        Ok(magnitude as i64)
+    }
+}
+
+/// Encode a number minimally as script number, the inverse of
+/// [`decode_script_num`]. 0 encodes to an empty byte string.
+/// ```
+/// # use bitcoinsuite_core::script::encode_script_num;
+/// assert_eq!(encode_script_num(0), vec![]);
+/// assert_eq!(encode_script_num(-1), vec![0x81]);
+/// assert_eq!(encode_script_num(255), vec![0xff, 0x00]);
+/// assert_eq!(encode_script_num(-255), vec![0xff, 0x80]);
+/// ```
+pub fn encode_script_num(num: i64) -> Vec<u8> {
+    let mut magnitude = num.unsigned_abs();
+    let mut data = Vec::new();
+    while magnitude > 0 {
+        data.push(magnitude as u8);
+        magnitude >>= 8;
+    }
+    let sign = if num < 0 { 0x80 } else { 0x00 };
+    match data.last_mut() {
+        None => {}
+        Some(last) if *last & 0x80 != 0 => data.push(sign),
+        Some(last) => *last |= sign,
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::{
+        error::DataError,
+        script::{
+            decode_script_num, decode_script_num_max_size, encode_script_num,
+        },
+    };
+
+    #[test]
+    fn test_script_num_roundtrip() {
+        for num in [
+            0,
+            1,
+            -1,
+            16,
+            127,
+            -127,
+            128,
+            -128,
+            255,
+            256,
+            0x7fff,
+            -0x8000,
+            0x7fff_ffff,
+            -0x7fff_ffff,
+            0x8000_0000,
+            i64::MAX,
+            -i64::MAX,
+        ] {
+            let data = encode_script_num(num);
+            assert_eq!(decode_script_num_max_size(&data, 8), Ok(num));
+        }
+        assert_eq!(encode_script_num(128), vec![0x80, 0x00]);
+        assert_eq!(encode_script_num(-128), vec![0x80, 0x80]);
+        assert_eq!(encode_script_num(0x8000_0000), vec![0, 0, 0, 0x80, 0]);
+        // Magnitude of i64::MIN needs the 9th byte for the sign
+        assert_eq!(encode_script_num(i64::MIN).len(), 9);
+    }
+
+    #[test]
+    fn test_decode_script_num_errors() {
+        for data in [
+            [0x00].as_ref(),
+            &[0x80],
+            &[0x01, 0x00],
+            &[0x01, 0x80],
+            &[0x7f, 0x00],
+        ] {
+            assert_eq!(
+                decode_script_num(data),
+                Err(DataError::NonMinimalScriptNum(data.to_vec())),
+            );
+        }
+        assert_eq!(
+            decode_script_num(&[0xff, 0xff, 0xff, 0x7f]),
+            Ok(i32::MAX.into()),
+        );
+        assert_eq!(
+            decode_script_num(&[0, 0, 0, 0x80, 0]),
+            Err(DataError::ScriptNumTooLong {
+                max_size: 4,
+                actual: 5,
+            }),
+        );
+        assert_eq!(
+            decode_script_num_max_size(&[0, 0, 0, 0x80, 0], 5),
+            Ok(0x8000_0000),
+        );
+        assert_eq!(
+            decode_script_num_max_size(&[1; 9], 100),
+            Err(DataError::ScriptNumTooLong {
+                max_size: 8,
+                actual: 9,
+            }),
+        );
+    }
+}
@@ -7,7 +7,8 @@ use bytes::Bytes;
 use crate::{
     bytes::{read_array, read_bytes},
     error::DataError,
-    script::opcode::*,
+    script::{decode_script_num, opcode::*},
+    ser::{BitcoinSer, BitcoinSerializer},
 };
 
 /// An operation in a script.
@@ -23,6 +24,45 @@ pub enum Op {
 impl Op {
     /// Read the next [`Op`] in the script bytecode, including the
     /// payload for [`Op::Push`] opcodes.
+    ///
+    /// If the declared push size exceeds the remaining bytecode, this returns
+    /// [`DataError::InvalidLength`]:
+    /// ```
+    /// # use bitcoinsuite_core::{error::DataError, script::Op};
+    /// # use hex_literal::hex;
+    /// let mut data = hex!("4d0300abcd").to_vec().into();
+    /// assert_eq!(
+    ///     Op::read_op(&mut data),
+    ///     Err(DataError::InvalidLength {
+    ///         expected: 3,
+    ///         actual: 2,
+    ///     }),
+    /// );
+    /// ```
+    ///
+    /// If the bytecode ends before the length prefix of an `OP_PUSHDATAn`
+    /// could be read, this returns [`DataError::UnexpectedEnd`]:
+    /// ```
+    /// # use bitcoinsuite_core::{error::DataError, script::Op};
+    /// # use hex_literal::hex;
+    /// let mut data = hex!("4d03").to_vec().into();
+    /// assert_eq!(
+    ///     Op::read_op(&mut data),
+    ///     Err(DataError::UnexpectedEnd {
+    ///         expected: 2,
+    ///         actual: 1,
+    ///     }),
+    /// );
+    ///
+    /// let mut data = hex!("4c").to_vec().into();
+    /// assert_eq!(
+    ///     Op::read_op(&mut data),
+    ///     Err(DataError::UnexpectedEnd {
+    ///         expected: 1,
+    ///         actual: 0,
+    ///     }),
+    /// );
+    /// ```
     pub fn read_op(data: &mut Bytes) -> Result<Op, DataError> {
         let opcode_num = read_bytes(data, 1)?[0];
         Ok(match Opcode(opcode_num) {
@@ -30,18 +70,326 @@ impl Op {
                 Op::Push(opcode, read_bytes(data, opcode_num as usize)?)
             }
             opcode @ OP_PUSHDATA1 => {
-                let size = read_bytes(data, 1)?[0] as usize;
-                Op::Push(opcode, read_bytes(data, size)?)
+                let size = u8::from_le_bytes(read_push_size(data)?);
+                Op::Push(opcode, read_bytes(data, size as usize)?)
             }
             opcode @ OP_PUSHDATA2 => {
-                let size = u16::from_le_bytes(read_array(data)?);
+                let size = u16::from_le_bytes(read_push_size(data)?);
                 Op::Push(opcode, read_bytes(data, size as usize)?)
             }
             opcode @ OP_PUSHDATA4 => {
-                let size = u32::from_le_bytes(read_array(data)?);
+                // The declared size can be close to u32::MAX, but read_bytes
+                // checks it against the remaining bytecode before slicing, so
+                // a bogus size never results in an allocation.
+                let size = u32::from_le_bytes(read_push_size(data)?);
                 Op::Push(opcode, read_bytes(data, size as usize)?)
             }
             otherwise => Op::Code(otherwise),
         })
     }
+
+    /// Opcode of this op, ignoring any pushed data.
+    /// ```
+    /// # use bitcoinsuite_core::script::{opcode::*, Op};
+    /// assert_eq!(Op::Code(OP_DUP).opcode(), OP_DUP);
+    /// assert_eq!(Op::Push(Opcode(2), vec![1, 2].into()).opcode(), Opcode(2));
+    /// ```
+    pub fn opcode(&self) -> Opcode {
+        match *self {
+            Op::Code(opcode) | Op::Push(opcode, _) => opcode,
+        }
+    }
+
+    /// Number pushed by this op, if it pushes one:
+    /// - [`OP_0`] pushes 0, [`OP_1NEGATE`] -1 and [`OP_1`] to [`OP_16`] 1 to
+    ///   16.
+    /// - Data pushes are decoded using [`decode_script_num`], i.e. they have
+    ///   to be minimally encoded and at most 4 bytes long, like operands of
+    ///   arithmetic opcodes.
+    ///
+    /// Any other opcode returns [`None`].
+    /// ```
+    /// # use bitcoinsuite_core::script::{opcode::*, Op};
+    /// assert_eq!(Op::Code(OP_0).as_number(), Some(0));
+    /// assert_eq!(Op::Code(OP_1NEGATE).as_number(), Some(-1));
+    /// assert_eq!(Op::Code(OP_16).as_number(), Some(16));
+    /// assert_eq!(Op::Code(OP_DUP).as_number(), None);
+    /// let push = Op::Push(Opcode(1), vec![0x11].into());
+    /// assert_eq!(push.as_number(), Some(17));
+    /// assert_eq!(
+    ///     Op::Push(Opcode(2), vec![0xff, 0x80].into()).as_number(),
+    ///     Some(-255),
+    /// );
+    /// // Not minimally encoded
+    /// assert_eq!(Op::Push(Opcode(2), vec![1, 0].into()).as_number(), None);
+    /// // Too long
+    /// assert_eq!(Op::Push(Opcode(5), vec![1; 5].into()).as_number(), None);
+    /// ```
+    pub fn as_number(&self) -> Option<i64> {
+        match self {
+            Op::Code(OP_0) => Some(0),
+            Op::Code(OP_1NEGATE) => Some(-1),
+            Op::Code(opcode) => match opcode.number() {
+                number @ OP_1::N..=OP_16::N => {
+                    Some(i64::from(number - OP_1::N) + 1)
+                }
+                _ => None,
+            },
+            Op::Push(_, data) => decode_script_num(data).ok(),
+        }
+    }
+}
+
+/// An operation in a script, borrowing the pushed data from the bytecode.
+///
+/// Like [`Op`], but reading it from a `&[u8]` doesn't touch the ref count of
+/// the script's [`Bytes`], e.g. for hot loops over many scripts.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum OpRef<'a> {
+    /// Opcode that stands by itself, with no data following it, see
+    /// [`Op::Code`].
+    Code(Opcode),
+    /// Opcode that pushes the bytes following it onto the stack, see
+    /// [`Op::Push`].
+    Push(Opcode, &'a [u8]),
+}
+
+impl<'a> OpRef<'a> {
+    /// Read the next [`OpRef`] in the script bytecode, advancing `data` past
+    /// it. Fails with the same errors as [`Op::read_op`], in which case `data`
+    /// is left unchanged.
+    /// ```
+    /// # use bitcoinsuite_core::script::{opcode::*, OpRef};
+    /// # use hex_literal::hex;
+    /// let bytecode = hex!("4c0201026a");
+    /// let mut data = bytecode.as_ref();
+    /// assert_eq!(
+    ///     OpRef::read_op(&mut data),
+    ///     Ok(OpRef::Push(OP_PUSHDATA1, &[1, 2])),
+    /// );
+    /// assert_eq!(OpRef::read_op(&mut data), Ok(OpRef::Code(OP_RETURN)));
+    /// assert!(data.is_empty());
+    /// ```
+    pub fn read_op(data: &mut &'a [u8]) -> Result<OpRef<'a>, DataError> {
+        let (&opcode_num, rest) =
+            data.split_first().ok_or(DataError::InvalidLength {
+                expected: 1,
+                actual: 0,
+            })?;
+        let opcode = Opcode(opcode_num);
+        let prefix_size = match opcode {
+            Opcode(0x01..=0x4b) => 0,
+            OP_PUSHDATA1 => 1,
+            OP_PUSHDATA2 => 2,
+            OP_PUSHDATA4 => 4,
+            _ => {
+                *data = rest;
+                return Ok(OpRef::Code(opcode));
+            }
+        };
+        if rest.len() < prefix_size {
+            return Err(DataError::UnexpectedEnd {
+                expected: prefix_size,
+                actual: rest.len(),
+            });
+        }
+        let (size_prefix, rest) = rest.split_at(prefix_size);
+        let size = match prefix_size {
+            0 => opcode_num as usize,
+            // Little-endian size prefix of OP_PUSHDATAn
+            _ => size_prefix
+                .iter()
+                .rev()
+                .fold(0, |size, &byte| (size << 8) | byte as usize),
+        };
+        if rest.len() < size {
+            return Err(DataError::InvalidLength {
+                expected: size,
+                actual: rest.len(),
+            });
+        }
+        let (pushed, rest) = rest.split_at(size);
+        *data = rest;
+        Ok(OpRef::Push(opcode, pushed))
+    }
+
+    /// Opcode of this op, ignoring any pushed data.
+    pub fn opcode(&self) -> Opcode {
+        match *self {
+            OpRef::Code(opcode) | OpRef::Push(opcode, _) => opcode,
+        }
+    }
+
+    /// Bytes this op pushes onto the stack, or [`None`] if it doesn't push
+    /// anything.
+    ///
+    /// Unlike [`OpRef::Push`], this includes the opcodes that push constants:
+    /// [`OP_0`] pushes an empty byte string, [`OP_1NEGATE`] pushes `[0x81]`
+    /// and [`OP_1`] to [`OP_16`] push the single byte 1 to 16.
+    /// ```
+    /// # use bitcoinsuite_core::script::{opcode::*, OpRef};
+    /// let op = OpRef::Push(OP_PUSHDATA1, &[1, 2]);
+    /// assert_eq!(op.pushed_bytes(), Some([1, 2].as_ref()));
+    /// assert_eq!(OpRef::Code(OP_0).pushed_bytes(), Some([].as_ref()));
+    /// assert_eq!(OpRef::Code(OP_16).pushed_bytes(), Some([16].as_ref()));
+    /// let op = OpRef::Code(OP_1NEGATE);
+    /// assert_eq!(op.pushed_bytes(), Some([0x81].as_ref()));
+    /// assert_eq!(OpRef::Code(OP_RESERVED).pushed_bytes(), None);
+    /// ```
+    pub fn pushed_bytes(&self) -> Option<&'a [u8]> {
+        static SMALL_INTS: [u8; 16] =
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+        match *self {
+            OpRef::Push(_, data) => Some(data),
+            OpRef::Code(OP_0) => Some(&[]),
+            OpRef::Code(OP_1NEGATE) => Some(&[0x81]),
+            OpRef::Code(opcode @ Opcode(0x51..=0x60)) => {
+                let idx = usize::from(opcode.number() - OP_1.number());
+                Some(&SMALL_INTS[idx..idx + 1])
+            }
+            OpRef::Code(_) => None,
+        }
+    }
+}
+
+/// Serialize the op as it appears in the script bytecode, i.e. the opcode
+/// followed by the size prefix of `OP_PUSHDATAn` and the pushed data.
+///
+/// Note: This is the inverse of [`Op::read_op`], i.e. the pushed data's size
+/// is expected to fit the opcode; it's not checked.
+/// ```
+/// # use bitcoinsuite_core::{script::{opcode::*, Op}, ser::BitcoinSer};
+/// # use hex_literal::hex;
+/// assert_eq!(Op::Code(OP_DUP).ser().as_ref(), &[0x76]);
+/// let op = Op::Push(Opcode(2), vec![1, 2].into());
+/// assert_eq!(op.ser().as_ref(), &hex!("020102"));
+/// let op = Op::Push(OP_PUSHDATA2, vec![1, 2].into());
+/// assert_eq!(op.ser().as_ref(), &hex!("4d02000102"));
+/// ```
+impl BitcoinSer for Op {
+    fn ser_to<S: BitcoinSerializer>(&self, bytes: &mut S) {
+        match self {
+            Op::Code(opcode) => opcode.number().ser_to(bytes),
+            Op::Push(opcode, data) => {
+                opcode.number().ser_to(bytes);
+                match *opcode {
+                    OP_PUSHDATA1 => (data.len() as u8).ser_to(bytes),
+                    OP_PUSHDATA2 => (data.len() as u16).ser_to(bytes),
+                    OP_PUSHDATA4 => (data.len() as u32).ser_to(bytes),
+                    _ => {}
+                }
+                bytes.put(data);
+            }
+        }
+    }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        Op::read_op(data)
+    }
+}
+
+fn read_push_size<const N: usize>(
+    data: &mut Bytes,
+) -> Result<[u8; N], DataError> {
+    read_array(data).map_err(|_| DataError::UnexpectedEnd {
+        expected: N,
+        actual: data.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use bytes::Bytes;
+
+    use crate::{
+        error::DataError,
+        script::{opcode::*, Op, OpRef},
+        ser::BitcoinSer,
+    };
+
+    #[test]
+    fn test_read_op_pushdata4_bogus_size() {
+        // Declared size of u32::MAX, but only 3 bytes follow
+        let mut data = Bytes::from(vec![0x4e, 0xff, 0xff, 0xff, 0xff, 1, 2, 3]);
+        assert_eq!(
+            Op::read_op(&mut data),
+            Err(DataError::InvalidLength {
+                expected: u32::MAX as usize,
+                actual: 3,
+            }),
+        );
+
+        // Declared size of 0x80000001 with no payload at all
+        let mut data = Bytes::from(vec![0x4e, 0x01, 0x00, 0x00, 0x80]);
+        assert_eq!(
+            Op::read_op(&mut data),
+            Err(DataError::InvalidLength {
+                expected: 0x8000_0001,
+                actual: 0,
+            }),
+        );
+
+        // Valid OP_PUSHDATA4
+        let mut data = Bytes::from(vec![0x4e, 0x02, 0x00, 0x00, 0x00, 1, 2]);
+        assert_eq!(
+            Op::read_op(&mut data),
+            Ok(Op::Push(OP_PUSHDATA4, vec![1, 2].into())),
+        );
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn test_read_op_ref() {
+        // Every possible opcode followed by a few possible tails
+        let tails: [&[u8]; 6] = [
+            &[],
+            &[0x00],
+            &[0xff, 0xff],
+            &[0x02, 1, 2],
+            &[0x01, 0, 0, 0, 0x51],
+            &[0x4e, 0x01, 0x00, 0x00, 0x80],
+        ];
+        for opcode in 0..=0xffu8 {
+            for tail in tails {
+                let bytecode = [[opcode].as_ref(), tail].concat();
+                let mut data = Bytes::from(bytecode.clone());
+                let mut data_ref = bytecode.as_slice();
+                let expected = Op::read_op(&mut data);
+                let actual = OpRef::read_op(&mut data_ref);
+                match (expected, actual) {
+                    (Ok(Op::Code(a)), Ok(OpRef::Code(b))) => assert_eq!(a, b),
+                    (Ok(Op::Push(a, a_data)), Ok(OpRef::Push(b, b_data))) => {
+                        assert_eq!(a, b);
+                        assert_eq!(a_data.as_ref(), b_data);
+                    }
+                    (Err(a), Err(b)) => {
+                        assert_eq!(a, b);
+                        continue;
+                    }
+                    (a, b) => panic!("Mismatch: {a:?} != {b:?}"),
+                }
+                assert_eq!(data.as_ref(), data_ref);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ser_op_roundtrip() {
+        let bytecodes: [&[u8]; 6] = [
+            &[0x00],
+            &[0x6a],
+            &[0x01, 0xff],
+            &[0x4c, 0x02, 1, 2],
+            &[0x4d, 0x00, 0x00],
+            &[0x4e, 0x01, 0x00, 0x00, 0x00, 7],
+        ];
+        for bytecode in bytecodes {
+            let mut data = Bytes::copy_from_slice(bytecode);
+            let op = Op::read_op(&mut data).unwrap();
+            assert_eq!(op.ser().as_ref(), bytecode);
+            assert_eq!(op.ser_len(), bytecode.len());
+        }
+    }
 }
@@ -52,6 +52,24 @@ macro_rules! define_opcodes {
                 _ => None,
             }
         }
+
+        /// Return the opcode's number using its name, including aliases like
+        /// `OP_TRUE`:
+        /// ```
+        /// # use bitcoinsuite_core::script::opcode::*;
+        /// assert_eq!(opcode_name_to_number("OP_0"), Some(0));
+        /// assert_eq!(opcode_name_to_number("OP_FALSE"), Some(0));
+        /// assert_eq!(opcode_name_to_number("OP_TRUE"), Some(0x51));
+        /// assert_eq!(opcode_name_to_number("OP_FOO"), None);
+        /// ```
+        pub fn opcode_name_to_number(name: &str) -> Option<u8> {
+            match name {
+                $(
+                    stringify!($opcode) => Some($number),
+                )*
+                _ => None,
+            }
+        }
     };
 }
 
@@ -61,8 +79,8 @@ impl From<Opcode> for u8 {
     }
 }
 
-impl std::fmt::Display for Opcode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Opcode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match opcode_number_to_name(self.number()) {
             Some(name) => write!(f, "{}", name),
             None => write!(f, "[unrecognized opcode]"),
@@ -80,6 +98,117 @@ impl Opcode {
     pub fn number(self) -> u8 {
         self.0
     }
+
+    /// Name of the opcode, e.g. "OP_CHECKSIG", or [`None`] for direct pushes
+    /// and undefined opcodes. For opcodes with aliases, this is the name used
+    /// by the node, e.g. "OP_1" for [`OP_TRUE`].
+    /// ```
+    /// # use bitcoinsuite_core::script::opcode::*;
+    /// assert_eq!(OP_CHECKSIG.name(), Some("OP_CHECKSIG"));
+    /// assert_eq!(OP_TRUE.name(), Some("OP_1"));
+    /// assert_eq!(Opcode(0x14).name(), None);
+    /// assert_eq!(Opcode(0xff).name(), None);
+    /// ```
+    pub fn name(self) -> Option<&'static str> {
+        opcode_number_to_name(self.0)
+    }
+
+    /// Opcode with the given name, the inverse of [`Opcode::name`]. Also
+    /// accepts aliases, e.g. "OP_TRUE" or "OP_NOP2".
+    /// ```
+    /// # use bitcoinsuite_core::script::opcode::*;
+    /// assert_eq!(Opcode::from_name("OP_REVERSEBYTES"), Some(OP_REVERSEBYTES));
+    /// assert_eq!(Opcode::from_name("OP_NOP2"), Some(OP_CHECKLOCKTIMEVERIFY));
+    /// assert_eq!(Opcode::from_name("REVERSEBYTES"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Opcode> {
+        opcode_name_to_number(name).map(Opcode)
+    }
+
+    /// Whether this opcode is disabled by the node, i.e. fails the script if
+    /// executed: [`OP_INVERT`], [`OP_2MUL`], [`OP_2DIV`], [`OP_MUL`],
+    /// [`OP_LSHIFT`] and [`OP_RSHIFT`].
+    ///
+    /// Opcodes re-enabled on eCash/BCH, like [`OP_CAT`], [`OP_SPLIT`] or
+    /// [`OP_DIV`], aren't disabled.
+    /// ```
+    /// # use bitcoinsuite_core::script::opcode::*;
+    /// assert!(OP_MUL.is_disabled());
+    /// assert!(!OP_CAT.is_disabled());
+    /// assert!(!OP_REVERSEBYTES.is_disabled());
+    /// ```
+    pub const fn is_disabled(self) -> bool {
+        matches!(
+            self,
+            OP_INVERT | OP_2MUL | OP_2DIV | OP_MUL | OP_LSHIFT | OP_RSHIFT
+        )
+    }
+
+    /// Whether this opcode is defined by the node, i.e. whether it is below
+    /// [`FIRST_UNDEFINED_OP_VALUE`]. Defined opcodes can still be disabled,
+    /// e.g. [`OP_MUL`].
+    /// ```
+    /// # use bitcoinsuite_core::script::opcode::*;
+    /// assert!(OP_0.is_defined());
+    /// assert!(Opcode(0x4b).is_defined());
+    /// assert!(OP_REVERSEBYTES.is_defined());
+    /// assert!(!Opcode(0xbd).is_defined());
+    /// assert!(!Opcode(0xff).is_defined());
+    /// ```
+    pub const fn is_defined(self) -> bool {
+        self.0 < FIRST_UNDEFINED_OP_VALUE
+    }
+
+    /// Estimated relative cost of executing this opcode, used for validation
+    /// cost heuristics (see [`crate::script::Script::execution_cost`]).
+    ///
+    /// | Cost | Opcodes                                                    |
+    /// |------|------------------------------------------------------------|
+    /// | 10   | Hashes: `OP_RIPEMD160`, `OP_SHA1`, `OP_SHA256`,            |
+    /// |      | `OP_HASH160`, `OP_HASH256`                                 |
+    /// | 100  | Signature checks: `OP_CHECKSIG`, `OP_CHECKSIGVERIFY`,      |
+    /// |      | `OP_CHECKDATASIG`, `OP_CHECKDATASIGVERIFY`                 |
+    /// | 2000 | Multisig: `OP_CHECKMULTISIG`, `OP_CHECKMULTISIGVERIFY`     |
+    /// |      | (assumes the maximum of 20 signature checks)               |
+    /// | 1    | Any other opcode, including pushes                         |
+    ///
+    /// Pushed data adds [`push_data_cost`] on top of the opcode's cost.
+    /// ```
+    /// # use bitcoinsuite_core::script::opcode::*;
+    /// assert_eq!(OP_DUP.execution_cost(), 1);
+    /// assert_eq!(OP_HASH160.execution_cost(), 10);
+    /// assert_eq!(OP_CHECKSIG.execution_cost(), 100);
+    /// assert_eq!(OP_CHECKMULTISIG.execution_cost(), 2000);
+    /// ```
+    pub const fn execution_cost(self) -> u64 {
+        match self {
+            OP_RIPEMD160 | OP_SHA1 | OP_SHA256 | OP_HASH160 | OP_HASH256 => 10,
+            OP_CHECKSIG
+            | OP_CHECKSIGVERIFY
+            | OP_CHECKDATASIG
+            | OP_CHECKDATASIGVERIFY => 100,
+            OP_CHECKMULTISIG | OP_CHECKMULTISIGVERIFY => 2000,
+            _ => 1,
+        }
+    }
+}
+
+/// Number of the first opcode not defined by the node. This opcode and all
+/// opcodes above it are invalid and fail the script if executed, but may still
+/// appear in e.g. unexecuted branches or OP_RETURN outputs.
+pub const FIRST_UNDEFINED_OP_VALUE: u8 = 0xbd;
+
+/// Estimated cost of pushing `num_bytes` of data onto the stack, on top of the
+/// [`Opcode::execution_cost`] of the push opcode: 1 per started 32 bytes.
+/// ```
+/// # use bitcoinsuite_core::script::opcode::push_data_cost;
+/// assert_eq!(push_data_cost(0), 0);
+/// assert_eq!(push_data_cost(1), 1);
+/// assert_eq!(push_data_cost(32), 1);
+/// assert_eq!(push_data_cost(33), 2);
+/// ```
+pub const fn push_data_cost(num_bytes: usize) -> u64 {
+    ((num_bytes as u64) + 31) / 32
 }
 
 define_opcodes! {
@@ -131,24 +260,206 @@ define_opcodes! {
     OP_15 = 0x5f,
     /// Push the number 16 onto the stack.
     OP_16 = 0x60,
+    /// Do nothing.
+    OP_NOP = 0x61,
+    /// Reserved, fails the script if executed.
+    OP_VER = 0x62,
+    /// Execute the following ops if the top stack item is true.
+    OP_IF = 0x63,
+    /// Execute the following ops if the top stack item is false.
+    OP_NOTIF = 0x64,
+    /// Reserved, fails the script even if not executed.
+    OP_VERIF = 0x65,
+    /// Reserved, fails the script even if not executed.
+    OP_VERNOTIF = 0x66,
+    /// Execute the following ops if the preceding branch wasn't executed.
+    OP_ELSE = 0x67,
+    /// End an `OP_IF`/`OP_NOTIF`/`OP_ELSE` block.
+    OP_ENDIF = 0x68,
+    /// Fail the script unless the top stack item is true, then pop it.
+    OP_VERIFY = 0x69,
     /// Mark transaction as invalid. Used to add data to a tx in an output.
     OP_RETURN = 0x6a,
+    /// Move the top stack item onto the alt stack.
+    OP_TOALTSTACK = 0x6b,
+    /// Move the top alt stack item onto the stack.
+    OP_FROMALTSTACK = 0x6c,
+    /// Remove the top two stack items.
+    OP_2DROP = 0x6d,
+    /// Duplicate the top two stack items.
+    OP_2DUP = 0x6e,
+    /// Duplicate the top three stack items.
+    OP_3DUP = 0x6f,
+    /// Copy the pair of items two spaces back to the top.
+    OP_2OVER = 0x70,
+    /// Move the fifth and sixth items to the top.
+    OP_2ROT = 0x71,
+    /// Swap the top two pairs of items.
+    OP_2SWAP = 0x72,
+    /// Duplicate the top stack item if it is true.
+    OP_IFDUP = 0x73,
+    /// Push the number of stack items onto the stack.
+    OP_DEPTH = 0x74,
+    /// Remove the top stack item.
+    OP_DROP = 0x75,
     /// Duplicate the top stack item.
     OP_DUP = 0x76,
+    /// Remove the second-to-top stack item.
+    OP_NIP = 0x77,
+    /// Copy the second-to-top stack item to the top.
+    OP_OVER = 0x78,
+    /// Copy the item n back in the stack to the top.
+    OP_PICK = 0x79,
+    /// Move the item n back in the stack to the top.
+    OP_ROLL = 0x7a,
+    /// Move the third stack item to the top.
+    OP_ROT = 0x7b,
+    /// Swap the top two stack items.
+    OP_SWAP = 0x7c,
+    /// Copy the top stack item before the second-to-top item.
+    OP_TUCK = 0x7d,
+    /// Concatenate the top two stack items.
+    OP_CAT = 0x7e,
+    /// Split the second-to-top item at the position given by the top item.
+    OP_SPLIT = 0x7f,
+    /// Convert a number into a byte string of the given size.
+    OP_NUM2BIN = 0x80,
+    /// Convert a byte string into a minimally encoded number.
+    OP_BIN2NUM = 0x81,
+    /// Push the size of the top stack item, without popping it.
+    OP_SIZE = 0x82,
+    /// Disabled, fails the script if executed.
+    OP_INVERT = 0x83,
+    /// Bitwise AND of the top two stack items.
+    OP_AND = 0x84,
+    /// Bitwise OR of the top two stack items.
+    OP_OR = 0x85,
+    /// Bitwise XOR of the top two stack items.
+    OP_XOR = 0x86,
     /// If the top two stack items are byte-equal, push 1 onto the stack,
     /// otherwise 0.
     OP_EQUAL = 0x87,
     /// Like [`OP_EQUAL`], but fail the script if the items aren't equal.
     OP_EQUALVERIFY = 0x88,
+    /// Reserved, fails the script if executed.
+    OP_RESERVED1 = 0x89,
+    /// Reserved, fails the script if executed.
+    OP_RESERVED2 = 0x8a,
+    /// Add 1 to the top stack item.
+    OP_1ADD = 0x8b,
+    /// Subtract 1 from the top stack item.
+    OP_1SUB = 0x8c,
+    /// Disabled, fails the script if executed.
+    OP_2MUL = 0x8d,
+    /// Disabled, fails the script if executed.
+    OP_2DIV = 0x8e,
+    /// Negate the top stack item.
+    OP_NEGATE = 0x8f,
+    /// Replace the top stack item by its absolute value.
+    OP_ABS = 0x90,
+    /// Replace 0 by 1 and any other number by 0.
+    OP_NOT = 0x91,
+    /// Replace 0 by 0 and any other number by 1.
+    OP_0NOTEQUAL = 0x92,
+    /// Add the top two stack items.
+    OP_ADD = 0x93,
+    /// Subtract the top stack item from the second-to-top item.
+    OP_SUB = 0x94,
+    /// Disabled, fails the script if executed.
+    OP_MUL = 0x95,
+    /// Divide the second-to-top stack item by the top item.
+    OP_DIV = 0x96,
+    /// Remainder of dividing the second-to-top item by the top item.
+    OP_MOD = 0x97,
+    /// Disabled, fails the script if executed.
+    OP_LSHIFT = 0x98,
+    /// Disabled, fails the script if executed.
+    OP_RSHIFT = 0x99,
+    /// Push 1 if both top stack items are non-zero, otherwise 0.
+    OP_BOOLAND = 0x9a,
+    /// Push 1 if either top stack item is non-zero, otherwise 0.
+    OP_BOOLOR = 0x9b,
+    /// Push 1 if the top two numbers are equal, otherwise 0.
+    OP_NUMEQUAL = 0x9c,
+    /// Like [`OP_NUMEQUAL`], but fail the script if the numbers differ.
+    OP_NUMEQUALVERIFY = 0x9d,
+    /// Push 1 if the top two numbers are not equal, otherwise 0.
+    OP_NUMNOTEQUAL = 0x9e,
+    /// Push 1 if the second-to-top number is less than the top one.
+    OP_LESSTHAN = 0x9f,
+    /// Push 1 if the second-to-top number is greater than the top one.
+    OP_GREATERTHAN = 0xa0,
+    /// Push 1 if the second-to-top number is at most the top one.
+    OP_LESSTHANOREQUAL = 0xa1,
+    /// Push 1 if the second-to-top number is at least the top one.
+    OP_GREATERTHANOREQUAL = 0xa2,
+    /// Push the smaller of the top two numbers.
+    OP_MIN = 0xa3,
+    /// Push the larger of the top two numbers.
+    OP_MAX = 0xa4,
+    /// Push 1 if x is within the range [min, max), otherwise 0.
+    OP_WITHIN = 0xa5,
+    /// Hash the top stack item using RIPEMD-160
+    OP_RIPEMD160 = 0xa6,
+    /// Hash the top stack item using SHA-1
+    OP_SHA1 = 0xa7,
+    /// Hash the top stack item using SHA-256
+    OP_SHA256 = 0xa8,
     /// Hash the top stack item x using RIPEMD-160(SHA-256(x))
     OP_HASH160 = 0xa9,
+    /// Hash the top stack item x using SHA-256(SHA-256(x))
+    OP_HASH256 = 0xaa,
+    /// Only sign the script after the last executed `OP_CODESEPARATOR`.
+    OP_CODESEPARATOR = 0xab,
     /// Pop pubkey and signature and verify if they sign this input's BIP143
     /// sighash.
     OP_CHECKSIG = 0xac,
+    /// Like [`OP_CHECKSIG`], but fail the script if the signature is invalid.
+    OP_CHECKSIGVERIFY = 0xad,
+    /// Verify an m-of-n multisig: Pop n, n pubkeys, m, m signatures and a
+    /// dummy element and verify the signatures sign this input's sighash.
+    OP_CHECKMULTISIG = 0xae,
+    /// Like [`OP_CHECKMULTISIG`], but fail the script if verification fails.
+    OP_CHECKMULTISIGVERIFY = 0xaf,
+    /// Do nothing, reserved for upgrades.
+    OP_NOP1 = 0xb0,
+    /// Fail the script unless the tx's locktime is at least the top item
+    /// (BIP65).
+    OP_CHECKLOCKTIMEVERIFY = 0xb1,
+    /// Former name of [`OP_CHECKLOCKTIMEVERIFY`].
+    OP_NOP2 = 0xb1,
+    /// Fail the script unless the input's relative locktime is at least the
+    /// top item (BIP112).
+    OP_CHECKSEQUENCEVERIFY = 0xb2,
+    /// Former name of [`OP_CHECKSEQUENCEVERIFY`].
+    OP_NOP3 = 0xb2,
+    /// Do nothing, reserved for upgrades.
+    OP_NOP4 = 0xb3,
+    /// Do nothing, reserved for upgrades.
+    OP_NOP5 = 0xb4,
+    /// Do nothing, reserved for upgrades.
+    OP_NOP6 = 0xb5,
+    /// Do nothing, reserved for upgrades.
+    OP_NOP7 = 0xb6,
+    /// Do nothing, reserved for upgrades.
+    OP_NOP8 = 0xb7,
+    /// Do nothing, reserved for upgrades.
+    OP_NOP9 = 0xb8,
+    /// Do nothing, reserved for upgrades.
+    OP_NOP10 = 0xb9,
+    /// Pop pubkey, message and signature and verify the signature signs the
+    /// SHA-256 of the message.
+    OP_CHECKDATASIG = 0xba,
+    /// Like [`OP_CHECKDATASIG`], but fail the script if verification fails.
+    OP_CHECKDATASIGVERIFY = 0xbb,
+    /// Reverse the bytes of the top stack item.
+    OP_REVERSEBYTES = 0xbc,
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::{string::ToString, vec::Vec};
+
     use crate::script::opcode::*;
 
     #[test]
@@ -158,6 +469,196 @@ mod tests {
         assert_eq!(u8::from(OP_TRUE), 0x51);
     }
 
+    #[test]
+    fn test_opcode_table() {
+        // Reference values from the node's script/script.h
+        let table = [
+            (OP_0, 0x00, "OP_0"),
+            (OP_FALSE, 0x00, "OP_0"),
+            (OP_PUSHDATA1, 0x4c, "OP_PUSHDATA1"),
+            (OP_PUSHDATA2, 0x4d, "OP_PUSHDATA2"),
+            (OP_PUSHDATA4, 0x4e, "OP_PUSHDATA4"),
+            (OP_1NEGATE, 0x4f, "OP_1NEGATE"),
+            (OP_RESERVED, 0x50, "OP_RESERVED"),
+            (OP_1, 0x51, "OP_1"),
+            (OP_TRUE, 0x51, "OP_1"),
+            (OP_2, 0x52, "OP_2"),
+            (OP_3, 0x53, "OP_3"),
+            (OP_4, 0x54, "OP_4"),
+            (OP_5, 0x55, "OP_5"),
+            (OP_6, 0x56, "OP_6"),
+            (OP_7, 0x57, "OP_7"),
+            (OP_8, 0x58, "OP_8"),
+            (OP_9, 0x59, "OP_9"),
+            (OP_10, 0x5a, "OP_10"),
+            (OP_11, 0x5b, "OP_11"),
+            (OP_12, 0x5c, "OP_12"),
+            (OP_13, 0x5d, "OP_13"),
+            (OP_14, 0x5e, "OP_14"),
+            (OP_15, 0x5f, "OP_15"),
+            (OP_16, 0x60, "OP_16"),
+            (OP_NOP, 0x61, "OP_NOP"),
+            (OP_VER, 0x62, "OP_VER"),
+            (OP_IF, 0x63, "OP_IF"),
+            (OP_NOTIF, 0x64, "OP_NOTIF"),
+            (OP_VERIF, 0x65, "OP_VERIF"),
+            (OP_VERNOTIF, 0x66, "OP_VERNOTIF"),
+            (OP_ELSE, 0x67, "OP_ELSE"),
+            (OP_ENDIF, 0x68, "OP_ENDIF"),
+            (OP_VERIFY, 0x69, "OP_VERIFY"),
+            (OP_RETURN, 0x6a, "OP_RETURN"),
+            (OP_TOALTSTACK, 0x6b, "OP_TOALTSTACK"),
+            (OP_FROMALTSTACK, 0x6c, "OP_FROMALTSTACK"),
+            (OP_2DROP, 0x6d, "OP_2DROP"),
+            (OP_2DUP, 0x6e, "OP_2DUP"),
+            (OP_3DUP, 0x6f, "OP_3DUP"),
+            (OP_2OVER, 0x70, "OP_2OVER"),
+            (OP_2ROT, 0x71, "OP_2ROT"),
+            (OP_2SWAP, 0x72, "OP_2SWAP"),
+            (OP_IFDUP, 0x73, "OP_IFDUP"),
+            (OP_DEPTH, 0x74, "OP_DEPTH"),
+            (OP_DROP, 0x75, "OP_DROP"),
+            (OP_DUP, 0x76, "OP_DUP"),
+            (OP_NIP, 0x77, "OP_NIP"),
+            (OP_OVER, 0x78, "OP_OVER"),
+            (OP_PICK, 0x79, "OP_PICK"),
+            (OP_ROLL, 0x7a, "OP_ROLL"),
+            (OP_ROT, 0x7b, "OP_ROT"),
+            (OP_SWAP, 0x7c, "OP_SWAP"),
+            (OP_TUCK, 0x7d, "OP_TUCK"),
+            (OP_CAT, 0x7e, "OP_CAT"),
+            (OP_SPLIT, 0x7f, "OP_SPLIT"),
+            (OP_NUM2BIN, 0x80, "OP_NUM2BIN"),
+            (OP_BIN2NUM, 0x81, "OP_BIN2NUM"),
+            (OP_SIZE, 0x82, "OP_SIZE"),
+            (OP_INVERT, 0x83, "OP_INVERT"),
+            (OP_AND, 0x84, "OP_AND"),
+            (OP_OR, 0x85, "OP_OR"),
+            (OP_XOR, 0x86, "OP_XOR"),
+            (OP_EQUAL, 0x87, "OP_EQUAL"),
+            (OP_EQUALVERIFY, 0x88, "OP_EQUALVERIFY"),
+            (OP_RESERVED1, 0x89, "OP_RESERVED1"),
+            (OP_RESERVED2, 0x8a, "OP_RESERVED2"),
+            (OP_1ADD, 0x8b, "OP_1ADD"),
+            (OP_1SUB, 0x8c, "OP_1SUB"),
+            (OP_2MUL, 0x8d, "OP_2MUL"),
+            (OP_2DIV, 0x8e, "OP_2DIV"),
+            (OP_NEGATE, 0x8f, "OP_NEGATE"),
+            (OP_ABS, 0x90, "OP_ABS"),
+            (OP_NOT, 0x91, "OP_NOT"),
+            (OP_0NOTEQUAL, 0x92, "OP_0NOTEQUAL"),
+            (OP_ADD, 0x93, "OP_ADD"),
+            (OP_SUB, 0x94, "OP_SUB"),
+            (OP_MUL, 0x95, "OP_MUL"),
+            (OP_DIV, 0x96, "OP_DIV"),
+            (OP_MOD, 0x97, "OP_MOD"),
+            (OP_LSHIFT, 0x98, "OP_LSHIFT"),
+            (OP_RSHIFT, 0x99, "OP_RSHIFT"),
+            (OP_BOOLAND, 0x9a, "OP_BOOLAND"),
+            (OP_BOOLOR, 0x9b, "OP_BOOLOR"),
+            (OP_NUMEQUAL, 0x9c, "OP_NUMEQUAL"),
+            (OP_NUMEQUALVERIFY, 0x9d, "OP_NUMEQUALVERIFY"),
+            (OP_NUMNOTEQUAL, 0x9e, "OP_NUMNOTEQUAL"),
+            (OP_LESSTHAN, 0x9f, "OP_LESSTHAN"),
+            (OP_GREATERTHAN, 0xa0, "OP_GREATERTHAN"),
+            (OP_LESSTHANOREQUAL, 0xa1, "OP_LESSTHANOREQUAL"),
+            (OP_GREATERTHANOREQUAL, 0xa2, "OP_GREATERTHANOREQUAL"),
+            (OP_MIN, 0xa3, "OP_MIN"),
+            (OP_MAX, 0xa4, "OP_MAX"),
+            (OP_WITHIN, 0xa5, "OP_WITHIN"),
+            (OP_RIPEMD160, 0xa6, "OP_RIPEMD160"),
+            (OP_SHA1, 0xa7, "OP_SHA1"),
+            (OP_SHA256, 0xa8, "OP_SHA256"),
+            (OP_HASH160, 0xa9, "OP_HASH160"),
+            (OP_HASH256, 0xaa, "OP_HASH256"),
+            (OP_CODESEPARATOR, 0xab, "OP_CODESEPARATOR"),
+            (OP_CHECKSIG, 0xac, "OP_CHECKSIG"),
+            (OP_CHECKSIGVERIFY, 0xad, "OP_CHECKSIGVERIFY"),
+            (OP_CHECKMULTISIG, 0xae, "OP_CHECKMULTISIG"),
+            (OP_CHECKMULTISIGVERIFY, 0xaf, "OP_CHECKMULTISIGVERIFY"),
+            (OP_NOP1, 0xb0, "OP_NOP1"),
+            (OP_CHECKLOCKTIMEVERIFY, 0xb1, "OP_CHECKLOCKTIMEVERIFY"),
+            (OP_NOP2, 0xb1, "OP_CHECKLOCKTIMEVERIFY"),
+            (OP_CHECKSEQUENCEVERIFY, 0xb2, "OP_CHECKSEQUENCEVERIFY"),
+            (OP_NOP3, 0xb2, "OP_CHECKSEQUENCEVERIFY"),
+            (OP_NOP4, 0xb3, "OP_NOP4"),
+            (OP_NOP5, 0xb4, "OP_NOP5"),
+            (OP_NOP6, 0xb5, "OP_NOP6"),
+            (OP_NOP7, 0xb6, "OP_NOP7"),
+            (OP_NOP8, 0xb7, "OP_NOP8"),
+            (OP_NOP9, 0xb8, "OP_NOP9"),
+            (OP_NOP10, 0xb9, "OP_NOP10"),
+            (OP_CHECKDATASIG, 0xba, "OP_CHECKDATASIG"),
+            (OP_CHECKDATASIGVERIFY, 0xbb, "OP_CHECKDATASIGVERIFY"),
+            (OP_REVERSEBYTES, 0xbc, "OP_REVERSEBYTES"),
+        ];
+        for (opcode, number, name) in table {
+            assert_eq!(opcode.number(), number);
+            assert_eq!(opcode_number_to_name(number), Some(name));
+        }
+        // Every named opcode is in the table above
+        let num_named = (0..=u8::MAX)
+            .filter(|&number| opcode_number_to_name(number).is_some())
+            .count();
+        assert_eq!(num_named, table.len() - 4);
+    }
+
+    #[test]
+    fn test_bch_opcodes() {
+        // Opcodes (re-)activated on BCH/eCash
+        let table = [
+            (OP_CAT, 0x7e, "OP_CAT"),
+            (OP_SPLIT, 0x7f, "OP_SPLIT"),
+            (OP_NUM2BIN, 0x80, "OP_NUM2BIN"),
+            (OP_BIN2NUM, 0x81, "OP_BIN2NUM"),
+            (OP_AND, 0x84, "OP_AND"),
+            (OP_OR, 0x85, "OP_OR"),
+            (OP_XOR, 0x86, "OP_XOR"),
+            (OP_DIV, 0x96, "OP_DIV"),
+            (OP_MOD, 0x97, "OP_MOD"),
+            (OP_CHECKDATASIG, 0xba, "OP_CHECKDATASIG"),
+            (OP_CHECKDATASIGVERIFY, 0xbb, "OP_CHECKDATASIGVERIFY"),
+            (OP_REVERSEBYTES, 0xbc, "OP_REVERSEBYTES"),
+        ];
+        for (opcode, number, name) in table {
+            assert_eq!(opcode.number(), number);
+            assert_eq!(opcode.name(), Some(name));
+            assert_eq!(Opcode::from_name(name), Some(opcode));
+            assert!(opcode.is_defined());
+            assert!(!opcode.is_disabled());
+        }
+    }
+
+    #[test]
+    fn test_opcode_name_roundtrip() {
+        for number in 0..=u8::MAX {
+            let opcode = Opcode(number);
+            match opcode.name() {
+                Some(name) => assert_eq!(Opcode::from_name(name), Some(opcode)),
+                None => assert!(
+                    (0x01..=0x4b).contains(&number) || !opcode.is_defined()
+                ),
+            }
+        }
+        assert_eq!(Opcode::from_name("OP_FALSE"), Some(OP_0));
+        assert_eq!(Opcode::from_name("OP_TRUE"), Some(OP_1));
+        assert_eq!(Opcode::from_name("OP_NOP3"), Some(OP_CHECKSEQUENCEVERIFY));
+        assert_eq!(Opcode::from_name(""), None);
+        assert_eq!(Opcode::from_name("op_dup"), None);
+    }
+
+    #[test]
+    fn test_opcode_is_disabled() {
+        let disabled = (0..=u8::MAX)
+            .map(Opcode)
+            .filter(|opcode| opcode.is_disabled())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            disabled,
+            [OP_INVERT, OP_2MUL, OP_2DIV, OP_MUL, OP_LSHIFT, OP_RSHIFT],
+        );
+    }
+
     #[test]
     fn test_display_opcode() {
         assert_eq!(OP_0.to_string(), "OP_0");
@@ -2,7 +2,8 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
-use std::str::FromStr;
+use alloc::{string::String, vec};
+use core::str::FromStr;
 
 use hex_literal::hex;
 
@@ -89,14 +90,53 @@ impl FromStr for PubKey {
     }
 }
 
+/// Parse a compressed pubkey, e.g. from a script push. Checks the length and
+/// that it starts with 0x02 or 0x03.
+/// ```
+/// # use bitcoinsuite_core::{error::DataError, script::PubKey};
+/// assert_eq!(PubKey::try_from(&[3u8; 33][..]), Ok(PubKey([3; 33])));
+/// assert_eq!(
+///     PubKey::try_from(&[2u8; 65][..]),
+///     Err(DataError::InvalidLength {
+///         expected: 33,
+///         actual: 65,
+///     }),
+/// );
+/// assert_eq!(
+///     PubKey::try_from(&[4u8; 33][..]),
+///     Err(DataError::InvalidPubKeyPrefix {
+///         expected: vec![2, 3],
+///         actual: 4,
+///     }),
+/// );
+/// ```
+impl TryFrom<&[u8]> for PubKey {
+    type Error = DataError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        let array: [u8; PubKey::SIZE] =
+            slice.try_into().map_err(|_| DataError::InvalidLength {
+                expected: PubKey::SIZE,
+                actual: slice.len(),
+            })?;
+        if !matches!(array[0], 0x02 | 0x03) {
+            return Err(DataError::InvalidPubKeyPrefix {
+                expected: vec![0x02, 0x03],
+                actual: array[0],
+            });
+        }
+        Ok(PubKey(array))
+    }
+}
+
 impl AsRef<[u8]> for PubKey {
     fn as_ref(&self) -> &[u8] {
         self.as_slice()
     }
 }
 
-impl std::fmt::Debug for PubKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for PubKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "PubKey({})", self.hex())
     }
 }
@@ -2,7 +2,8 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
-use std::str::FromStr;
+use alloc::vec;
+use core::str::FromStr;
 
 use crate::{
     error::DataError,
@@ -2,14 +2,46 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
-use bytes::Bytes;
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::str::FromStr;
+
+use bytes::{Bytes, BytesMut};
 
 use crate::{
-    hash::{Hashed, ShaRmd160},
-    script::{opcode::*, PubKey, ScriptMut, ScriptOpIter, UncompressedPubKey},
+    address::{
+        decode_base58_address, decode_cashaddr, encode_base58_address,
+        encode_cashaddr, Base58Error,
+    },
+    error::DataError,
+    hash::{Hashed, Sha256, ShaRmd160},
+    network::Network,
+    script::{
+        opcode::*, Op, OpRef, PubKey, PubKeyVariant, ScriptMut,
+        ScriptOpIndexedIter, ScriptOpIter, ScriptOpRefIter, ScriptOpStrictIter,
+        ScriptType, ScriptVariant, UncompressedPubKey,
+    },
     ser::{BitcoinSer, BitcoinSerializer},
 };
 
+/// Maximum size of a script in bytes, as enforced by the node.
+pub const MAX_SCRIPT_SIZE: usize = 10_000;
+
+/// Maximum number of non-push opcodes in a script, as enforced by the node.
+pub const MAX_OPS_PER_SCRIPT: usize = 201;
+
+/// Maximum number of pubkeys in an `OP_CHECKMULTISIG`, as enforced by the node.
+pub const MAX_PUBKEYS_PER_MULTISIG: u32 = 20;
+
+/// Typical size of an ECDSA signature in a scriptSig, including the sighash
+/// byte, used for estimating spend sizes. Schnorr signatures are smaller (65
+/// bytes), so estimates using this are on the safe side.
+pub const ESTIMATED_SIG_SIZE: usize = 72;
+
 /// A Bitcoin script.
 ///
 /// This is immutable, and uses [`Bytes`] to store the bytecode, making it cheap
@@ -36,12 +68,11 @@ impl Script {
     /// );
     /// ```
     pub fn p2pkh(hash: &ShaRmd160) -> Script {
-        let mut script = ScriptMut::with_capacity(2 + 1 + ShaRmd160::SIZE + 2);
-        script.put_opcodes([OP_DUP, OP_HASH160]);
-        script.put_bytecode(&[ShaRmd160::SIZE as u8]);
-        script.put_bytecode(hash.as_le_bytes());
-        script.put_opcodes([OP_EQUALVERIFY, OP_CHECKSIG]);
-        script.freeze()
+        ScriptMut::with_capacity(2 + 1 + ShaRmd160::SIZE + 2)
+            .ops([OP_DUP, OP_HASH160])
+            .push(hash.as_le_bytes())
+            .ops([OP_EQUALVERIFY, OP_CHECKSIG])
+            .freeze()
     }
 
     /// Pay-to-script-hash: `OP_HASH160 <script hash> OP_EQUAL`
@@ -56,12 +87,11 @@ impl Script {
     /// );
     /// ```
     pub fn p2sh(hash: &ShaRmd160) -> Script {
-        let mut script = ScriptMut::with_capacity(1 + 1 + ShaRmd160::SIZE + 1);
-        script.put_opcodes([OP_HASH160]);
-        script.put_bytecode(&[ShaRmd160::SIZE as u8]);
-        script.put_bytecode(hash.as_le_bytes());
-        script.put_opcodes([OP_EQUAL]);
-        script.freeze()
+        ScriptMut::with_capacity(1 + 1 + ShaRmd160::SIZE + 1)
+            .op(OP_HASH160)
+            .push(hash.as_le_bytes())
+            .op(OP_EQUAL)
+            .freeze()
     }
 
     /// Pay-to-public-key (compressed): `<pubkey> OP_CHECKSIG`
@@ -79,11 +109,10 @@ impl Script {
     /// );
     /// ```
     pub fn p2pk(pubkey: &PubKey) -> Script {
-        let mut script = ScriptMut::with_capacity(1 + PubKey::SIZE + 1);
-        script.put_bytecode(&[PubKey::SIZE as u8]);
-        script.put_bytecode(pubkey.as_slice());
-        script.put_opcodes([OP_CHECKSIG]);
-        script.freeze()
+        ScriptMut::with_capacity(1 + PubKey::SIZE + 1)
+            .push(pubkey.as_slice())
+            .op(OP_CHECKSIG)
+            .freeze()
     }
 
     /// Pay-to-public-key (uncompressed): `<pubkey> OP_CHECKSIG`
@@ -106,11 +135,94 @@ impl Script {
     /// );
     /// ```
     pub fn p2pk_uncompressed(pubkey: &UncompressedPubKey) -> Script {
-        let mut script =
-            ScriptMut::with_capacity(1 + UncompressedPubKey::SIZE + 1);
-        script.put_bytecode(&[UncompressedPubKey::SIZE as u8]);
-        script.put_bytecode(pubkey.as_ref());
-        script.put_opcodes([OP_CHECKSIG]);
+        ScriptMut::with_capacity(1 + UncompressedPubKey::SIZE + 1)
+            .push(pubkey.as_ref())
+            .op(OP_CHECKSIG)
+            .freeze()
+    }
+
+    /// Script signature (scriptSig) spending a [`Script::p2pkh`] output:
+    /// `<signature> <pubkey>`.
+    ///
+    /// Both are pushed using the minimal push opcode for their size, see
+    /// [`ScriptMut::put_pushdata`]. `signature` has to include the sighash
+    /// type byte.
+    /// ```
+    /// # use bitcoinsuite_core::script::{PubKey, Script};
+    /// # use hex_literal::hex;
+    /// let pubkey = PubKey(hex!(
+    ///     "0200112233445566778899aabbccddeeff00112233445566778899aabbccddeeff"
+    /// ));
+    /// let script = Script::p2pkh_sig(&[0xab; 65], &pubkey);
+    /// assert_eq!(
+    ///     script.hex(),
+    ///     format!(
+    ///         "41{}21{}",
+    ///         "ab".repeat(65),
+    ///         "0200112233445566778899aabbccddeeff00112233445566778899aabbccdd\
+    ///          eeff",
+    ///     ),
+    /// );
+    /// ```
+    pub fn p2pkh_sig(signature: &[u8], pubkey: &PubKey) -> Script {
+        ScriptMut::with_capacity(2 + signature.len() + 1 + PubKey::SIZE)
+            .push(signature)
+            .push(pubkey.as_slice())
+            .freeze()
+    }
+
+    /// Script signature (scriptSig) spending a [`Script::p2sh`] output wrapping
+    /// a bare multisig `redeem` script:
+    /// `OP_0 <signature>... <redeem script>`.
+    ///
+    /// The leading [`OP_0`] is the dummy element consumed by
+    /// [`OP_CHECKMULTISIG`] due to its off-by-one bug. Signatures have to be in
+    /// the same order as their pubkeys in the redeem script and include the
+    /// sighash type byte. All items are pushed using the minimal push opcode
+    /// for their size, see [`ScriptMut::put_pushdata`]; e.g. a 2-of-3
+    /// multisig redeem script with compressed pubkeys is 105 bytes long and is
+    /// therefore pushed using [`OP_PUSHDATA1`].
+    /// ```
+    /// # use bitcoinsuite_core::script::Script;
+    /// // 1-of-1 multisig
+    /// let redeem_hex = format!("5121{}51ae", "02".repeat(33));
+    /// let redeem = Script::from_hex(&redeem_hex).unwrap();
+    /// let script = Script::p2sh_multisig_sig(&[&[0xab; 65]], &redeem);
+    /// assert_eq!(
+    ///     script.hex(),
+    ///     format!("0041{}25{}", "ab".repeat(65), redeem.hex()),
+    /// );
+    /// ```
+    pub fn p2sh_multisig_sig(sigs: &[&[u8]], redeem: &Script) -> Script {
+        let mut script = ScriptMut::with_capacity(
+            1 + sigs.iter().map(|sig| 2 + sig.len()).sum::<usize>()
+                + 3
+                + redeem.bytecode().len(),
+        );
+        script.put_opcodes([OP_0]);
+        for sig in sigs {
+            script.put_pushdata(sig);
+        }
+        script.put_pushdata(redeem.bytecode());
+        script.freeze()
+    }
+
+    /// OP_RETURN script following the LOKAD convention:
+    /// `OP_RETURN <lokad id> <payload...>`, where the 4-byte LOKAD ID
+    /// identifies the protocol and each payload chunk is pushed using
+    /// [`ScriptMut::put_pushdata`]. Can be parsed with [`Script::parse_lokad`].
+    /// ```
+    /// # use bitcoinsuite_core::script::Script;
+    /// let script = Script::lokad(*b"SLP\0", &[vec![1].into(), vec![].into()]);
+    /// assert_eq!(script.hex(), "6a04534c5000010100");
+    /// ```
+    pub fn lokad(lokad_id: [u8; 4], payload: &[Bytes]) -> Script {
+        let mut script = ScriptMut::default();
+        script.put_opcodes([OP_RETURN]);
+        script.put_pushdata(&lokad_id);
+        for chunk in payload {
+            script.put_pushdata(chunk);
+        }
         script.freeze()
     }
 
@@ -136,7 +248,43 @@ impl Script {
         self.0.to_vec()
     }
 
+    /// Whether the bytecode of this script and `other` are backed by
+    /// overlapping memory, e.g. because one was cloned from the other or both
+    /// were sliced from the same part of a buffer. Intended for diagnostics,
+    /// e.g. to find scripts that keep a large buffer alive.
+    ///
+    /// Note: [`Bytes`] doesn't expose its underlying allocation, so this
+    /// compares memory ranges; scripts sliced from disjoint parts of the same
+    /// buffer aren't detected. Empty scripts never share an allocation.
+    /// ```
+    /// # use bitcoinsuite_core::script::Script;
+    /// use bytes::Bytes;
+    /// let buffer = Bytes::from(vec![0x51, 0x52, 0x53]);
+    /// let script = Script::new(buffer.clone());
+    /// let sliced = Script::new(buffer.slice(1..));
+    /// assert!(script.shares_allocation_with(&script.clone()));
+    /// assert!(script.shares_allocation_with(&sliced));
+    /// assert!(sliced.shares_allocation_with(&script));
+    /// // Same bytecode, but different allocation
+    /// let copied = Script::new(Bytes::copy_from_slice(&buffer));
+    /// assert!(!script.shares_allocation_with(&copied));
+    /// // Disjoint slices aren't detected
+    /// let first = Script::new(buffer.slice(..1));
+    /// assert!(!first.shares_allocation_with(&sliced));
+    /// ```
+    pub fn shares_allocation_with(&self, other: &Script) -> bool {
+        if self.0.is_empty() || other.0.is_empty() {
+            return false;
+        }
+        let self_range = self.0.as_ptr_range();
+        let other_range = other.0.as_ptr_range();
+        self_range.start < other_range.end && other_range.start < self_range.end
+    }
+
     /// Hex of the bytecode.
+    ///
+    /// This is the order the node, Chronik and block explorers display
+    /// scripts in, and the one to use almost always.
     /// ```
     /// # use bitcoinsuite_core::script::Script;
     /// use bytes::Bytes;
@@ -147,6 +295,291 @@ impl Script {
         hex::encode(&self.0)
     }
 
+    /// Hex of the bytecode with the byte order reversed.
+    ///
+    /// Only for tooling that displays script bytes reversed; the result isn't
+    /// a valid script and can't be parsed by [`Script::from_hex`] without
+    /// reversing it again. Note that the scripthashes of Electrum are
+    /// reversed *hashes* of the script, see [`Script::electrum_scripthash`].
+    /// ```
+    /// # use bitcoinsuite_core::script::Script;
+    /// let script = Script::new(vec![0xa9, 0x14, 0x87].into());
+    /// assert_eq!(script.hex(), "a91487");
+    /// assert_eq!(script.hex_rev(), "8714a9");
+    /// ```
+    pub fn hex_rev(&self) -> String {
+        let mut bytecode = self.0.to_vec();
+        bytecode.reverse();
+        hex::encode(bytecode)
+    }
+
+    /// Parse a script from the hex of its bytecode.
+    /// ```
+    /// # use bitcoinsuite_core::{error::DataError, script::Script};
+    /// let script = Script::from_hex("76a988ac").unwrap();
+    /// assert_eq!(script.to_vec(), vec![0x76, 0xa9, 0x88, 0xac]);
+    /// assert_eq!(script.hex(), "76a988ac");
+    /// assert_eq!(
+    ///     Script::from_hex("76a"),
+    ///     Err(DataError::InvalidHex(hex::FromHexError::OddLength)),
+    /// );
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Script, DataError> {
+        let bytecode = hex::decode(hex).map_err(DataError::InvalidHex)?;
+        Ok(Script(bytecode.into()))
+    }
+
+    /// Human-readable form of the script, with opcodes by their canonical
+    /// name (see [`Opcode::name`]) and pushes as `<hex>`, separated by spaces.
+    ///
+    /// Opcodes without a name are written as `0x` followed by their hex, and
+    /// a parse error ends the asm with `[error]`. Can be parsed again using
+    /// [`Script::from_asm`], which however always uses the minimal push
+    /// opcode, so scripts with non-minimal pushes don't roundtrip exactly.
+    /// ```
+    /// # use bitcoinsuite_core::{hash::ShaRmd160, script::Script};
+    /// let script = Script::p2pkh(&ShaRmd160([0xab; 20]));
+    /// assert_eq!(
+    ///     script.asm(),
+    ///     format!(
+    ///         "OP_DUP OP_HASH160 <{}> OP_EQUALVERIFY OP_CHECKSIG",
+    ///         "ab".repeat(20),
+    ///     ),
+    /// );
+    /// let script = Script::from_hex("6a4c0001ff51bd4c").unwrap();
+    /// assert_eq!(script.asm(), "OP_RETURN <> <ff> OP_1 0xbd [error]");
+    /// ```
+    pub fn asm(&self) -> String {
+        let mut tokens = Vec::new();
+        for op in self.iter_ops() {
+            match op {
+                Ok(Op::Code(opcode)) => tokens.push(match opcode.name() {
+                    Some(name) => name.to_string(),
+                    None => format!("0x{:02x}", opcode.number()),
+                }),
+                Ok(Op::Push(_, data)) => {
+                    tokens.push(format!("<{}>", hex::encode(data)))
+                }
+                Err(_) => {
+                    tokens.push("[error]".to_string());
+                    break;
+                }
+            }
+        }
+        tokens.join(" ")
+    }
+
+    /// Parse a script from its asm, see [`Script::asm`].
+    ///
+    /// Opcodes are parsed leniently: case-insensitive, and with or without
+    /// the `OP_` prefix, e.g. `OP_DUP`, `dup` or `Op_Dup`. Aliases like
+    /// `OP_TRUE` are accepted too. Data is pushed using the minimal push
+    /// opcode (see [`ScriptMut::put_pushdata`]); bare push opcodes like
+    /// `OP_PUSHDATA1` are rejected, as they'd be missing their data.
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     error::DataError,
+    /// #     hash::ShaRmd160,
+    /// #     script::Script,
+    /// # };
+    /// let hash = "00".repeat(20);
+    /// let asm = format!("dup hash160 <{hash}> OP_EQUALVERIFY CheckSig");
+    /// let script = Script::p2pkh(&ShaRmd160([0; 20]));
+    /// assert_eq!(Script::from_asm(&asm), Ok(script));
+    /// assert_eq!(Script::from_asm(""), Ok(Script::default()));
+    /// assert_eq!(
+    ///     Script::from_asm("OP_FOO"),
+    ///     Err(DataError::InvalidAsmToken("OP_FOO".to_string())),
+    /// );
+    /// ```
+    pub fn from_asm(asm: &str) -> Result<Script, DataError> {
+        let mut script = ScriptMut::default();
+        for token in asm.split_whitespace() {
+            let push_hex = token
+                .strip_prefix('<')
+                .and_then(|token| token.strip_suffix('>'));
+            match push_hex {
+                Some(push_hex) => {
+                    let data =
+                        hex::decode(push_hex).map_err(DataError::InvalidHex)?;
+                    script.put_pushdata(&data);
+                }
+                None => script.put_opcodes([parse_asm_opcode(token)?]),
+            }
+        }
+        Ok(script.freeze())
+    }
+
+    /// SHA-256 of the bytecode, as used by Electrum servers to index scripts.
+    ///
+    /// The hash is returned in the byte order produced by SHA-256. Electrum
+    /// APIs display and expect the "scripthash" as the hex of the reversed
+    /// bytes, which is what [`Hashed::hex_be`] of [`Sha256`] yields.
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::{Hashed, Sha256, ShaRmd160},
+    /// #     script::Script,
+    /// # };
+    /// let script = Script::p2pkh(&ShaRmd160([0; 20]));
+    /// let scripthash = script.electrum_scripthash();
+    /// assert_eq!(
+    ///     hex::encode(scripthash),
+    ///     "75def5fcc8bd1a6e9718970604e2728eb114750f6cfd2a2e2cca9d319679b8ac",
+    /// );
+    /// // Electrum representation is the reversed hex
+    /// assert_eq!(
+    ///     Sha256(scripthash).hex_be(),
+    ///     "acb87996319dca2c2e2afd6c0f7514b18e72e204069718976e1abdc8fcf5de75",
+    /// );
+    /// ```
+    pub fn electrum_scripthash(&self) -> [u8; 32] {
+        Sha256::digest(&self.0).into()
+    }
+
+    /// Whether this script is the output script of the given CashAddr
+    /// address, with the expected prefix `hrp` (e.g. "ecash").
+    ///
+    /// Invalid addresses, or addresses with another prefix, never match.
+    /// ```
+    /// # use bitcoinsuite_core::{hash::ShaRmd160, script::Script};
+    /// # use hex_literal::hex;
+    /// let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+    /// let addr = "ecash:qpm2qsznhks23z7629mms6s4cwef74vcwva87rkuu2";
+    /// assert!(Script::p2pkh(&hash).matches_cashaddr(addr, "ecash"));
+    /// assert!(!Script::p2sh(&hash).matches_cashaddr(addr, "ecash"));
+    /// assert!(!Script::p2pkh(&hash).matches_cashaddr(addr, "ectest"));
+    /// ```
+    pub fn matches_cashaddr(&self, addr: &str, hrp: &str) -> bool {
+        match decode_cashaddr(addr, hrp) {
+            Ok(variant) => variant.to_script() == *self,
+            Err(_) => false,
+        }
+    }
+
+    /// CashAddr address (with the prefix `hrp`, e.g. "ecash") of this script,
+    /// or [`None`] if it's not a P2PKH or P2SH script.
+    /// ```
+    /// # use bitcoinsuite_core::{hash::ShaRmd160, script::Script};
+    /// # use hex_literal::hex;
+    /// let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+    /// assert_eq!(
+    ///     Script::p2pkh(&hash).to_cashaddr("ecash").as_deref(),
+    ///     Some("ecash:qpm2qsznhks23z7629mms6s4cwef74vcwva87rkuu2"),
+    /// );
+    /// assert_eq!(
+    ///     Script::p2sh(&hash).to_cashaddr("ecash").as_deref(),
+    ///     Some("ecash:ppm2qsznhks23z7629mms6s4cwef74vcwv2zrv3l8h"),
+    /// );
+    /// assert_eq!(Script::default().to_cashaddr("ecash"), None);
+    /// ```
+    pub fn to_cashaddr(&self, hrp: &str) -> Option<String> {
+        encode_cashaddr(&ScriptVariant::from_script(self), hrp)
+    }
+
+    /// Legacy Base58Check address of this script, using `p2pkh_version` or
+    /// `p2sh_version` as version byte depending on the kind of script, or
+    /// [`None`] if it's not a P2PKH or P2SH script.
+    /// ```
+    /// # use bitcoinsuite_core::{hash::ShaRmd160, script::Script};
+    /// # use hex_literal::hex;
+    /// let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+    /// assert_eq!(
+    ///     Script::p2pkh(&hash).to_base58(0, 5).as_deref(),
+    ///     Some("1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu"),
+    /// );
+    /// assert_eq!(
+    ///     Script::p2sh(&hash).to_base58(0, 5).as_deref(),
+    ///     Some("3CWFddi6m4ndiGyKqzYvsFYagqDLPVMTzC"),
+    /// );
+    /// assert_eq!(Script::default().to_base58(0, 5), None);
+    /// ```
+    pub fn to_base58(
+        &self,
+        p2pkh_version: u8,
+        p2sh_version: u8,
+    ) -> Option<String> {
+        let variant = ScriptVariant::from_script(self);
+        let version = match variant {
+            ScriptVariant::P2PKH(_) => p2pkh_version,
+            ScriptVariant::P2SH(_) => p2sh_version,
+            _ => return None,
+        };
+        encode_base58_address(&variant, version)
+    }
+
+    /// Parse a legacy Base58Check address into its P2PKH or P2SH script,
+    /// depending on whether its version byte is `p2pkh_version` or
+    /// `p2sh_version`.
+    /// ```
+    /// # use bitcoinsuite_core::{hash::ShaRmd160, script::Script};
+    /// # use hex_literal::hex;
+    /// let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+    /// assert_eq!(
+    ///     Script::from_base58("3CWFddi6m4ndiGyKqzYvsFYagqDLPVMTzC", 0, 5),
+    ///     Ok(Script::p2sh(&hash)),
+    /// );
+    /// ```
+    pub fn from_base58(
+        addr: &str,
+        p2pkh_version: u8,
+        p2sh_version: u8,
+    ) -> Result<Script, Base58Error> {
+        let variant = decode_base58_address(addr, p2pkh_version, p2sh_version)?;
+        Ok(variant.to_script())
+    }
+
+    /// CashAddr address of this script on the given network, or [`None`] if
+    /// it's not a P2PKH or P2SH script, see [`Script::to_cashaddr`].
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::ShaRmd160, network::Network, script::Script,
+    /// # };
+    /// # use hex_literal::hex;
+    /// let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+    /// assert_eq!(
+    ///     Script::p2pkh(&hash).to_address(Network::Mainnet).as_deref(),
+    ///     Some("ecash:qpm2qsznhks23z7629mms6s4cwef74vcwva87rkuu2"),
+    /// );
+    /// ```
+    pub fn to_address(&self, network: Network) -> Option<String> {
+        self.to_cashaddr(network.cashaddr_prefix())
+    }
+
+    /// Legacy Base58Check address of this script on the given network, with
+    /// the version byte matching the kind of script, or [`None`] if it's not
+    /// a P2PKH or P2SH script.
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::ShaRmd160, network::Network, script::Script,
+    /// # };
+    /// # use hex_literal::hex;
+    /// let hash = ShaRmd160(hex!("76a04053bda0a88bda5177b86a15c3b29f559873"));
+    /// let script = Script::p2sh(&hash);
+    /// let addr = script.to_legacy_address(Network::Mainnet).unwrap();
+    /// assert_eq!(addr, "3CWFddi6m4ndiGyKqzYvsFYagqDLPVMTzC");
+    /// assert_eq!(
+    ///     Script::from_legacy_address(&addr, Network::Mainnet),
+    ///     Ok(script),
+    /// );
+    /// assert!(Script::from_legacy_address(&addr, Network::Testnet).is_err());
+    /// ```
+    pub fn to_legacy_address(&self, network: Network) -> Option<String> {
+        self.to_base58(network.p2pkh_version(), network.p2sh_version())
+    }
+
+    /// Parse a legacy Base58Check address of the given network into its P2PKH
+    /// or P2SH script, see [`Script::from_base58`].
+    pub fn from_legacy_address(
+        addr: &str,
+        network: Network,
+    ) -> Result<Script, Base58Error> {
+        Script::from_base58(
+            addr,
+            network.p2pkh_version(),
+            network.p2sh_version(),
+        )
+    }
+
     /// Whether this script is an OP_RETURN script.
     /// ```
     /// # use bitcoinsuite_core::script::Script;
@@ -161,6 +594,285 @@ impl Script {
         }
     }
 
+    /// Whether outputs with this script can never be spent and can
+    /// therefore be left out of the UTXO set.
+    ///
+    /// Applies the same rules as the node's `CScript::IsUnspendable`, so a
+    /// UTXO set built with this matches the node's:
+    /// 1. The script starts with [`OP_RETURN`], or
+    /// 2. the script is longer than [`MAX_SCRIPT_SIZE`] bytes.
+    ///
+    /// Other unspendable scripts, e.g. ones that fail to parse or exceed
+    /// [`MAX_OPS_PER_SCRIPT`], are kept in the UTXO set by the node, as that
+    /// is only checked when they're spent.
+    /// ```
+    /// # use bitcoinsuite_core::script::{opcode::*, Script, MAX_SCRIPT_SIZE};
+    /// assert!(Script::from_hex("6a").unwrap().is_provably_prunable());
+    /// let script = Script::from_hex("6a0401020304").unwrap();
+    /// assert!(script.is_provably_prunable());
+    /// assert!(!Script::from_hex("").unwrap().is_provably_prunable());
+    /// // OP_RETURN that's not at the start doesn't count
+    /// assert!(!Script::from_hex("516a").unwrap().is_provably_prunable());
+    /// // Too long
+    /// let script = Script::new(vec![OP_1::N; MAX_SCRIPT_SIZE + 1].into());
+    /// assert!(script.is_provably_prunable());
+    /// let script = Script::new(vec![OP_1::N; MAX_SCRIPT_SIZE].into());
+    /// assert!(!script.is_provably_prunable());
+    /// // Unparsable scripts aren't pruned
+    /// assert!(!Script::from_hex("0201").unwrap().is_provably_prunable());
+    /// ```
+    pub fn is_provably_prunable(&self) -> bool {
+        self.is_opreturn() || self.0.len() > MAX_SCRIPT_SIZE
+    }
+
+    /// Whether the script consists only of data pushes, after an optional
+    /// leading [`OP_RETURN`], e.g. to check that an OP_RETURN output only
+    /// carries data and doesn't contain any other opcodes.
+    ///
+    /// Pushes include the opcodes pushing constants, i.e. [`OP_0`],
+    /// [`OP_1NEGATE`] and [`OP_1`] to [`OP_16`], but not [`OP_RESERVED`].
+    /// Empty scripts are data-only, scripts that fail to parse are not.
+    /// ```
+    /// # use bitcoinsuite_core::script::Script;
+    /// assert!(Script::from_hex("6a04534c500000").unwrap().is_data_only());
+    /// assert!(Script::from_hex("6a").unwrap().is_data_only());
+    /// assert!(Script::from_hex("0102514f").unwrap().is_data_only());
+    /// // OP_EQUAL smuggled into the data
+    /// assert!(!Script::from_hex("6a010287").unwrap().is_data_only());
+    /// // Only a single leading OP_RETURN is allowed
+    /// assert!(!Script::from_hex("6a6a").unwrap().is_data_only());
+    /// assert!(!Script::from_hex("6a50").unwrap().is_data_only());
+    /// // Push exceeds the script
+    /// assert!(!Script::from_hex("6a0201").unwrap().is_data_only());
+    /// ```
+    pub fn is_data_only(&self) -> bool {
+        let mut ops = self.iter_ops().peekable();
+        if let Some(Ok(Op::Code(OP_RETURN))) = ops.peek() {
+            ops.next();
+        }
+        ops.all(|op| match op {
+            Ok(Op::Push(..)) => true,
+            Ok(Op::Code(opcode)) => {
+                opcode == OP_0
+                    || opcode == OP_1NEGATE
+                    || (OP_1..=OP_16).contains(&opcode)
+            }
+            Err(_) => false,
+        })
+    }
+
+    /// Normalized form of an OP_RETURN script, with all pushes removed and
+    /// only the non-push opcodes (e.g. [`OP_RETURN`] and [`OP_0`]) kept, e.g.
+    /// to group outputs by protocol structure.
+    ///
+    /// Scripts that aren't OP_RETURN scripts are returned unchanged. If the
+    /// script fails to parse, everything from the invalid op on is dropped.
+    /// ```
+    /// # use bitcoinsuite_core::script::Script;
+    /// let script = Script::from_hex("6a04534c50000051").unwrap();
+    /// assert_eq!(script.strip_opreturn_data().hex(), "6a0051");
+    /// let script = Script::from_hex("6a0201").unwrap();
+    /// assert_eq!(script.strip_opreturn_data().hex(), "6a");
+    /// let script = Script::from_hex("510201").unwrap();
+    /// assert_eq!(script.strip_opreturn_data(), script);
+    /// ```
+    pub fn strip_opreturn_data(&self) -> Script {
+        if !self.is_opreturn() {
+            return self.clone();
+        }
+        let mut script = BytesMut::new();
+        for op in self.iter_ops() {
+            match op {
+                Ok(op @ Op::Code(_)) => op.ser_to(&mut script),
+                Ok(Op::Push(..)) => {}
+                Err(_) => break,
+            }
+        }
+        Script(script.freeze())
+    }
+
+    /// Parse an OP_RETURN script following the LOKAD convention, see
+    /// [`Script::lokad`]. Returns the LOKAD ID and the pushed payload chunks,
+    /// or [`None`] if the script doesn't start with `OP_RETURN` followed by a
+    /// 4-byte push, or if any subsequent op isn't a push ([`OP_0`] counts as
+    /// an empty push).
+    /// ```
+    /// # use bitcoinsuite_core::script::Script;
+    /// use bytes::Bytes;
+    /// let payload = vec![Bytes::from(vec![1, 2]), Bytes::new()];
+    /// let script = Script::lokad(*b"SLP\0", &payload);
+    /// assert_eq!(script.parse_lokad(), Some((*b"SLP\0", payload)));
+    /// // 3-byte LOKAD ID
+    /// let script = Script::from_hex("6a03534c50").unwrap();
+    /// assert_eq!(script.parse_lokad(), None);
+    /// // OP_1 isn't a data push
+    /// let script = Script::from_hex("6a04534c500051").unwrap();
+    /// assert_eq!(script.parse_lokad(), None);
+    /// ```
+    pub fn parse_lokad(&self) -> Option<([u8; 4], Vec<Bytes>)> {
+        let mut ops = self.iter_ops();
+        if ops.next()?.ok()? != Op::Code(OP_RETURN) {
+            return None;
+        }
+        let lokad_id = match ops.next()?.ok()? {
+            Op::Push(_, data) => data.as_ref().try_into().ok()?,
+            _ => return None,
+        };
+        let mut payload = Vec::new();
+        for op in ops {
+            match op.ok()? {
+                Op::Push(_, data) => payload.push(data),
+                Op::Code(OP_0) => payload.push(Bytes::new()),
+                Op::Code(_) => return None,
+            }
+        }
+        Some((lokad_id, payload))
+    }
+
+    /// Human-readable label of the type of this script, e.g. to show it to
+    /// users next to an output, similar to the node's `scriptPubKey.type`.
+    ///
+    /// One of:
+    /// - `"p2pkh"`, `"p2sh"` and `"p2pk"`, see [`ScriptType`],
+    /// - `"nulldata"`: [`OP_RETURN`] followed only by pushes,
+    /// - `"multisig"`: bare `OP_m <pubkey>... OP_n OP_CHECKMULTISIG`,
+    /// - `"nonstandard"`: any other script.
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::ShaRmd160,
+    /// #     script::{PubKey, Script},
+    /// # };
+    /// let hash = ShaRmd160([7; 20]);
+    /// assert_eq!(Script::p2pkh(&hash).type_label(), "p2pkh");
+    /// assert_eq!(Script::p2sh(&hash).type_label(), "p2sh");
+    /// assert_eq!(Script::p2pk(&PubKey([2; 33])).type_label(), "p2pk");
+    /// let script = Script::from_hex("6a04534c5000").unwrap();
+    /// assert_eq!(script.type_label(), "nulldata");
+    /// // 1-of-2 bare multisig
+    /// let hex = format!("5121{}21{}52ae", "02".repeat(33), "03".repeat(33));
+    /// assert_eq!(Script::from_hex(&hex).unwrap().type_label(), "multisig");
+    /// // Number of pubkeys doesn't match n
+    /// let hex = format!("5121{}52ae", "02".repeat(33));
+    /// assert_eq!(Script::from_hex(&hex).unwrap().type_label(), "nonstandard");
+    /// // OP_RETURN followed by a non-push opcode
+    /// let script = Script::from_hex("6a0102ac").unwrap();
+    /// assert_eq!(script.type_label(), "nonstandard");
+    /// assert_eq!(Script::default().type_label(), "nonstandard");
+    /// ```
+    pub fn type_label(&self) -> &'static str {
+        match ScriptType::from_script(self) {
+            ScriptType::P2PKH => "p2pkh",
+            ScriptType::P2SH => "p2sh",
+            ScriptType::P2PK => "p2pk",
+            ScriptType::Other if self.is_opreturn() && self.is_data_only() => {
+                "nulldata"
+            }
+            ScriptType::Other if self.parse_multisig().is_some() => "multisig",
+            ScriptType::Other => "nonstandard",
+        }
+    }
+
+    /// Parse a bare multisig script `OP_m <pubkey>... OP_n OP_CHECKMULTISIG`,
+    /// returning the number of required signatures m, the pubkeys and the
+    /// number of pubkeys n.
+    ///
+    /// m and n must be encoded as [`OP_1`] to [`OP_16`], m must not exceed n
+    /// and there must be exactly n pubkeys, each either compressed or
+    /// uncompressed. Returns [`None`] if the script isn't a multisig script.
+    /// ```
+    /// # use bitcoinsuite_core::script::{
+    /// #     opcode::*, PubKey, PubKeyVariant, Script, ScriptMut,
+    /// #     UncompressedPubKey,
+    /// # };
+    /// let pubkeys = [PubKey([2; 33]), PubKey([3; 33])];
+    /// let script = ScriptMut::new()
+    ///     .op(OP_1)
+    ///     .push(pubkeys[0].as_slice())
+    ///     .push(pubkeys[1].as_slice())
+    ///     .ops([OP_2, OP_CHECKMULTISIG])
+    ///     .freeze();
+    /// assert_eq!(
+    ///     script.parse_multisig(),
+    ///     Some((
+    ///         1,
+    ///         vec![
+    ///             PubKeyVariant::Compressed(pubkeys[0]),
+    ///             PubKeyVariant::Compressed(pubkeys[1]),
+    ///         ],
+    ///         2,
+    ///     )),
+    /// );
+    /// // Number of pubkeys doesn't match n
+    /// let script = ScriptMut::new()
+    ///     .op(OP_1)
+    ///     .push(pubkeys[0].as_slice())
+    ///     .ops([OP_2, OP_CHECKMULTISIG])
+    ///     .freeze();
+    /// assert_eq!(script.parse_multisig(), None);
+    /// // m exceeds n
+    /// let script = ScriptMut::new()
+    ///     .op(OP_2)
+    ///     .push(pubkeys[0].as_slice())
+    ///     .ops([OP_1, OP_CHECKMULTISIG])
+    ///     .freeze();
+    /// assert_eq!(script.parse_multisig(), None);
+    /// // m isn't a small-integer opcode
+    /// let script = ScriptMut::new()
+    ///     .push(&[1])
+    ///     .push(pubkeys[0].as_slice())
+    ///     .ops([OP_1, OP_CHECKMULTISIG])
+    ///     .freeze();
+    /// assert_eq!(script.parse_multisig(), None);
+    /// // Uncompressed pubkeys can be mixed with compressed ones
+    /// let script = ScriptMut::new()
+    ///     .op(OP_1)
+    ///     .push(&[4; 65])
+    ///     .push(pubkeys[0].as_slice())
+    ///     .ops([OP_2, OP_CHECKMULTISIG])
+    ///     .freeze();
+    /// assert_eq!(script.type_label(), "multisig");
+    /// assert_eq!(
+    ///     script.parse_multisig(),
+    ///     Some((
+    ///         1,
+    ///         vec![
+    ///             PubKeyVariant::Uncompressed(UncompressedPubKey([4; 65])),
+    ///             PubKeyVariant::Compressed(pubkeys[0]),
+    ///         ],
+    ///         2,
+    ///     )),
+    /// );
+    /// ```
+    pub fn parse_multisig(&self) -> Option<(u8, Vec<PubKeyVariant>, u8)> {
+        let small_int = |op: Op| match op {
+            Op::Code(opcode) if (OP_1..=OP_16).contains(&opcode) => {
+                Some(opcode.number() - OP_1.number() + 1)
+            }
+            _ => None,
+        };
+        let mut ops = self.iter_ops().collect::<Result<Vec<_>, _>>().ok()?;
+        if ops.pop()? != Op::Code(OP_CHECKMULTISIG) {
+            return None;
+        }
+        let num_pubkeys = small_int(ops.pop()?)?;
+        let mut ops = ops.into_iter();
+        let num_required = small_int(ops.next()?)?;
+        let pubkeys = ops
+            .map(|op| match op {
+                Op::Push(_, data) => {
+                    PubKeyVariant::try_from(data.as_ref()).ok()
+                }
+                Op::Code(_) => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if num_required > num_pubkeys
+            || pubkeys.len() != usize::from(num_pubkeys)
+        {
+            return None;
+        }
+        Some((num_required, pubkeys, num_pubkeys))
+    }
+
     /// Iterator over the operations in this script.
     ///
     /// ```
@@ -209,6 +921,589 @@ impl Script {
     pub fn iter_ops(&self) -> ScriptOpIter {
         ScriptOpIter::new(self.0.clone())
     }
+
+    /// Iterator over only the data pushed by this script, skipping all
+    /// opcodes that don't push data, e.g. to parse OP_RETURN protocols.
+    ///
+    /// [`OP_0`] counts as an empty push, like in [`Script::parse_lokad`], but
+    /// the number opcodes [`OP_1NEGATE`] and [`OP_1`] to [`OP_16`] are skipped.
+    /// Parse errors are yielded as in [`Script::iter_ops`].
+    /// ```
+    /// # use bitcoinsuite_core::{error::DataError, script::Script};
+    /// # use hex_literal::hex;
+    /// let script = Script::new(hex!("6a0201020051874c0103").to_vec().into());
+    /// let mut iter = script.iter_pushes();
+    /// assert_eq!(iter.next(), Some(Ok(vec![1, 2].into())));
+    /// assert_eq!(iter.next(), Some(Ok(vec![].into())));
+    /// assert_eq!(iter.next(), Some(Ok(vec![3].into())));
+    /// assert_eq!(iter.next(), None);
+    ///
+    /// let script = Script::new(hex!("6a0201").to_vec().into());
+    /// let mut iter = script.iter_pushes();
+    /// assert_eq!(
+    ///     iter.next(),
+    ///     Some(Err(DataError::InvalidLength {
+    ///         expected: 2,
+    ///         actual: 1,
+    ///     })),
+    /// );
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter_pushes(
+        &self,
+    ) -> impl Iterator<Item = Result<Bytes, DataError>> {
+        self.iter_ops().filter_map(|op| match op {
+            Ok(Op::Push(_, data)) => Some(Ok(data)),
+            Ok(Op::Code(OP_0)) => Some(Ok(Bytes::new())),
+            Ok(Op::Code(_)) => None,
+            Err(err) => Some(Err(err)),
+        })
+    }
+
+    /// Iterator over the operations in this script, failing on opcodes that
+    /// aren't defined by the node, e.g. for validation.
+    ///
+    /// [`Script::iter_ops`] instead yields undefined opcodes as [`Op::Code`].
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     script::{opcode::*, Op, Script},
+    /// #     error::DataError,
+    /// # };
+    /// # use hex_literal::hex;
+    /// let script = Script::new(hex!("51bcbd51").to_vec().into());
+    /// let mut iter = script.iter_ops_strict();
+    /// assert_eq!(iter.next(), Some(Ok(Op::Code(OP_1))));
+    /// assert_eq!(iter.next(), Some(Ok(Op::Code(OP_REVERSEBYTES))));
+    /// assert_eq!(iter.next(), Some(Err(DataError::InvalidOpcode(0xbd))));
+    /// assert_eq!(iter.next(), None);
+    ///
+    /// // The lenient iterator accepts the undefined opcode
+    /// let mut iter = script.iter_ops();
+    /// assert_eq!(iter.nth(2), Some(Ok(Op::Code(Opcode(0xbd)))));
+    /// ```
+    pub fn iter_ops_strict(&self) -> ScriptOpStrictIter {
+        ScriptOpStrictIter::new(self.0.clone())
+    }
+
+    /// Iterator over the operations in this script, borrowing the pushed data
+    /// from the script instead of slicing its [`Bytes`], see [`OpRef`].
+    ///
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     error::DataError,
+    /// #     script::{opcode::*, OpRef, Script},
+    /// # };
+    /// # use hex_literal::hex;
+    /// let script = Script::new(hex!("6a0301020387004c05ab").to_vec().into());
+    /// let mut iter = script.iter_ops_ref();
+    /// assert_eq!(iter.next(), Some(Ok(OpRef::Code(OP_RETURN))));
+    /// assert_eq!(iter.next(), Some(Ok(OpRef::Push(Opcode(3), &[1, 2, 3]))));
+    /// assert_eq!(iter.next(), Some(Ok(OpRef::Code(OP_EQUAL))));
+    /// assert_eq!(iter.next(), Some(Ok(OpRef::Code(OP_0))));
+    /// assert_eq!(
+    ///     iter.next(),
+    ///     Some(Err(DataError::InvalidLength {
+    ///         expected: 5,
+    ///         actual: 1,
+    ///     })),
+    /// );
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter_ops_ref(&self) -> ScriptOpRefIter<'_> {
+        ScriptOpRefIter::new(&self.0)
+    }
+
+    /// Iterator over the operations in this script, yielding the byte offset
+    /// at which each op starts alongside the op.
+    ///
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     script::{opcode::*, Op, Script},
+    /// #     error::DataError,
+    /// # };
+    /// # use hex_literal::hex;
+    /// let script = Script::new(hex!("6a0301020387004c05ab").to_vec().into());
+    /// let mut iter = script.iter_ops_indexed();
+    /// assert_eq!(iter.next(), Some((0, Ok(Op::Code(OP_RETURN)))));
+    /// assert_eq!(
+    ///     iter.next(),
+    ///     Some((1, Ok(Op::Push(Opcode(3), vec![1, 2, 3].into())))),
+    /// );
+    /// assert_eq!(iter.next(), Some((5, Ok(Op::Code(OP_EQUAL)))));
+    /// assert_eq!(iter.next(), Some((6, Ok(Op::Code(OP_0)))));
+    /// assert_eq!(
+    ///     iter.next(),
+    ///     Some((
+    ///         7,
+    ///         Err(DataError::InvalidLength {
+    ///             expected: 5,
+    ///             actual: 1,
+    ///         }),
+    ///     )),
+    /// );
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter_ops_indexed(&self) -> ScriptOpIndexedIter {
+        ScriptOpIndexedIter::new(self.0.clone())
+    }
+
+    /// Eagerly parse all the ops of the given bytecode.
+    ///
+    /// Returns either all the ops, or the ops parsed until the first error
+    /// together with that error. Never panics, which makes it a convenient
+    /// entrypoint for fuzzing.
+    ///
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     script::{opcode::*, Op, Script},
+    /// #     error::DataError,
+    /// # };
+    /// # use hex_literal::hex;
+    /// assert_eq!(
+    ///     Script::try_parse_all(&hex!("6a020102")),
+    ///     Ok(vec![
+    ///         Op::Code(OP_RETURN),
+    ///         Op::Push(Opcode(2), vec![1, 2].into()),
+    ///     ]),
+    /// );
+    /// assert_eq!(
+    ///     Script::try_parse_all(&hex!("6a0201024d")),
+    ///     Err((
+    ///         vec![
+    ///             Op::Code(OP_RETURN),
+    ///             Op::Push(Opcode(2), vec![1, 2].into()),
+    ///         ],
+    ///         DataError::UnexpectedEnd {
+    ///             expected: 2,
+    ///             actual: 0,
+    ///         },
+    ///     )),
+    /// );
+    /// assert_eq!(Script::try_parse_all(&[]), Ok(vec![]));
+    /// ```
+    pub fn try_parse_all(
+        bytes: &[u8],
+    ) -> Result<Vec<Op>, (Vec<Op>, DataError)> {
+        let mut ops = Vec::new();
+        for op in ScriptOpIter::new(Bytes::copy_from_slice(bytes)) {
+            match op {
+                Ok(op) => ops.push(op),
+                Err(err) => return Err((ops, err)),
+            }
+        }
+        Ok(ops)
+    }
+
+    /// Concatenate the data of all the pushes in this script, in order.
+    ///
+    /// [`OP_0`] counts as an empty push. Any other opcode that doesn't push
+    /// bytes onto the stack (including [`OP_1NEGATE`] and [`OP_1`] to
+    /// [`OP_16`]) results in [`DataError::UnexpectedOpcode`].
+    ///
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     script::{opcode::*, Script},
+    /// #     error::DataError,
+    /// # };
+    /// # use hex_literal::hex;
+    /// let script = hex!("0201020004030405064c0107");
+    /// let script = Script::new(script.to_vec().into());
+    /// assert_eq!(
+    ///     script.pushed_data(),
+    ///     Ok(hex!("01020304050607").to_vec().into()),
+    /// );
+    /// assert_eq!(Script::default().pushed_data(), Ok(vec![].into()));
+    /// assert_eq!(
+    ///     Script::new(hex!("6a020102").to_vec().into()).pushed_data(),
+    ///     Err(DataError::UnexpectedOpcode(OP_RETURN)),
+    /// );
+    /// ```
+    pub fn pushed_data(&self) -> Result<Bytes, DataError> {
+        let mut data = BytesMut::new();
+        for op in self.iter_ops() {
+            match op? {
+                Op::Push(_, push) => data.extend_from_slice(&push),
+                Op::Code(OP_0) => {}
+                Op::Code(opcode) => {
+                    return Err(DataError::UnexpectedOpcode(opcode));
+                }
+            }
+        }
+        Ok(data.freeze())
+    }
+
+    /// Whether this script performs the same ops as `other`, treating pushes
+    /// of the same bytes as equal, regardless of how they are encoded.
+    ///
+    /// Unlike `==`, which compares the bytecode, this considers e.g. a direct
+    /// push, `OP_PUSHDATA1` and `OP_PUSHDATA2` of the same data to be equal,
+    /// and also the opcodes pushing constants, e.g. [`OP_0`] and an empty
+    /// `OP_PUSHDATA1`, or [`OP_1`] and a direct push of `[0x01]` (see
+    /// [`OpRef::pushed_bytes`]).
+    ///
+    /// Scripts that fail to parse are only equal to identical bytecode.
+    /// ```
+    /// # use bitcoinsuite_core::script::Script;
+    /// # use hex_literal::hex;
+    /// let direct = Script::new(hex!("6a03010203").to_vec().into());
+    /// let pushdata1 = Script::new(hex!("6a4c03010203").to_vec().into());
+    /// assert_ne!(direct, pushdata1);
+    /// assert!(direct.semantic_eq(&pushdata1));
+    /// let op_1 = Script::new(hex!("51").to_vec().into());
+    /// assert!(op_1.semantic_eq(&Script::new(hex!("0101").to_vec().into())));
+    /// assert!(!op_1.semantic_eq(&Script::new(hex!("0102").to_vec().into())));
+    /// ```
+    pub fn semantic_eq(&self, other: &Script) -> bool {
+        if self == other {
+            return true;
+        }
+        let mut ops = self.iter_ops_ref();
+        let mut other_ops = other.iter_ops_ref();
+        loop {
+            let (op, other_op) = match (ops.next(), other_ops.next()) {
+                (None, None) => return true,
+                (Some(Ok(op)), Some(Ok(other_op))) => (op, other_op),
+                // Different number of ops, or parse error
+                _ => return false,
+            };
+            let is_eq = match (op.pushed_bytes(), other_op.pushed_bytes()) {
+                (Some(data), Some(other_data)) => data == other_data,
+                (None, None) => op.opcode() == other_op.opcode(),
+                _ => false,
+            };
+            if !is_eq {
+                return false;
+            }
+        }
+    }
+
+    /// Whether this script is within the node's size limits, i.e. at most
+    /// [`MAX_SCRIPT_SIZE`] bytes and at most [`MAX_OPS_PER_SCRIPT`] non-push
+    /// opcodes (opcodes above [`OP_16`]). Scripts that fail to parse are
+    /// never within the limits.
+    ///
+    /// ```
+    /// # use bitcoinsuite_core::script::{
+    /// #     opcode::*, Script, MAX_OPS_PER_SCRIPT, MAX_SCRIPT_SIZE,
+    /// # };
+    /// let script = Script::new(vec![OP_DUP::N; MAX_OPS_PER_SCRIPT].into());
+    /// assert!(script.is_standard_size());
+    /// let bytecode = vec![OP_DUP::N; MAX_OPS_PER_SCRIPT + 1];
+    /// let script = Script::new(bytecode.into());
+    /// assert!(!script.is_standard_size());
+    /// // Pushes don't count towards the op limit
+    /// let script = Script::new(vec![OP_1::N; MAX_SCRIPT_SIZE].into());
+    /// assert!(script.is_standard_size());
+    /// let script = Script::new(vec![OP_1::N; MAX_SCRIPT_SIZE + 1].into());
+    /// assert!(!script.is_standard_size());
+    /// ```
+    pub fn is_standard_size(&self) -> bool {
+        if self.0.len() > MAX_SCRIPT_SIZE {
+            return false;
+        }
+        let mut num_ops = 0;
+        for op in self.iter_ops() {
+            match op {
+                Ok(op) if op.opcode() > OP_16 => num_ops += 1,
+                Ok(_) => {}
+                Err(_) => return false,
+            }
+            if num_ops > MAX_OPS_PER_SCRIPT {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Number of ops in this script, both pushes and other opcodes.
+    ///
+    /// Equivalent to counting [`Script::iter_ops`], but this only walks the
+    /// size prefixes of pushes without slicing out the pushed data, e.g. for
+    /// cheap pre-checks of scripts. Fails with the same error as
+    /// [`Script::iter_ops`] if the script fails to parse.
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     error::DataError,
+    /// #     hash::ShaRmd160,
+    /// #     script::Script,
+    /// # };
+    /// let script = Script::p2pkh(&ShaRmd160([0; 20]));
+    /// assert_eq!(script.count_ops(), Ok(5));
+    /// let script = Script::from_hex("514c02ab").unwrap();
+    /// assert_eq!(
+    ///     script.count_ops(),
+    ///     Err(DataError::InvalidLength {
+    ///         expected: 2,
+    ///         actual: 1,
+    ///     }),
+    /// );
+    /// ```
+    pub fn count_ops(&self) -> Result<usize, DataError> {
+        let mut remaining = self.0.as_ref();
+        let mut num_ops = 0;
+        while !remaining.is_empty() {
+            OpRef::read_op(&mut remaining)?;
+            num_ops += 1;
+        }
+        Ok(num_ops)
+    }
+
+    /// Estimated cost of executing this script, as the sum of the
+    /// [`Opcode::execution_cost`] of its ops plus [`push_data_cost`] of any
+    /// pushed data. Ops after a parse error aren't counted.
+    /// ```
+    /// # use bitcoinsuite_core::{hash::ShaRmd160, script::Script};
+    /// let script = Script::p2pkh(&ShaRmd160([0; 20]));
+    /// // OP_DUP + OP_HASH160 + <20 bytes> + OP_EQUALVERIFY + OP_CHECKSIG
+    /// assert_eq!(script.execution_cost(), 1 + 10 + (1 + 1) + 1 + 100);
+    /// // Invalid push at the end
+    /// let script = Script::from_hex("76a9acac4c").unwrap();
+    /// assert_eq!(script.execution_cost(), 1 + 10 + 100 + 100);
+    /// ```
+    pub fn execution_cost(&self) -> u64 {
+        let mut cost = 0;
+        for op in self.iter_ops() {
+            let op = match op {
+                Ok(op) => op,
+                Err(_) => break,
+            };
+            cost += op.opcode().execution_cost();
+            if let Op::Push(_, data) = &op {
+                cost += push_data_cost(data.len());
+            }
+        }
+        cost
+    }
+
+    /// Number of signature checks in this script, as counted by the node for
+    /// its sigop limits.
+    ///
+    /// [`OP_CHECKSIG`], [`OP_CHECKSIGVERIFY`], [`OP_CHECKDATASIG`] and
+    /// [`OP_CHECKDATASIGVERIFY`] count as 1 each. [`OP_CHECKMULTISIG`] and
+    /// [`OP_CHECKMULTISIGVERIFY`] count as the number of pubkeys if `accurate`
+    /// is set and they're preceded by [`OP_1`] to [`OP_16`], and as
+    /// [`MAX_PUBKEYS_PER_MULTISIG`] otherwise. Counting stops at the first
+    /// parse error, like in the node.
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::ShaRmd160,
+    /// #     script::{opcode::*, Script},
+    /// # };
+    /// let script = Script::p2pkh(&ShaRmd160([7; 20]));
+    /// assert_eq!(script.sigop_count(true), 1);
+    /// // <sig> <msg> <pubkey> OP_CHECKDATASIGVERIFY <pubkey> OP_CHECKSIG
+    /// let script = Script::from_hex("000000bb00ac").unwrap();
+    /// assert_eq!(script.sigop_count(true), 2);
+    /// // OP_1 <pubkey> <pubkey> OP_2 OP_CHECKMULTISIG
+    /// let script = Script::from_hex("51000052ae").unwrap();
+    /// assert_eq!(script.sigop_count(true), 2);
+    /// assert_eq!(script.sigop_count(false), 20);
+    /// // Invalid push after OP_CHECKSIG
+    /// let script = Script::from_hex("ac4cac").unwrap();
+    /// assert_eq!(script.sigop_count(true), 1);
+    /// ```
+    pub fn sigop_count(&self, accurate: bool) -> u32 {
+        let mut num_sigops = 0;
+        let mut last_opcode = None;
+        for op in self.iter_ops_ref() {
+            let opcode = match op {
+                Ok(op) => op.opcode(),
+                Err(_) => break,
+            };
+            match opcode {
+                OP_CHECKSIG
+                | OP_CHECKSIGVERIFY
+                | OP_CHECKDATASIG
+                | OP_CHECKDATASIGVERIFY => num_sigops += 1,
+                OP_CHECKMULTISIG | OP_CHECKMULTISIGVERIFY => {
+                    num_sigops += match last_opcode {
+                        Some(Opcode(n @ OP_1::N..=OP_16::N)) if accurate => {
+                            u32::from(n - OP_1::N + 1)
+                        }
+                        _ => MAX_PUBKEYS_PER_MULTISIG,
+                    };
+                }
+                _ => {}
+            }
+            last_opcode = Some(opcode);
+        }
+        num_sigops
+    }
+
+    /// Estimated size of the scriptSig spending an output with this script,
+    /// for fee estimation, or [`None`] if the script isn't a recognized
+    /// template (see [`ScriptVariant::from_script`]).
+    ///
+    /// Signatures are estimated as [`ESTIMATED_SIG_SIZE`] bytes. P2PKH assumes
+    /// a compressed pubkey. P2SH returns [`None`], as the redeem script isn't
+    /// known from the output; use
+    /// [`Script::estimated_p2sh_multisig_spend_size`] for P2SH multisig.
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::ShaRmd160,
+    /// #     script::{PubKey, Script},
+    /// # };
+    /// let script = Script::p2pkh(&ShaRmd160([7; 20]));
+    /// assert_eq!(script.estimated_spend_size(), Some(107));
+    /// let script = Script::p2pk(&PubKey([2; 33]));
+    /// assert_eq!(script.estimated_spend_size(), Some(73));
+    /// let script = Script::p2sh(&ShaRmd160([7; 20]));
+    /// assert_eq!(script.estimated_spend_size(), None);
+    /// assert_eq!(Script::default().estimated_spend_size(), None);
+    /// ```
+    pub fn estimated_spend_size(&self) -> Option<usize> {
+        match ScriptVariant::from_script(self) {
+            // <sig> <pubkey>
+            ScriptVariant::P2PKH(_) => {
+                Some(1 + ESTIMATED_SIG_SIZE + 1 + PubKey::SIZE)
+            }
+            // <sig>
+            ScriptVariant::P2PK(_) => Some(1 + ESTIMATED_SIG_SIZE),
+            ScriptVariant::P2SH(_) | ScriptVariant::Other(_) => None,
+        }
+    }
+
+    /// Estimated size of the scriptSig spending a P2SH output wrapping an
+    /// `m`-of-`n` multisig redeem script with compressed pubkeys, see
+    /// [`Script::p2sh_multisig_sig`].
+    /// ```
+    /// # use bitcoinsuite_core::script::Script;
+    /// // OP_0 <sig> <sig> OP_PUSHDATA1 <redeem script>, with redeem script
+    /// // OP_2 <pubkey> <pubkey> <pubkey> OP_3 OP_CHECKMULTISIG
+    /// assert_eq!(
+    ///     Script::estimated_p2sh_multisig_spend_size(2, 3),
+    ///     1 + 2 * 73 + 2 + 105,
+    /// );
+    /// ```
+    pub fn estimated_p2sh_multisig_spend_size(m: usize, n: usize) -> usize {
+        // OP_m <pubkey>... OP_n OP_CHECKMULTISIG
+        let redeem_size = 1 + n * (1 + PubKey::SIZE) + 2;
+        let redeem_push_size = match redeem_size {
+            0x00..=0x4b => 1,
+            0x4c..=0xff => 2,
+            _ => 3,
+        };
+        1 + m * (1 + ESTIMATED_SIG_SIZE) + redeem_push_size + redeem_size
+    }
+
+    /// Set of the distinct opcodes in this script, e.g. to quickly check
+    /// whether a script uses [`OP_CHECKMULTISIG`] without matching its whole
+    /// structure. Pushes are included by their push opcode.
+    ///
+    /// Parsing stops at the first parse error, so a script with an invalid op
+    /// yields only the opcodes before it.
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::ShaRmd160,
+    /// #     script::{opcode::*, Script},
+    /// # };
+    /// let script = Script::p2pkh(&ShaRmd160([7; 20]));
+    /// assert_eq!(
+    ///     script.opcode_set().into_iter().collect::<Vec<_>>(),
+    ///     [Opcode(20), OP_DUP, OP_EQUALVERIFY, OP_HASH160, OP_CHECKSIG],
+    /// );
+    /// // Duplicate opcodes, and an invalid push at the end
+    /// let script = Script::from_hex("acacae4c").unwrap();
+    /// assert_eq!(
+    ///     script.opcode_set().into_iter().collect::<Vec<_>>(),
+    ///     [OP_CHECKSIG, OP_CHECKMULTISIG],
+    /// );
+    /// assert!(Script::default().opcode_set().is_empty());
+    /// ```
+    pub fn opcode_set(&self) -> BTreeSet<Opcode> {
+        self.iter_ops_ref()
+            .map_while(|op| op.ok())
+            .map(|op| op.opcode())
+            .collect()
+    }
+
+    /// Whether the opcodes of the first ops of this script match `pattern`,
+    /// ignoring any pushed data. Returns `false` if the script fails to parse
+    /// before the pattern is matched.
+    ///
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::ShaRmd160,
+    /// #     script::{opcode::*, Script},
+    /// # };
+    /// let script = Script::p2pkh(&ShaRmd160([7; 20]));
+    /// assert!(script.starts_with_ops(&[OP_DUP, OP_HASH160, Opcode(20)]));
+    /// assert!(script.starts_with_ops(&[]));
+    /// assert!(!script.starts_with_ops(&[OP_HASH160]));
+    /// assert!(!Script::new(vec![0x76].into()).starts_with_ops(&[OP_DUP; 2]));
+    /// ```
+    pub fn starts_with_ops(&self, pattern: &[Opcode]) -> bool {
+        let mut ops = self.iter_ops();
+        for &expected in pattern {
+            match ops.next() {
+                Some(Ok(op)) if op.opcode() == expected => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Whether the opcodes of the last ops of this script match `pattern`,
+    /// ignoring any pushed data. Returns `false` if the script fails to
+    /// parse.
+    ///
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::ShaRmd160,
+    /// #     script::{opcode::*, Script},
+    /// # };
+    /// let script = Script::p2pkh(&ShaRmd160([7; 20]));
+    /// assert!(script.ends_with_ops(&[OP_EQUALVERIFY, OP_CHECKSIG]));
+    /// assert!(script.ends_with_ops(&[]));
+    /// assert!(!script.ends_with_ops(&[OP_EQUAL]));
+    /// let script = Script::p2sh(&ShaRmd160([7; 20]));
+    /// assert!(script.ends_with_ops(&[OP_HASH160, Opcode(20), OP_EQUAL]));
+    /// // Trailing invalid push
+    /// assert!(!Script::new(vec![0x87, 0x02].into()).ends_with_ops(&[]));
+    /// ```
+    pub fn ends_with_ops(&self, pattern: &[Opcode]) -> bool {
+        let opcodes = self
+            .iter_ops()
+            .map(|op| op.map(|op| op.opcode()))
+            .collect::<Result<Vec<_>, _>>();
+        match opcodes {
+            Ok(opcodes) => opcodes.ends_with(pattern),
+            Err(_) => false,
+        }
+    }
+}
+
+impl FromStr for Script {
+    type Err = DataError;
+
+    /// Parse the asm of a script, see [`Script::from_asm`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Script::from_asm(s)
+    }
+}
+
+fn parse_asm_opcode(token: &str) -> Result<Opcode, DataError> {
+    let invalid = || DataError::InvalidAsmToken(token.to_string());
+    let opcode = match token.strip_prefix("0x") {
+        Some(hex) if hex.len() == 2 => {
+            Opcode(u8::from_str_radix(hex, 16).map_err(|_| invalid())?)
+        }
+        Some(_) => return Err(invalid()),
+        None => {
+            let name = token.to_ascii_uppercase();
+            let name = if name.starts_with("OP_") {
+                name
+            } else {
+                format!("OP_{name}")
+            };
+            Opcode::from_name(&name).ok_or_else(invalid)?
+        }
+    };
+    // Push opcodes need data, which has to be given as <hex>
+    if (0x01..=OP_PUSHDATA4::N).contains(&opcode.number()) {
+        return Err(invalid());
+    }
+    Ok(opcode)
 }
 
 impl AsRef<[u8]> for Script {
@@ -221,15 +1516,382 @@ impl BitcoinSer for Script {
     fn ser_to<S: BitcoinSerializer>(&self, bytes: &mut S) {
         self.0.ser_to(bytes)
     }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        Ok(Script(Bytes::deser(data)?))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{script::Script, ser::BitcoinSer};
+    use alloc::{format, string::ToString, vec, vec::Vec};
+
+    use bytes::Bytes;
+
+    use crate::{
+        error::DataError,
+        script::{
+            opcode::*, Op, PubKey, Script, ScriptMut, MAX_OPS_PER_SCRIPT,
+            MAX_SCRIPT_SIZE,
+        },
+        ser::BitcoinSer,
+    };
 
     fn verify_ser(a: Script, b: &[u8]) {
         assert_eq!(a.ser().as_ref(), b);
         assert_eq!(a.ser_len(), b.len());
+        assert_eq!(a.checked_ser_len(), Some(b.len()));
+    }
+
+    #[test]
+    fn test_try_parse_all_never_panics() {
+        // Every possible opcode followed by a few possible tails
+        let tails: [&[u8]; 4] = [&[], &[0x00], &[0xff, 0xff], &[0x02, 1, 2]];
+        for opcode in 0..=0xffu8 {
+            for tail in tails {
+                let bytecode = [[opcode].as_ref(), tail].concat();
+                let script = Script::new(bytecode.clone().into());
+                let expected = script.iter_ops().collect::<Vec<_>>();
+                let actual = match Script::try_parse_all(&bytecode) {
+                    Ok(ops) => ops.into_iter().map(Ok).collect::<Vec<_>>(),
+                    Err((ops, err)) => {
+                        let ops = ops.into_iter().map(Ok);
+                        ops.chain([Err(err)]).collect::<Vec<_>>()
+                    }
+                };
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_asm_spellings() {
+        let expected = Script::new(vec![OP_DUP::N].into());
+        for spelling in ["OP_DUP", "DUP", "dup", "op_dup", "Op_Dup", "oP_dUp"] {
+            assert_eq!(spelling.parse::<Script>(), Ok(expected.clone()));
+            assert_eq!(Script::from_asm(spelling), Ok(expected.clone()));
+        }
+        // Aliases and numbers
+        let cases = [
+            ("OP_TRUE", OP_1),
+            ("true", OP_1),
+            ("1", OP_1),
+            ("op_16", OP_16),
+            ("0", OP_0),
+            ("FALSE", OP_0),
+            ("nop2", OP_CHECKLOCKTIMEVERIFY),
+            ("1add", OP_1ADD),
+            ("0xbd", Opcode(0xbd)),
+            ("0xac", OP_CHECKSIG),
+        ];
+        for (spelling, opcode) in cases {
+            assert_eq!(
+                Script::from_asm(spelling),
+                Ok(Script::new(vec![opcode.number()].into())),
+                "{spelling}",
+            );
+        }
+        // Whitespace is flexible
+        assert_eq!(
+            Script::from_asm("  dup\n\thash160  <> "),
+            Ok(Script::new(vec![OP_DUP::N, OP_HASH160::N, OP_0::N].into())),
+        );
+        for invalid in [
+            "OP_",
+            "DUPP",
+            "OPDUP",
+            "0x",
+            "0xbdbd",
+            "0xzz",
+            "[error]",
+            "OP_PUSHDATA1",
+            "0x14",
+            "<ab",
+            "ab>",
+        ] {
+            assert_eq!(
+                Script::from_asm(invalid),
+                Err(DataError::InvalidAsmToken(invalid.to_string())),
+                "{invalid}",
+            );
+        }
+        assert_eq!(
+            Script::from_asm("<abc>"),
+            Err(DataError::InvalidHex(hex::FromHexError::OddLength)),
+        );
+    }
+
+    #[test]
+    fn test_asm_roundtrip() {
+        let mut bytecode = vec![OP_RETURN::N, OP_0::N, 0x4c, 0x4c];
+        bytecode.extend([0xee; 0x4c]);
+        bytecode.extend([OP_1NEGATE::N, OP_16::N, 0xff, OP_REVERSEBYTES::N]);
+        let script = Script::new(bytecode.into());
+        let asm = script.asm();
+        assert_eq!(
+            asm,
+            format!(
+                "OP_RETURN OP_0 <{}> OP_1NEGATE OP_16 0xff OP_REVERSEBYTES",
+                "ee".repeat(0x4c),
+            ),
+        );
+        assert_eq!(Script::from_asm(&asm), Ok(script));
+        // Always emits canonical names, even if parsed from other spellings
+        let script = Script::from_asm("true nop3 checkdatasig").unwrap();
+        assert_eq!(script.asm(), "OP_1 OP_CHECKSEQUENCEVERIFY OP_CHECKDATASIG");
+        // Non-minimal pushes are normalized
+        let script = Script::from_hex("4c0101").unwrap();
+        assert_eq!(script.asm(), "<01>");
+        assert_eq!(Script::from_asm(&script.asm()), Script::from_hex("0101"));
+    }
+
+    #[test]
+    fn test_iter_ops_preserves_push_opcodes() {
+        // Non-minimal pushes of every kind, which must not be normalized
+        let script = Script::new(Bytes::from_static(&[
+            0x4c, 0x01, 0xab, // OP_PUSHDATA1 of 1 byte
+            0x4d, 0x02, 0x00, 0x01, 0x02, // OP_PUSHDATA2 of 2 bytes
+            0x4e, 0x00, 0x00, 0x00, 0x00, // OP_PUSHDATA4 of 0 bytes
+            0x4c, 0x00, // OP_PUSHDATA1 of 0 bytes instead of OP_0
+            0x01, 0x05, // Direct push of [5] instead of OP_5
+            0x00, 0x51, 0x87, // OP_0 OP_1 OP_EQUAL
+        ]));
+        let ops = script.iter_ops().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            ops,
+            vec![
+                Op::Push(OP_PUSHDATA1, Bytes::from_static(&[0xab])),
+                Op::Push(OP_PUSHDATA2, Bytes::from_static(&[1, 2])),
+                Op::Push(OP_PUSHDATA4, Bytes::new()),
+                Op::Push(OP_PUSHDATA1, Bytes::new()),
+                Op::Push(Opcode(1), Bytes::from_static(&[5])),
+                Op::Code(OP_0),
+                Op::Code(OP_1),
+                Op::Code(OP_EQUAL),
+            ],
+        );
+        // Re-serializing the ops reproduces the exact bytecode
+        let reser = ops.iter().flat_map(|op| op.ser()).collect::<Vec<_>>();
+        assert_eq!(reser.as_slice(), script.bytecode().as_ref());
+        // Same for iter_ops_ref
+        for (op, op_ref) in ops.iter().zip(script.iter_ops_ref()) {
+            assert_eq!(op.opcode(), op_ref.unwrap().opcode());
+        }
+    }
+
+    #[test]
+    fn test_count_ops() {
+        // Every possible opcode followed by a few possible tails
+        let tails: [&[u8]; 6] = [
+            &[],
+            &[0x00],
+            &[0xff, 0xff],
+            &[0x02, 1, 2],
+            &[0x02, 1, 2, 0x51],
+            &[0x01, 0, 0, 0, 0x51],
+        ];
+        for opcode in 0..=0xffu8 {
+            for tail in tails {
+                let bytecode = [[opcode].as_ref(), tail].concat();
+                let script = Script::new(bytecode.into());
+                let expected = script
+                    .iter_ops()
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(|ops| ops.len());
+                assert_eq!(script.count_ops(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_semantic_eq() {
+        let script = |hex: &str| Script::from_hex(hex).unwrap();
+        let equal_groups: [&[&str]; 7] = [
+            // Pushes of [1, 2] and OP_RETURN
+            &["0201026a", "4c0201026a", "4d020001026a", "4e0200000001026a"],
+            // Empty pushes
+            &["00", "4c00", "4d0000", "4e00000000"],
+            // Small ints
+            &["4f", "0181", "4c0181"],
+            &["51", "0101", "4d010001"],
+            &["60", "0110", "4e0100000010"],
+            // Scripts that fail to parse
+            &["4c"],
+            &["0201"],
+        ];
+        for (group_idx, group) in equal_groups.iter().enumerate() {
+            for (other_idx, other_group) in equal_groups.iter().enumerate() {
+                for &a in group.iter() {
+                    for &b in other_group.iter() {
+                        assert_eq!(
+                            script(a).semantic_eq(&script(b)),
+                            group_idx == other_idx,
+                            "{a} vs. {b}",
+                        );
+                    }
+                }
+            }
+        }
+        // Non-push opcodes aren't pushes of their number
+        assert!(!script("50").semantic_eq(&script("0150")));
+        assert!(!script("76").semantic_eq(&script("0176")));
+        // Trailing ops have to match too
+        assert!(!script("0101").semantic_eq(&script("010176")));
+    }
+
+    #[test]
+    fn test_starts_ends_with_ops() {
+        // OP_RETURN <push 2> OP_0 <PUSHDATA1 1>
+        let script = Script::new(vec![0x6a, 2, 1, 2, 0x00, 0x4c, 1, 3].into());
+        let opcodes = [OP_RETURN, Opcode(2), OP_0, OP_PUSHDATA1];
+        for len in 0..=opcodes.len() {
+            assert!(script.starts_with_ops(&opcodes[..len]));
+            assert!(script.ends_with_ops(&opcodes[opcodes.len() - len..]));
+        }
+        let mut longer = vec![OP_RETURN];
+        longer.extend(opcodes);
+        assert!(!script.starts_with_ops(&longer));
+        assert!(!script.ends_with_ops(&longer));
+        assert!(!script.starts_with_ops(&[OP_0]));
+        assert!(!script.ends_with_ops(&[OP_0]));
+
+        // Parse error after the prefix still matches the prefix
+        let script = Script::new(vec![0x6a, 0x4d, 0x01].into());
+        assert!(script.starts_with_ops(&[OP_RETURN]));
+        assert!(!script.starts_with_ops(&[OP_RETURN, OP_PUSHDATA2]));
+        assert!(!script.ends_with_ops(&[]));
+    }
+
+    #[test]
+    fn test_is_standard_size() {
+        let make_script = |bytecode: Vec<u8>| Script::new(bytecode.into());
+        assert!(make_script(vec![]).is_standard_size());
+
+        // Size limit, using a single big push
+        let push_len = MAX_SCRIPT_SIZE - 3;
+        let mut bytecode = vec![OP_PUSHDATA2::N];
+        bytecode.extend((push_len as u16).to_le_bytes());
+        bytecode.extend(vec![0; push_len]);
+        assert_eq!(bytecode.len(), MAX_SCRIPT_SIZE);
+        assert!(make_script(bytecode.clone()).is_standard_size());
+        bytecode.push(OP_1::N);
+        assert!(!make_script(bytecode).is_standard_size());
+
+        // Op limit, mixing counted and uncounted ops
+        let mut bytecode = vec![OP_1::N, OP_16::N, OP_0::N, 0x01, 0xff];
+        bytecode.extend(vec![OP_CHECKSIG::N; MAX_OPS_PER_SCRIPT]);
+        assert!(make_script(bytecode.clone()).is_standard_size());
+        bytecode.push(OP_RETURN::N);
+        assert!(!make_script(bytecode).is_standard_size());
+
+        // Invalid scripts are never standard
+        assert!(!make_script(vec![OP_PUSHDATA1::N]).is_standard_size());
+    }
+
+    /// Assert that every op of `script` is a minimally encoded data push
+    fn assert_minimal_pushes(script: &Script) {
+        for op in script.iter_ops() {
+            let (opcode, data) = match op.unwrap() {
+                Op::Code(OP_0) => continue,
+                Op::Push(opcode, data) => (opcode, data),
+                op => panic!("Not a push: {op:?}"),
+            };
+            let expected = match data.len() {
+                0 => panic!("Empty push should use OP_0"),
+                len @ 0x01..=0x4b => Opcode(len as u8),
+                0x4c..=0xff => OP_PUSHDATA1,
+                0x100..=0xffff => OP_PUSHDATA2,
+                _ => OP_PUSHDATA4,
+            };
+            assert_eq!(opcode, expected);
+        }
+    }
+
+    #[test]
+    fn test_p2pkh_sig() {
+        let pubkey = PubKey([2; 33]);
+        // Empty, Schnorr and max DER signatures, all with sighash type
+        for sig_len in [0, 65, 73] {
+            let sig = vec![0x30; sig_len];
+            let script = Script::p2pkh_sig(&sig, &pubkey);
+            assert_minimal_pushes(&script);
+            let ops = script.iter_ops().collect::<Result<Vec<_>, _>>().unwrap();
+            let sig_op = match sig_len {
+                0 => Op::Code(OP_0),
+                _ => Op::Push(Opcode(sig_len as u8), sig.into()),
+            };
+            let pubkey_op =
+                Op::Push(Opcode(33), pubkey.as_slice().to_vec().into());
+            assert_eq!(ops, vec![sig_op, pubkey_op]);
+        }
+    }
+
+    #[test]
+    fn test_p2sh_multisig_sig() {
+        // 2-of-3 multisig redeem script with compressed pubkeys
+        let mut redeem = ScriptMut::default();
+        redeem.put_opcodes([OP_2]);
+        for pubkey_byte in 2..5 {
+            redeem.put_pushdata(&[pubkey_byte; 33]);
+        }
+        redeem.put_opcodes([OP_3, OP_CHECKMULTISIG]);
+        let redeem = redeem.freeze();
+        assert_eq!(redeem.bytecode().len(), 105);
+
+        let sig1 = vec![0x30; 72];
+        let sig2 = vec![0x41; 65];
+        let script = Script::p2sh_multisig_sig(&[&sig1, &sig2], &redeem);
+        assert_minimal_pushes(&script);
+        let ops = script.iter_ops().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            ops,
+            vec![
+                Op::Code(OP_0),
+                Op::Push(Opcode(72), sig1.into()),
+                Op::Push(Opcode(65), sig2.into()),
+                Op::Push(OP_PUSHDATA1, redeem.bytecode().clone()),
+            ],
+        );
+        assert_eq!(&script.bytecode()[140..142], &[OP_PUSHDATA1::N, 105]);
+
+        // No signatures: only the dummy element and the redeem script
+        let script = Script::p2sh_multisig_sig(&[], &redeem);
+        assert_eq!(script.hex(), format!("004c69{}", redeem.hex()));
+    }
+
+    #[test]
+    fn test_lokad_roundtrip() {
+        let lokad_id = *b"TEST";
+        for chunk_len in [0, 1, 0x4b, 0x4c, 0xff, 0x100, 0x10000] {
+            let payload: Vec<Bytes> =
+                vec![vec![7; chunk_len].into(), vec![8].into()];
+            let script = Script::lokad(lokad_id, &payload);
+            assert_eq!(script.parse_lokad(), Some((lokad_id, payload)));
+        }
+        assert_eq!(
+            Script::lokad(lokad_id, &[]).parse_lokad(),
+            Some((lokad_id, vec![])),
+        );
+
+        // Not LOKAD scripts
+        let not_lokad = ["", "6a", "51", "6a4c", "6a0401020304ac", "6a4c0401"];
+        for hex in not_lokad {
+            assert_eq!(Script::from_hex(hex).unwrap().parse_lokad(), None);
+        }
+    }
+
+    #[test]
+    fn test_strip_opreturn_data() {
+        let strip = |hex: &str| {
+            Script::from_hex(hex).unwrap().strip_opreturn_data().hex()
+        };
+        assert_eq!(strip("6a"), "6a");
+        assert_eq!(strip("6a00"), "6a00");
+        assert_eq!(strip("6a4c0201024d0100ff6a"), "6a6a");
+        assert_eq!(strip("6a76a988ac"), "6a76a988ac");
+        assert_eq!(strip("6a5104010203044e"), "6a51");
+        assert_eq!(strip(""), "");
+        assert_eq!(strip("76a90102"), "76a90102");
     }
 
     #[test]
@@ -237,10 +1899,18 @@ mod tests {
         verify_ser(Script::default(), &[0x00]);
         verify_ser(Script::new(vec![0x51].into()), &[0x01, 0x51]);
         verify_ser(Script::new(vec![0x51, 0x52].into()), &[0x02, 0x51, 0x52]);
+        verify_ser(
+            Script::new(vec![3; 0xfc].into()),
+            &[[0xfc].as_ref(), &[3; 0xfc]].concat(),
+        );
         verify_ser(
             Script::new(vec![4; 0xfd].into()),
             &[[0xfd, 0xfd, 0].as_ref(), &[4; 0xfd]].concat(),
         );
+        verify_ser(
+            Script::new(vec![4; 0xffff].into()),
+            &[[0xfd, 0xff, 0xff].as_ref(), &vec![4; 0xffff]].concat(),
+        );
         verify_ser(
             Script::new(vec![5; 0x10000].into()),
             &[[0xfe, 0, 0, 1, 0].as_ref(), &vec![5; 0x10000]].concat(),
@@ -1,6 +1,6 @@
 use bytes::{BufMut, BytesMut};
 
-use crate::script::{opcode::Opcode, Script};
+use crate::script::{opcode::*, Script};
 
 /// A mutable version of [`Script`], it allows appending more opcodes/bytecode
 /// etc.
@@ -21,6 +21,59 @@ impl ScriptMut {
         ScriptMut(BytesMut::with_capacity(size))
     }
 
+    /// Create a new, empty [`ScriptMut`], same as [`ScriptMut::default`].
+    /// Useful to start a chain of [`ScriptMut::op`] and [`ScriptMut::push`].
+    pub fn new() -> Self {
+        ScriptMut::default()
+    }
+
+    /// Append the opcode to the script, for building scripts by chaining
+    /// calls.
+    ///
+    /// Unlike [`ScriptMut::put_opcodes`], this takes and returns the script
+    /// by value, so the chain can end with [`ScriptMut::freeze`].
+    /// ```
+    /// # use bitcoinsuite_core::script::ScriptMut;
+    /// use bitcoinsuite_core::script::opcode::*;
+    /// let script = ScriptMut::new()
+    ///     .op(OP_DUP)
+    ///     .op(OP_HASH160)
+    ///     .push(&[0x11; 20])
+    ///     .op(OP_EQUALVERIFY)
+    ///     .op(OP_CHECKSIG)
+    ///     .freeze();
+    /// assert_eq!(script.hex(), format!("76a914{}88ac", "11".repeat(20)));
+    /// ```
+    pub fn op(mut self, opcode: Opcode) -> Self {
+        self.put_opcodes([opcode]);
+        self
+    }
+
+    /// Append all the given opcodes to the script, chainable version of
+    /// [`ScriptMut::put_opcodes`].
+    /// ```
+    /// # use bitcoinsuite_core::script::ScriptMut;
+    /// use bitcoinsuite_core::script::opcode::{OP_EQUAL, OP_TRUE};
+    /// let script = ScriptMut::new().ops([OP_EQUAL, OP_TRUE]).freeze();
+    /// assert_eq!(script.hex(), "8751");
+    /// ```
+    pub fn ops(mut self, opcodes: impl IntoIterator<Item = Opcode>) -> Self {
+        self.put_opcodes(opcodes);
+        self
+    }
+
+    /// Append a push of the given data to the script, chainable version of
+    /// [`ScriptMut::put_pushdata`].
+    /// ```
+    /// # use bitcoinsuite_core::script::ScriptMut;
+    /// let script = ScriptMut::new().push(&[]).push(&[1, 2]).freeze();
+    /// assert_eq!(script.hex(), "00020102");
+    /// ```
+    pub fn push(mut self, data: &[u8]) -> Self {
+        self.put_pushdata(data);
+        self
+    }
+
     /// Append the opcode numbers of the given list of opcodes to the script.
     /// ```
     /// # use bitcoinsuite_core::script::ScriptMut;
@@ -48,6 +101,43 @@ impl ScriptMut {
         self.0.put_slice(slice);
     }
 
+    /// Append a push of the given data to this script, using the smallest push
+    /// opcode for its size: [`OP_0`] for empty data, a direct push for up to 75
+    /// bytes, otherwise [`OP_PUSHDATA1`], [`OP_PUSHDATA2`] or
+    /// [`OP_PUSHDATA4`].
+    ///
+    /// Note: Unlike the node's minimal push rule, single-byte data is never
+    /// encoded as [`OP_1`] to [`OP_16`] or [`OP_1NEGATE`], as protocols on
+    /// OP_RETURN expect data pushes.
+    /// ```
+    /// # use bitcoinsuite_core::script::ScriptMut;
+    /// let mut script_mut = ScriptMut::default();
+    /// script_mut.put_pushdata(&[]);
+    /// script_mut.put_pushdata(&[1, 2]);
+    /// script_mut.put_pushdata(&[3; 76]);
+    /// let script = script_mut.freeze();
+    /// assert_eq!(script.hex(), format!("000201024c4c{}", "03".repeat(76)));
+    /// ```
+    pub fn put_pushdata(&mut self, data: &[u8]) {
+        match data.len() {
+            0 => self.0.put_u8(OP_0::N),
+            len @ 0x01..=0x4b => self.0.put_u8(len as u8),
+            len @ 0x4c..=0xff => {
+                self.0.put_u8(OP_PUSHDATA1::N);
+                self.0.put_u8(len as u8);
+            }
+            len @ 0x100..=0xffff => {
+                self.0.put_u8(OP_PUSHDATA2::N);
+                self.0.put_u16_le(len as u16);
+            }
+            len => {
+                self.0.put_u8(OP_PUSHDATA4::N);
+                self.0.put_u32_le(len as u32);
+            }
+        }
+        self.0.put_slice(data);
+    }
+
     /// Turn the given [`ScriptMut`] into a [`Script`], making it immutable.
     /// ```
     /// # use bitcoinsuite_core::script::{Script, ScriptMut};
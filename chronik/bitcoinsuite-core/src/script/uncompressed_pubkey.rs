@@ -2,7 +2,8 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
-use std::str::FromStr;
+use alloc::{string::String, vec};
+use core::str::FromStr;
 
 use hex_literal::hex;
 
@@ -80,6 +81,48 @@ impl Default for UncompressedPubKey {
     }
 }
 
+/// Parse an uncompressed pubkey, e.g. from a script push. Checks the length
+/// and that it starts with 0x04.
+/// ```
+/// # use bitcoinsuite_core::{error::DataError, script::UncompressedPubKey};
+/// assert_eq!(
+///     UncompressedPubKey::try_from(&[4u8; 65][..]),
+///     Ok(UncompressedPubKey([4; 65])),
+/// );
+/// assert_eq!(
+///     UncompressedPubKey::try_from(&[4u8; 33][..]),
+///     Err(DataError::InvalidLength {
+///         expected: 65,
+///         actual: 33,
+///     }),
+/// );
+/// assert_eq!(
+///     UncompressedPubKey::try_from(&[2u8; 65][..]),
+///     Err(DataError::InvalidPubKeyPrefix {
+///         expected: vec![4],
+///         actual: 2,
+///     }),
+/// );
+/// ```
+impl TryFrom<&[u8]> for UncompressedPubKey {
+    type Error = DataError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        let array: [u8; UncompressedPubKey::SIZE] =
+            slice.try_into().map_err(|_| DataError::InvalidLength {
+                expected: UncompressedPubKey::SIZE,
+                actual: slice.len(),
+            })?;
+        if array[0] != 0x04 {
+            return Err(DataError::InvalidPubKeyPrefix {
+                expected: vec![0x04],
+                actual: array[0],
+            });
+        }
+        Ok(UncompressedPubKey(array))
+    }
+}
+
 impl FromStr for UncompressedPubKey {
     type Err = DataError;
 
@@ -100,8 +143,8 @@ impl AsRef<[u8]> for UncompressedPubKey {
     }
 }
 
-impl std::fmt::Debug for UncompressedPubKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for UncompressedPubKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "UncompressedPubKey({})", self.hex())
     }
 }
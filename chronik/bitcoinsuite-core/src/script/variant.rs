@@ -2,24 +2,41 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
-use std::str::FromStr;
-
-use thiserror::Error;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
 
 use crate::{
     error::DataError,
-    hash::ShaRmd160,
-    script::{PubKeyVariant, Script},
+    hash::{Hashed, ShaRmd160},
+    script::{opcode::*, PubKey, PubKeyVariant, Script, UncompressedPubKey},
 };
 
 /// Errors indicating a script type couldn't be parsed.
-#[derive(Clone, Debug, Error, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ScriptTypeError {
     /// Script type unknown.
-    #[error("Unknown script type: {0}")]
     UnknownScriptType(String),
 }
 
+impl Display for ScriptTypeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ScriptTypeError::UnknownScriptType(script_type) => {
+                write!(f, "Unknown script type: {script_type}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ScriptTypeError {}
+
 /// Script variants.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum ScriptType {
@@ -52,6 +69,104 @@ pub enum ScriptVariant {
     Other(Script),
 }
 
+impl ScriptType {
+    /// Recognize the type of the given script, like
+    /// [`ScriptVariant::from_script`], but without extracting the payload.
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::ShaRmd160,
+    /// #     script::{PubKey, Script, ScriptType},
+    /// # };
+    /// let hash = ShaRmd160([3; 20]);
+    /// let script = Script::p2pkh(&hash);
+    /// assert_eq!(ScriptType::from_script(&script), ScriptType::P2PKH);
+    /// let script = Script::p2sh(&hash);
+    /// assert_eq!(ScriptType::from_script(&script), ScriptType::P2SH);
+    /// let script = Script::p2pk(&PubKey([2; 33]));
+    /// assert_eq!(ScriptType::from_script(&script), ScriptType::P2PK);
+    /// let script = Script::new(vec![0x6a, 0x01, 0x02].into());
+    /// assert_eq!(ScriptType::from_script(&script), ScriptType::Other);
+    /// ```
+    pub fn from_script(script: &Script) -> ScriptType {
+        ScriptType::from_bytecode(script.bytecode())
+    }
+
+    fn from_bytecode(bytecode: &[u8]) -> ScriptType {
+        const HASH_SIZE: usize = ShaRmd160::SIZE;
+        const PK_SIZE: usize = PubKey::SIZE;
+        const UNCOMP_PK_SIZE: usize = UncompressedPubKey::SIZE;
+        let checksig = OP_CHECKSIG.number();
+        match bytecode.len() {
+            // OP_DUP OP_HASH160 <hash> OP_EQUALVERIFY OP_CHECKSIG
+            len @ 25
+                if bytecode[..3]
+                    == [
+                        OP_DUP.number(),
+                        OP_HASH160.number(),
+                        HASH_SIZE as u8,
+                    ]
+                    && bytecode[len - 2..]
+                        == [OP_EQUALVERIFY.number(), checksig] =>
+            {
+                ScriptType::P2PKH
+            }
+            // OP_HASH160 <hash> OP_EQUAL
+            len @ 23
+                if bytecode[..2] == [OP_HASH160.number(), HASH_SIZE as u8]
+                    && bytecode[len - 1] == OP_EQUAL.number() =>
+            {
+                ScriptType::P2SH
+            }
+            // <pubkey> OP_CHECKSIG
+            len @ 35
+                if bytecode[0] == PK_SIZE as u8
+                    && bytecode[len - 1] == checksig =>
+            {
+                ScriptType::P2PK
+            }
+            len @ 67
+                if bytecode[0] == UNCOMP_PK_SIZE as u8
+                    && bytecode[len - 1] == checksig =>
+            {
+                ScriptType::P2PK
+            }
+            _ => ScriptType::Other,
+        }
+    }
+}
+
+/// Classify many scripts at once, e.g. all outputs of a block, using
+/// [`ScriptType::from_script`].
+///
+/// Unlike classifying each script with [`ScriptVariant::from_script`], this
+/// only inspects the bytecode of each script in place, without cloning scripts
+/// or copying payloads, and allocates only the result, once. See the
+/// `classify_many` benchmark for a comparison.
+/// ```
+/// # use bitcoinsuite_core::{
+/// #     hash::ShaRmd160,
+/// #     script::{classify_many, Script, ScriptType},
+/// # };
+/// let scripts = [
+///     Script::p2pkh(&ShaRmd160([1; 20])),
+///     Script::default(),
+///     Script::p2sh(&ShaRmd160([2; 20])),
+/// ];
+/// assert_eq!(
+///     classify_many(&scripts),
+///     [ScriptType::P2PKH, ScriptType::Other, ScriptType::P2SH],
+/// );
+/// ```
+pub fn classify_many(scripts: &[Script]) -> Vec<ScriptType> {
+    let mut script_types = Vec::with_capacity(scripts.len());
+    script_types.extend(
+        scripts
+            .iter()
+            .map(|script| ScriptType::from_bytecode(script.bytecode())),
+    );
+    script_types
+}
+
 impl ScriptVariant {
     /// Try to interpret the payload as the given [`ScriptType`], and return the
     /// corresponding variant. ```
@@ -105,6 +220,54 @@ impl ScriptVariant {
         })
     }
 
+    /// Recognize the variant of the given script, the inverse of
+    /// [`ScriptVariant::to_script`].
+    ///
+    /// Only the exact templates are recognized, e.g. P2PK with a non-minimal
+    /// push of the pubkey is [`ScriptVariant::Other`].
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::ShaRmd160,
+    /// #     script::{PubKey, PubKeyVariant, Script, ScriptVariant},
+    /// # };
+    /// let hash = ShaRmd160([3; 20]);
+    /// assert_eq!(
+    ///     ScriptVariant::from_script(&Script::p2pkh(&hash)),
+    ///     ScriptVariant::P2PKH(hash),
+    /// );
+    /// assert_eq!(
+    ///     ScriptVariant::from_script(&Script::p2sh(&hash)),
+    ///     ScriptVariant::P2SH(hash),
+    /// );
+    /// assert_eq!(
+    ///     ScriptVariant::from_script(&Script::p2pk(&PubKey([2; 33]))),
+    ///     ScriptVariant::P2PK(PubKeyVariant::Compressed(PubKey([2; 33]))),
+    /// );
+    /// let script = Script::new(vec![0x6a, 0x01, 0x02].into());
+    /// assert_eq!(
+    ///     ScriptVariant::from_script(&script),
+    ///     ScriptVariant::Other(script),
+    /// );
+    /// ```
+    pub fn from_script(script: &Script) -> ScriptVariant {
+        let bytecode = script.bytecode().as_ref();
+        match ScriptType::from_bytecode(bytecode) {
+            ScriptType::P2PKH => {
+                let hash = bytecode[3..3 + ShaRmd160::SIZE].try_into().unwrap();
+                ScriptVariant::P2PKH(ShaRmd160(hash))
+            }
+            ScriptType::P2SH => {
+                let hash = bytecode[2..2 + ShaRmd160::SIZE].try_into().unwrap();
+                ScriptVariant::P2SH(ShaRmd160(hash))
+            }
+            ScriptType::P2PK => {
+                let pubkey = &bytecode[1..bytecode.len() - 1];
+                ScriptVariant::P2PK(pubkey.try_into().unwrap())
+            }
+            ScriptType::Other => ScriptVariant::Other(script.clone()),
+        }
+    }
+
     /// ```
     /// # use bitcoinsuite_core::{
     /// #     hash::ShaRmd160,
@@ -157,9 +320,15 @@ fn parse_array<const N: usize>(payload: &[u8]) -> Result<[u8; N], DataError> {
 
 #[cfg(test)]
 mod tests {
+    use alloc::{string::ToString, vec, vec::Vec};
+
     use crate::{
         error::DataError,
-        script::{ScriptType, ScriptTypeError, ScriptVariant},
+        hash::ShaRmd160,
+        script::{
+            classify_many, PubKey, PubKeyVariant, Script, ScriptType,
+            ScriptTypeError, ScriptVariant, UncompressedPubKey,
+        },
     };
 
     #[test]
@@ -211,4 +380,81 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_script_variant_from_script() {
+        let variants = [
+            ScriptVariant::P2PKH(ShaRmd160([1; 20])),
+            ScriptVariant::P2SH(ShaRmd160([2; 20])),
+            ScriptVariant::P2PK(PubKeyVariant::Compressed(PubKey([3; 33]))),
+            ScriptVariant::P2PK(PubKeyVariant::Uncompressed(
+                UncompressedPubKey([4; 65]),
+            )),
+        ];
+        for variant in &variants {
+            let script = variant.to_script();
+            assert_eq!(ScriptVariant::from_script(&script), *variant);
+        }
+        // Scripts that almost match a template
+        let modify = |variant: &ScriptVariant, f: fn(&mut Vec<u8>)| {
+            let mut bytecode = variant.to_script().to_vec();
+            f(&mut bytecode);
+            Script::new(bytecode.into())
+        };
+        let [p2pkh, p2sh, p2pk, p2pk_uncompressed] = &variants;
+        let scripts = [
+            // P2PKH with OP_EQUAL instead of OP_EQUALVERIFY
+            modify(p2pkh, |bytecode| bytecode[23] = 0x87),
+            // P2PKH with 19-byte hash
+            modify(p2pkh, |bytecode| {
+                bytecode.remove(3);
+                bytecode[2] = 19;
+            }),
+            // P2SH with extra trailing byte
+            modify(p2sh, |bytecode| bytecode.push(0xac)),
+            // P2PK with OP_CHECKSIGVERIFY
+            modify(p2pk, |bytecode| *bytecode.last_mut().unwrap() = 0xad),
+            // P2PK with the wrong push opcode for an uncompressed pubkey
+            modify(p2pk_uncompressed, |bytecode| bytecode[0] = 0x21),
+        ];
+        for script in scripts {
+            assert_eq!(
+                ScriptVariant::from_script(&script),
+                ScriptVariant::Other(script),
+            );
+        }
+    }
+
+    #[test]
+    fn test_classify_many() {
+        assert_eq!(classify_many(&[]), Vec::<ScriptType>::new());
+        let scripts = [
+            ScriptVariant::P2PKH(ShaRmd160([1; 20])).to_script(),
+            ScriptVariant::P2SH(ShaRmd160([2; 20])).to_script(),
+            ScriptVariant::P2PK(PubKeyVariant::Compressed(PubKey([3; 33])))
+                .to_script(),
+            ScriptVariant::P2PK(PubKeyVariant::Uncompressed(
+                UncompressedPubKey([4; 65]),
+            ))
+            .to_script(),
+            Script::default(),
+            Script::new(vec![0x6a, 0x01, 0x02].into()),
+        ];
+        let script_types = classify_many(&scripts);
+        assert_eq!(
+            script_types,
+            [
+                ScriptType::P2PKH,
+                ScriptType::P2SH,
+                ScriptType::P2PK,
+                ScriptType::P2PK,
+                ScriptType::Other,
+                ScriptType::Other,
+            ],
+        );
+        // Matches classifying each script on its own
+        for (script, script_type) in scripts.iter().zip(script_types) {
+            assert_eq!(ScriptType::from_script(script), script_type);
+        }
+    }
 }
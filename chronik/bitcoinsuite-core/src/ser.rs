@@ -4,19 +4,34 @@
 
 //! Module containing [`BitcoinSer`].
 
+use alloc::vec::Vec;
+
 use bytes::{Bytes, BytesMut};
 
+use crate::{
+    bytes::{read_array, read_bytes},
+    error::DataError,
+};
+
 /// Serializer for implementors of [`BitcoinSer`].
 pub trait BitcoinSerializer {
     /// Serialize the given slice of data.
     fn put(&mut self, slice: &[u8]);
 }
 
-/// Trait for serializing data using the serialization Bitcoin is using.
-pub trait BitcoinSer {
+/// Trait for serializing and deserializing data using the serialization
+/// Bitcoin is using.
+pub trait BitcoinSer: Sized {
     /// Serialize to the given serializer
     fn ser_to<S: BitcoinSerializer>(&self, bytes: &mut S);
 
+    /// Deserialize from the given bytes, consuming exactly the bytes of the
+    /// serialized object and leaving the remaining bytes in `data`.
+    ///
+    /// Sliced data (e.g. scripts) shares the memory of `data` instead of being
+    /// copied.
+    fn deser(data: &mut Bytes) -> Result<Self, DataError>;
+
     /// Serialize to bytes.
     fn ser(&self) -> Bytes {
         let mut bytes = BytesMut::new();
@@ -26,11 +41,74 @@ pub trait BitcoinSer {
 
     /// Measure the length of the serialized result without storing the
     /// serialization.
+    ///
+    /// Saturates at [`usize::MAX`] if the length doesn't fit into a [`usize`],
+    /// see [`BitcoinSer::checked_ser_len`].
     fn ser_len(&self) -> usize {
         let mut len = 0usize;
         self.ser_to(&mut len);
         len
     }
+
+    /// Measure the length of the serialized result, or [`None`] if it doesn't
+    /// fit into a [`usize`].
+    ///
+    /// This can only happen for objects close to the size of the address
+    /// space, e.g. a script of almost 4 GiB on a 32-bit platform, whose size
+    /// prefix pushes the serialized length over the limit.
+    fn checked_ser_len(&self) -> Option<usize> {
+        let mut ser = LengthSerializer::new();
+        self.ser_to(&mut ser);
+        ser.checked_len()
+    }
+}
+
+/// [`BitcoinSerializer`] that only counts the serialized bytes, without
+/// storing them.
+///
+/// Useful to compute the serialized length of composite types, e.g. when
+/// implementing [`BitcoinSer::ser_len`] for a custom type, or to sum up the
+/// length of multiple objects without allocating.
+///
+/// ```
+/// # use bitcoinsuite_core::ser::{BitcoinSer, LengthSerializer};
+/// let mut ser = LengthSerializer::new();
+/// assert!(ser.is_empty());
+/// 1234u32.ser_to(&mut ser);
+/// vec![1u8, 2, 3].ser_to(&mut ser);
+/// assert_eq!(ser.len(), 4 + 1 + 3);
+/// assert_eq!(ser.checked_len(), Some(4 + 1 + 3));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct LengthSerializer {
+    len: usize,
+    overflowed: bool,
+}
+
+impl LengthSerializer {
+    /// Create a new serializer, with a length of 0.
+    pub fn new() -> Self {
+        LengthSerializer::default()
+    }
+
+    /// Number of bytes serialized so far, saturating at [`usize::MAX`].
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no bytes have been serialized so far.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of bytes serialized so far, or [`None`] if it overflowed a
+    /// [`usize`].
+    pub fn checked_len(&self) -> Option<usize> {
+        match self.overflowed {
+            true => None,
+            false => Some(self.len),
+        }
+    }
 }
 
 impl BitcoinSerializer for BytesMut {
@@ -42,7 +120,19 @@ impl BitcoinSerializer for BytesMut {
 
 impl BitcoinSerializer for usize {
     fn put(&mut self, slice: &[u8]) {
-        *self += slice.len();
+        *self = self.saturating_add(slice.len());
+    }
+}
+
+impl BitcoinSerializer for LengthSerializer {
+    fn put(&mut self, slice: &[u8]) {
+        match self.len.checked_add(slice.len()) {
+            Some(len) => self.len = len,
+            None => {
+                self.len = usize::MAX;
+                self.overflowed = true;
+            }
+        }
     }
 }
 
@@ -51,12 +141,23 @@ impl BitcoinSer for Bytes {
         write_compact_size(bytes, self.len() as u64);
         bytes.put(self.as_ref());
     }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        let size = read_compact_size(data)?;
+        // Sizes beyond usize::MAX can never fit into data
+        let size = usize::try_from(size).unwrap_or(usize::MAX);
+        read_bytes(data, size)
+    }
 }
 
 impl<const N: usize> BitcoinSer for [u8; N] {
     fn ser_to<S: BitcoinSerializer>(&self, bytes: &mut S) {
         bytes.put(self.as_ref());
     }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        read_array(data)
+    }
 }
 
 impl<T: BitcoinSer> BitcoinSer for Vec<T> {
@@ -66,12 +167,27 @@ impl<T: BitcoinSer> BitcoinSer for Vec<T> {
             part.ser_to(bytes);
         }
     }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        let size = read_compact_size(data)?;
+        // Don't trust the size for preallocating, it could be bogus
+        let capacity = size.min(data.len() as u64) as usize;
+        let mut vec = Vec::with_capacity(capacity);
+        for _ in 0..size {
+            vec.push(T::deser(data)?);
+        }
+        Ok(vec)
+    }
 }
 
 impl BitcoinSer for bool {
     fn ser_to<S: BitcoinSerializer>(&self, bytes: &mut S) {
         bytes.put(&[*self as u8]);
     }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        Ok(u8::deser(data)? != 0)
+    }
 }
 
 macro_rules! integer_impls {
@@ -81,6 +197,11 @@ macro_rules! integer_impls {
                 fn ser_to<S: BitcoinSerializer>(&self, bytes: &mut S) {
                     bytes.put(&self.to_le_bytes())
                 }
+
+                fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+                    let array = read_array::<$SIZE>(data)?;
+                    Ok($T::from_le_bytes(array))
+                }
             }
         )+
     }
@@ -90,7 +211,10 @@ integer_impls! {
     u8 1, i8 1, u16 2, i16 2, u32 4, i32 4, u64 8, i64 8, u128 16, i128 16,
 }
 
-fn write_compact_size<S: BitcoinSerializer>(bytes: &mut S, size: u64) {
+pub(crate) fn write_compact_size<S: BitcoinSerializer>(
+    bytes: &mut S,
+    size: u64,
+) {
     match size {
         0..=0xfc => bytes.put(&[size as u8]),
         0xfd..=0xffff => {
@@ -108,15 +232,125 @@ fn write_compact_size<S: BitcoinSerializer>(bytes: &mut S, size: u64) {
     }
 }
 
+/// Read a compact size, as written by the node's WriteCompactSize.
+///
+/// Like the node, this rejects non-canonical encodings, i.e. sizes that could
+/// have been encoded using fewer bytes.
+pub(crate) fn read_compact_size(data: &mut Bytes) -> Result<u64, DataError> {
+    let (size, min_size) = match u8::deser(data)? {
+        0xfd => (u16::deser(data)? as u64, 0xfd),
+        0xfe => (u32::deser(data)? as u64, 0x10000),
+        0xff => (u64::deser(data)?, 0x1_0000_0000),
+        size => return Ok(size as u64),
+    };
+    if size < min_size {
+        return Err(DataError::NonCanonicalCompactSize(size));
+    }
+    Ok(size)
+}
+
 #[cfg(test)]
 mod tests {
+    use alloc::{vec, vec::Vec};
+    use core::fmt::Debug;
+
     use bytes::Bytes;
 
-    use crate::ser::BitcoinSer;
+    use crate::{
+        error::DataError,
+        ser::{
+            read_compact_size, write_compact_size, BitcoinSer,
+            BitcoinSerializer, LengthSerializer,
+        },
+    };
 
-    fn verify_ser<T: BitcoinSer>(a: T, b: &[u8]) {
+    fn verify_ser<T: BitcoinSer + Debug + PartialEq>(a: T, b: &[u8]) {
         assert_eq!(a.ser().as_ref(), b);
         assert_eq!(a.ser_len(), b.len());
+        assert_eq!(a.checked_ser_len(), Some(b.len()));
+        let mut data = Bytes::copy_from_slice(b);
+        assert_eq!(T::deser(&mut data), Ok(a));
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn test_read_compact_size() {
+        let read =
+            |data: &[u8]| read_compact_size(&mut Bytes::copy_from_slice(data));
+        assert_eq!(read(&[0xfc]), Ok(0xfc));
+        assert_eq!(read(&[0xfd, 0xfd, 0x00]), Ok(0xfd));
+        assert_eq!(read(&[0xfe, 0, 0, 1, 0]), Ok(0x10000));
+        assert_eq!(read(&[0xff, 0, 0, 0, 0, 1, 0, 0, 0]), Ok(0x1_0000_0000));
+        assert_eq!(
+            read(&[0xfd, 0xfc, 0x00]),
+            Err(DataError::NonCanonicalCompactSize(0xfc)),
+        );
+        assert_eq!(
+            read(&[0xfe, 0xff, 0xff, 0, 0]),
+            Err(DataError::NonCanonicalCompactSize(0xffff)),
+        );
+        assert_eq!(
+            read(&[0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0]),
+            Err(DataError::NonCanonicalCompactSize(0xffff_ffff)),
+        );
+        assert_eq!(
+            read(&[0xfd, 0x00]),
+            Err(DataError::InvalidLength {
+                expected: 2,
+                actual: 1,
+            }),
+        );
+        assert_eq!(
+            read(&[]),
+            Err(DataError::InvalidLength {
+                expected: 1,
+                actual: 0,
+            }),
+        );
+    }
+
+    #[test]
+    fn test_deser_bogus_size() {
+        // Huge declared sizes fail without allocating
+        let mut data = Bytes::from([[0xff; 9].as_ref(), &[1, 2]].concat());
+        assert_eq!(
+            Bytes::deser(&mut data.clone()),
+            Err(DataError::InvalidLength {
+                expected: usize::MAX,
+                actual: 2,
+            }),
+        );
+        assert_eq!(
+            Vec::<u8>::deser(&mut data),
+            Err(DataError::InvalidLength {
+                expected: 1,
+                actual: 0,
+            }),
+        );
+    }
+
+    #[test]
+    fn test_ser_len_overflow() {
+        // Length prefix of 3 bytes pushing the length over usize::MAX
+        let mut len = usize::MAX - 2;
+        write_compact_size(&mut len, 0x1000);
+        assert_eq!(len, usize::MAX);
+        len.put(&[0; 0x1000]);
+        assert_eq!(len, usize::MAX);
+
+        let mut ser = LengthSerializer {
+            len: usize::MAX - 0x1000,
+            overflowed: false,
+        };
+        ser.put(&[0; 0xffd]);
+        assert_eq!(ser.checked_len(), Some(usize::MAX - 3));
+        ser.put(&[0xfd, 0x00, 0x10]);
+        assert_eq!(ser.checked_len(), Some(usize::MAX));
+        ser.put(&[1]);
+        assert_eq!(ser.checked_len(), None);
+        assert_eq!(ser.len(), usize::MAX);
+        ser.put(&[]);
+        assert_eq!(ser.checked_len(), None);
     }
 
     #[test]
@@ -2,7 +2,13 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
+use alloc::{string::ToString, vec::Vec};
+
+use bytes::Bytes;
+
 use crate::{
+    error::DataError,
+    hash::Sha256d,
     script::Script,
     ser::{BitcoinSer, BitcoinSerializer},
     tx::TxId,
@@ -128,9 +134,99 @@ impl Tx {
     pub fn txid_ref(&self) -> &TxId {
         &self.txid
     }
+
+    /// Whether both txs have the same serialization, i.e. the same version,
+    /// inputs (including scriptSigs), outputs and locktime.
+    ///
+    /// Unlike `==`, this ignores the stored txid and the spent coins of the
+    /// inputs, which aren't part of the serialization. Detects e.g. malleated
+    /// variants spending the same inputs with a different scriptSig.
+    ///
+    /// On BCH, the txid is the hash of the entire serialization, so for txs
+    /// with correct txids this is equivalent to comparing txids (unlike chains
+    /// with SegWit, where the txid excludes the witness).
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     script::Script,
+    /// #     tx::{Tx, TxId, TxInput, TxMut},
+    /// # };
+    /// let tx = TxMut {
+    ///     inputs: vec![TxInput::default()],
+    ///     ..Default::default()
+    /// };
+    /// let a = Tx::with_txid(TxId::from([1; 32]), tx.clone());
+    /// let b = Tx::with_txid(TxId::from([2; 32]), tx.clone());
+    /// assert!(a.ser_eq(&b));
+    /// assert_ne!(a, b);
+    ///
+    /// let mut malleated = tx;
+    /// malleated.inputs[0].script = Script::new(vec![0x51].into());
+    /// let c = Tx::with_txid(TxId::from([1; 32]), malleated);
+    /// assert!(!a.ser_eq(&c));
+    /// ```
+    pub fn ser_eq(&self, other: &Tx) -> bool {
+        self.ser_len() == other.ser_len() && self.ser() == other.ser()
+    }
+
+    /// Deserialize txs serialized back-to-back until `data` is exhausted.
+    ///
+    /// Fails if the remaining bytes don't form a complete tx; `data` is then
+    /// left at the start of the incomplete tx.
+    /// ```
+    /// # use bitcoinsuite_core::{error::DataError, ser::BitcoinSer, tx::Tx};
+    /// use bytes::Bytes;
+    /// let raw_tx = Tx::default().ser();
+    /// let mut data = Bytes::from([raw_tx.as_ref(), &raw_tx].concat());
+    /// assert_eq!(Tx::deser_many(&mut data)?.len(), 2);
+    /// assert!(data.is_empty());
+    ///
+    /// let mut data = Bytes::from([raw_tx.as_ref(), &raw_tx[..6]].concat());
+    /// assert_eq!(
+    ///     Tx::deser_many(&mut data),
+    ///     Err(DataError::InvalidLength {
+    ///         expected: 4,
+    ///         actual: 0,
+    ///     }),
+    /// );
+    /// assert_eq!(data.len(), 6);
+    /// # Ok::<_, DataError>(())
+    /// ```
+    pub fn deser_many(data: &mut Bytes) -> Result<Vec<Tx>, DataError> {
+        let mut txs = Vec::new();
+        while !data.is_empty() {
+            let mut remaining = data.clone();
+            txs.push(Tx::deser(&mut remaining)?);
+            *data = remaining;
+        }
+        Ok(txs)
+    }
 }
 
-impl std::ops::Deref for Tx {
+impl TxMut {
+    /// Serialize the tx and compute its txid from the same bytes, i.e.
+    /// without serializing it a second time for hashing.
+    ///
+    /// Also available on [`Tx`]; there, the txid is computed from the bytes
+    /// and not taken from [`Tx::txid`], so it is always correct.
+    /// ```
+    /// # use bitcoinsuite_core::{
+    /// #     hash::Sha256d,
+    /// #     ser::BitcoinSer,
+    /// #     tx::{Tx, TxId, TxMut},
+    /// # };
+    /// let tx = Tx::with_txid(TxId::from([1; 32]), TxMut::default());
+    /// let (raw_tx, txid) = tx.ser_with_txid();
+    /// assert_eq!(raw_tx, tx.ser());
+    /// assert_eq!(txid, TxId::from(Sha256d::digest(&raw_tx)));
+    /// ```
+    pub fn ser_with_txid(&self) -> (Bytes, TxId) {
+        let raw_tx = self.ser();
+        let txid = TxId::from(Sha256d::digest(&raw_tx));
+        (raw_tx, txid)
+    }
+}
+
+impl core::ops::Deref for Tx {
     type Target = TxMut;
 
     fn deref(&self) -> &Self::Target {
@@ -145,12 +241,29 @@ impl BitcoinSer for TxMut {
         self.outputs.ser_to(bytes);
         self.locktime.ser_to(bytes);
     }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        Ok(TxMut {
+            version: BitcoinSer::deser(data)?,
+            inputs: BitcoinSer::deser(data)?,
+            outputs: BitcoinSer::deser(data)?,
+            locktime: BitcoinSer::deser(data)?,
+        })
+    }
 }
 
 impl BitcoinSer for Tx {
     fn ser_to<S: BitcoinSerializer>(&self, bytes: &mut S) {
         TxMut::ser_to(self, bytes)
     }
+
+    /// Deserialize the tx, computing its txid from the consumed bytes.
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        let raw_tx = data.clone();
+        let tx = TxMut::deser(data)?;
+        let raw_tx = &raw_tx[..raw_tx.len() - data.len()];
+        Ok(Tx::with_txid(TxId::from(Sha256d::digest(raw_tx)), tx))
+    }
 }
 
 impl BitcoinSer for OutPoint {
@@ -158,6 +271,35 @@ impl BitcoinSer for OutPoint {
         self.txid.ser_to(bytes);
         self.out_idx.ser_to(bytes);
     }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        Ok(OutPoint {
+            txid: BitcoinSer::deser(data)?,
+            out_idx: BitcoinSer::deser(data)?,
+        })
+    }
+}
+
+impl core::fmt::Display for OutPoint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}", self.txid, self.out_idx)
+    }
+}
+
+impl core::str::FromStr for OutPoint {
+    type Err = DataError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (txid, out_idx) = s
+            .split_once(':')
+            .ok_or_else(|| DataError::InvalidOutPoint(s.to_string()))?;
+        Ok(OutPoint {
+            txid: txid.parse()?,
+            out_idx: out_idx
+                .parse()
+                .map_err(|_| DataError::InvalidOutPoint(s.to_string()))?,
+        })
+    }
 }
 
 impl BitcoinSer for TxInput {
@@ -166,6 +308,17 @@ impl BitcoinSer for TxInput {
         self.script.ser_to(bytes);
         self.sequence.ser_to(bytes);
     }
+
+    /// Deserialize the input; the spent coin isn't part of the serialization,
+    /// so it's always [`None`].
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        Ok(TxInput {
+            prev_out: BitcoinSer::deser(data)?,
+            script: BitcoinSer::deser(data)?,
+            sequence: BitcoinSer::deser(data)?,
+            coin: None,
+        })
+    }
 }
 
 impl BitcoinSer for TxOutput {
@@ -173,19 +326,49 @@ impl BitcoinSer for TxOutput {
         self.value.ser_to(bytes);
         self.script.ser_to(bytes);
     }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        Ok(TxOutput {
+            value: BitcoinSer::deser(data)?,
+            script: BitcoinSer::deser(data)?,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::{format, string::ToString, vec};
+
+    use bytes::Bytes;
+
     use crate::{
+        error::DataError,
+        hash::Sha256d,
         script::Script,
         ser::BitcoinSer,
         tx::{OutPoint, Tx, TxId, TxInput, TxMut, TxOutput},
     };
 
+    const GENESIS_TX_HEX: &str =
+        "01000000010000000000000000000000000000000000000000000000000000\
+         000000000000ffffffff4d04ffff001d0104455468652054696d6573203033\
+         2f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f\
+         66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff01\
+         00f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828\
+         e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384d\
+         f7ba0b8d578a4c702b6bf11d5fac00000000";
+
     fn verify_ser(tx: TxMut, ser: &[u8]) {
         assert_eq!(tx.ser().as_ref(), ser);
         assert_eq!(tx.ser_len(), ser.len());
+        let mut data = Bytes::copy_from_slice(ser);
+        assert_eq!(TxMut::deser(&mut data), Ok(tx.clone()));
+        let txid = TxId::from(Sha256d::digest(ser));
+        assert_eq!(tx.ser_with_txid(), (Bytes::copy_from_slice(ser), txid));
+        assert_eq!(
+            Tx::deser(&mut Bytes::copy_from_slice(ser)),
+            Ok(Tx::with_txid(txid, tx.clone())),
+        );
         let tx = Tx::with_txid(TxId::from([12; 32]), tx);
         assert_eq!(tx.ser().as_ref(), ser);
         assert_eq!(tx.ser_len(), ser.len());
@@ -234,18 +417,77 @@ mod tests {
             }],
             locktime: 0,
         };
-        verify_ser(
-            genesis_tx,
-            &hex::decode(
-                "01000000010000000000000000000000000000000000000000000000000000\
-                 000000000000ffffffff4d04ffff001d0104455468652054696d6573203033\
-                 2f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f\
-                 66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff01\
-                 00f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828\
-                 e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384d\
-                 f7ba0b8d578a4c702b6bf11d5fac00000000"
-            )?,
+        verify_ser(genesis_tx, &hex::decode(GENESIS_TX_HEX)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_outpoint() -> Result<(), DataError> {
+        let txid_hex =
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+        let outpoint = OutPoint {
+            txid: txid_hex.parse()?,
+            out_idx: 7,
+        };
+        assert_eq!(outpoint.to_string(), format!("{txid_hex}:7"));
+        assert_eq!(format!("{txid_hex}:7").parse::<OutPoint>()?, outpoint);
+        let max_idx = format!("{txid_hex}:4294967295");
+        assert_eq!(max_idx.parse::<OutPoint>()?.out_idx, u32::MAX);
+        assert_eq!(max_idx.parse::<OutPoint>()?.to_string(), max_idx);
+
+        // Ser is txid (little-endian) followed by out_idx as u32 LE
+        let ser = outpoint.ser();
+        assert_eq!(ser.len(), 36);
+        assert_eq!(ser[..32], outpoint.txid.to_bytes());
+        assert_eq!(ser[32..], [7, 0, 0, 0]);
+        assert_eq!(OutPoint::deser(&mut ser.clone())?, outpoint);
+
+        for invalid in [
+            txid_hex.to_string(),
+            format!("{txid_hex}:"),
+            format!("{txid_hex}:-1"),
+            format!("{txid_hex}:4294967296"),
+            format!("{txid_hex}:1:2"),
+        ] {
+            assert_eq!(
+                invalid.parse::<OutPoint>(),
+                Err(DataError::InvalidOutPoint(invalid.clone())),
+            );
+        }
+        assert!(matches!(
+            "abc:0".parse::<OutPoint>(),
+            Err(DataError::InvalidHex(_)),
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_deser_tx() -> Result<(), DataError> {
+        let raw_tx = hex::decode(GENESIS_TX_HEX).unwrap();
+        let mut data = Bytes::from(raw_tx.clone());
+        let tx = Tx::deser(&mut data)?;
+        assert!(data.is_empty());
+        assert_eq!(
+            tx.txid(),
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"
+                .parse::<TxId>()?,
         );
+        assert_eq!(tx.inputs[0].coin, None);
+
+        // Concatenated txs
+        let mut data = Bytes::from([raw_tx.as_slice(), &[0; 10]].concat());
+        let txs = Tx::deser_many(&mut data)?;
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0], tx);
+        assert_eq!(*txs[1], TxMut::default());
+        assert!(data.is_empty());
+        assert_eq!(Tx::deser_many(&mut Bytes::new()), Ok(vec![]));
+
+        // Truncated tx
+        for len in 0..raw_tx.len() {
+            let mut data = Bytes::copy_from_slice(&raw_tx[..len]);
+            assert!(Tx::deser(&mut data).is_err());
+        }
         Ok(())
     }
 }
@@ -2,6 +2,10 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+
 use crate::{
     error::DataError,
     hash::{Hashed, Sha256d},
@@ -14,14 +18,14 @@ use crate::{
 #[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct TxId(Sha256d);
 
-impl std::fmt::Debug for TxId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for TxId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "TxId({})", self.0.hex_be())
     }
 }
 
-impl std::fmt::Display for TxId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for TxId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.0.hex_be().fmt(f)
     }
 }
@@ -73,7 +77,7 @@ impl TxId {
     }
 }
 
-impl std::str::FromStr for TxId {
+impl core::str::FromStr for TxId {
     type Err = DataError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -111,10 +115,16 @@ impl BitcoinSer for TxId {
     fn ser_to<S: BitcoinSerializer>(&self, bytes: &mut S) {
         bytes.put(self.as_bytes())
     }
+
+    fn deser(data: &mut Bytes) -> Result<Self, DataError> {
+        Ok(TxId::from(<[u8; 32]>::deser(data)?))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::{format, string::ToString};
+
     use hex_literal::hex;
 
     use crate::{
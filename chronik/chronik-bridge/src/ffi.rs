@@ -37,6 +37,8 @@ mod ffi_inner {
         pub hash: [u8; 32],
         /// hashPrevBlock, hash of the previous block in the chain
         pub prev_hash: [u8; 32],
+        /// hashMerkleRoot, Merkle root of the txids of the block
+        pub merkle_root: [u8; 32],
         /// nBits, difficulty of the header
         pub n_bits: u32,
         /// Timestamp of the block
@@ -126,6 +128,29 @@ mod ffi_inner {
         pub is_coinbase: bool,
     }
 
+    /// Whether [`ChronikBridge::bridge_tx`] succeeded, or why it failed.
+    #[derive(Debug)]
+    pub enum BridgeTxStatus {
+        /// The tx has been bridged.
+        Ok,
+        /// A coin spent by the tx couldn't be found, e.g. because the tx has
+        /// been mined or evicted in the meantime. Retrying won't help.
+        MissingCoin,
+        /// The node's locks are held by another thread, so the coins of the
+        /// tx couldn't be looked up right now. It's worth retrying, see
+        /// [`crate::util::retry_with_backoff`].
+        Busy,
+    }
+
+    /// Result of [`ChronikBridge::bridge_tx`].
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct BridgeTxResult {
+        /// Whether the tx has been bridged.
+        pub status: BridgeTxStatus,
+        /// The bridged tx, empty unless `status` is [`BridgeTxStatus::Ok`].
+        pub tx: Tx,
+    }
+
     #[allow(missing_debug_implementations)]
     unsafe extern "C++" {
         include!("blockindex.h");
@@ -199,7 +224,26 @@ mod ffi_inner {
 
         /// Bridge CTransaction -> ffi::Tx, including finding the spent coins.
         /// `tx` can be a mempool tx.
-        fn bridge_tx(self: &ChronikBridge, tx: &CTransaction) -> Result<Tx>;
+        /// If a spent coin couldn't be found, the status of the result is
+        /// [`BridgeTxStatus::MissingCoin`].
+        /// Unless `wait_for_locks` is set, this doesn't wait for the node's
+        /// locks and the status is [`BridgeTxStatus::Busy`] if they're held.
+        fn bridge_tx(
+            self: &ChronikBridge,
+            tx: &CTransaction,
+            wait_for_locks: bool,
+        ) -> Result<BridgeTxResult>;
+
+        /// Submit the serialized tx to the node's mempool and relay it to the
+        /// network. Txs paying a fee rate higher than the node's default
+        /// maximum for raw txs are rejected. Returns the txid of the tx, or an
+        /// error with the reason why the tx has been rejected.
+        /// Blocks until the node's callbacks queued the tx for Chronik's
+        /// indexer, so this must not be called on an async executor thread.
+        fn broadcast_tx(
+            self: &ChronikBridge,
+            raw_tx: &[u8],
+        ) -> Result<[u8; 32]>;
 
         /// Find at which block the given block_index forks off from the node.
         fn find_fork(
@@ -220,6 +264,13 @@ mod ffi_inner {
         /// Load the CTransaction from disk and serialize it.
         fn load_raw_tx(file_num: u32, data_pos: u32) -> Result<Vec<u8>>;
 
+        /// Load the CBlock from disk and serialize it.
+        fn load_raw_block(file_num: u32, data_pos: u32) -> Result<Vec<u8>>;
+
+        /// Load only the 80-byte serialized header of the block from disk,
+        /// without reading any of its txs.
+        fn load_block_header(file_num: u32, data_pos: u32) -> Result<[u8; 80]>;
+
         /// Get a BlockInfo for this CBlockIndex.
         fn get_block_info(block_index: &CBlockIndex) -> BlockInfo;
 
@@ -242,5 +293,23 @@ mod ffi_inner {
         /// Calls `AbortNode` from shutdown.h to gracefully shut down the node
         /// when an unrecoverable error occured.
         fn abort_node(msg: &str, user_msg: &str);
+
+        /// Calls `ShutdownRequested` from shutdown.h, i.e. whether the node is
+        /// shutting down, e.g. because the user pressed Ctrl+C.
+        fn shutdown_requested() -> bool;
     }
 }
+
+// SAFETY: ChronikBridge has no mutable state: it only holds const references
+// to the node's NodeContext and Consensus::Params, which are created before
+// Chronik is started and destroyed only after Chronik has been stopped, so it
+// can be moved to another thread.
+#[allow(unsafe_code)]
+unsafe impl Send for ChronikBridge {}
+
+// SAFETY: All methods of ChronikBridge are const and only access node state
+// through APIs that are safe to call concurrently: chain state is accessed
+// under cs_main, the mempool under its own lock, block files are read without
+// shared state, and BroadcastTransaction takes the locks it needs itself.
+#[allow(unsafe_code)]
+unsafe impl Sync for ChronikBridge {}
@@ -4,6 +4,8 @@
 
 //! Module for bridge utilities
 
+use std::time::Duration;
+
 use bitcoinsuite_core::{
     script::Script,
     tx::{Coin, OutPoint, Tx, TxId, TxInput, TxMut, TxOutput},
@@ -20,6 +22,62 @@ pub fn expect_unique_ptr<'ptr, T: cxx::memory::UniquePtrTarget>(
         .unwrap_or_else(|| panic!("{name} returned a null std::unique_ptr"))
 }
 
+/// Kind of an error reported by the C++ side of the bridge.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BridgeErrorKind {
+    /// The call might succeed when retrying, e.g. because the node's state was
+    /// momentarily inconsistent.
+    Transient,
+    /// The call will fail again when retrying.
+    Permanent,
+}
+
+impl BridgeErrorKind {
+    /// Classify the status of [`ffi::ChronikBridge::bridge_tx`], or [`None`]
+    /// if the tx has been bridged successfully.
+    pub fn from_bridge_tx_status(status: ffi::BridgeTxStatus) -> Option<Self> {
+        match status {
+            ffi::BridgeTxStatus::Ok => None,
+            ffi::BridgeTxStatus::Busy => Some(BridgeErrorKind::Transient),
+            // The UTXO set is consistent while the node's locks are held, so
+            // a missing coin stays missing, e.g. because the tx has been
+            // mined or evicted since the callback was queued.
+            ffi::BridgeTxStatus::MissingCoin => {
+                Some(BridgeErrorKind::Permanent)
+            }
+            // Statuses added on the C++ side but not handled here
+            _ => Some(BridgeErrorKind::Permanent),
+        }
+    }
+}
+
+/// Call `f` until it succeeds or fails with a [`BridgeErrorKind::Permanent`]
+/// error, retrying [`BridgeErrorKind::Transient`] errors at most
+/// `max_retries` times. Sleeps `initial_backoff` before the first retry and
+/// doubles it for every retry after that. `f` is told whether it's the last
+/// attempt. Returns the error of the last attempt if all of them failed.
+pub fn retry_with_backoff<T, E>(
+    max_retries: u32,
+    initial_backoff: Duration,
+    mut f: impl FnMut(bool) -> Result<T, (BridgeErrorKind, E)>,
+) -> Result<T, E> {
+    let mut backoff = initial_backoff;
+    let mut num_retries = 0;
+    loop {
+        match f(num_retries == max_retries) {
+            Ok(value) => return Ok(value),
+            Err((BridgeErrorKind::Transient, _))
+                if num_retries < max_retries =>
+            {
+                std::thread::sleep(backoff);
+                backoff *= 2;
+                num_retries += 1;
+            }
+            Err((_, err)) => return Err(err),
+        }
+    }
+}
+
 impl From<ffi::Tx> for Tx {
     fn from(tx: ffi::Tx) -> Self {
         Tx::with_txid(
@@ -72,3 +130,82 @@ impl From<ffi::Coin> for Coin {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::{
+        ffi::BridgeTxStatus,
+        util::{retry_with_backoff, BridgeErrorKind},
+    };
+
+    #[test]
+    fn test_bridge_error_kind_from_bridge_tx_status() {
+        assert_eq!(
+            BridgeErrorKind::from_bridge_tx_status(BridgeTxStatus::Ok),
+            None,
+        );
+        assert_eq!(
+            BridgeErrorKind::from_bridge_tx_status(BridgeTxStatus::Busy),
+            Some(BridgeErrorKind::Transient),
+        );
+        assert_eq!(
+            BridgeErrorKind::from_bridge_tx_status(BridgeTxStatus::MissingCoin),
+            Some(BridgeErrorKind::Permanent),
+        );
+        assert_eq!(
+            BridgeErrorKind::from_bridge_tx_status(BridgeTxStatus { repr: 99 }),
+            Some(BridgeErrorKind::Permanent),
+        );
+    }
+
+    #[test]
+    fn test_retry_with_backoff() {
+        let backoff = Duration::from_millis(10);
+
+        // Succeeds right away
+        let mut attempts = Vec::new();
+        let result = retry_with_backoff(3, backoff, |is_last| {
+            attempts.push(is_last);
+            Ok::<_, (BridgeErrorKind, &str)>(1)
+        });
+        assert_eq!(result, Ok(1));
+        assert_eq!(attempts, [false]);
+
+        // Transient errors are retried with backoff until the call succeeds
+        let mut attempts = Vec::new();
+        let start = Instant::now();
+        let result = retry_with_backoff(3, backoff, |is_last| {
+            attempts.push(is_last);
+            match attempts.len() {
+                1 | 2 => Err((BridgeErrorKind::Transient, "busy")),
+                _ => Ok(2),
+            }
+        });
+        assert_eq!(result, Ok(2));
+        assert_eq!(attempts, [false, false, false]);
+        // Slept 10ms, then 20ms
+        assert!(start.elapsed() >= Duration::from_millis(30));
+
+        // The last attempt is flagged, and its error is returned
+        let mut attempts = Vec::new();
+        let result = retry_with_backoff(2, backoff, |is_last| {
+            attempts.push(is_last);
+            Err::<(), _>((BridgeErrorKind::Transient, attempts.len()))
+        });
+        assert_eq!(result, Err(3));
+        assert_eq!(attempts, [false, false, true]);
+
+        // Permanent errors aren't retried
+        let mut attempts = Vec::new();
+        let start = Instant::now();
+        let result = retry_with_backoff(3, backoff, |is_last| {
+            attempts.push(is_last);
+            Err::<(), _>((BridgeErrorKind::Permanent, "missing coin"))
+        });
+        assert_eq!(result, Err("missing coin"));
+        assert_eq!(attempts, [false]);
+        assert!(start.elapsed() < backoff);
+    }
+}
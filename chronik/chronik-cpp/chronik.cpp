@@ -22,18 +22,41 @@ template <typename T, typename C> rust::Vec<T> ToRustVec(const C &container) {
 }
 
 bool Start(const Config &config, const node::NodeContext &node, bool fWipe) {
+    const std::string log_file = gArgs.GetArg("-chroniklogfile", "");
     return chronik_bridge::setup_chronik(
         {
             .datadir_net = gArgs.GetDataDirNet().u8string(),
+            .network = gArgs.GetChainName(),
             .hosts = ToRustVec<rust::String>(gArgs.IsArgSet("-chronikbind")
                                                  ? gArgs.GetArgs("-chronikbind")
                                                  : DEFAULT_BINDS),
             .default_port = BaseParams().ChronikPort(),
             .wipe_db = fWipe,
+            .validate_only = gArgs.GetBoolArg("-chronikvalidateonly", false),
+            .conflict_retention_blocks =
+                int32_t(gArgs.GetIntArg("-chronikconflictretention",
+                                        DEFAULT_CONFLICT_RETENTION_BLOCKS)),
+            .script_filter = ToRustVec<rust::String>(
+                gArgs.GetArgs("-chronikscriptfilter")),
+            .request_timeout_secs = gArgs.GetIntArg(
+                "-chronikrequesttimeout", DEFAULT_REQUEST_TIMEOUT_SECS),
+            .route_timeouts = ToRustVec<rust::String>(
+                gArgs.GetArgs("-chronikroutetimeout")),
+            .debug_stats = gArgs.GetBoolArg("-chronikdebugstats", false),
+            .log_file =
+                log_file.empty()
+                    ? ""
+                    : AbsPathForConfigVal(fs::PathFromString(log_file))
+                          .u8string(),
+            .log_debug = LogInstance().WillLogCategory(BCLog::CHRONIK),
         },
         config, node);
 }
 
+void RequestReopenLogFile() {
+    chronik_bridge::request_reopen_log_sink();
+}
+
 void Stop() {
     LogPrintf("Stopping Chronik...\n");
     StopChronikValidationInterface();
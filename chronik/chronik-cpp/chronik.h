@@ -5,6 +5,7 @@
 #ifndef BITCOIN_CHRONIK_CPP_CHRONIK_H
 #define BITCOIN_CHRONIK_CPP_CHRONIK_H
 
+#include <cstdint>
 #include <string>
 #include <vector>
 
@@ -17,9 +18,20 @@ namespace chronik {
 
 static const std::vector<std::string> DEFAULT_BINDS = {"127.0.0.1", "::1"};
 
+// How many blocks to keep resolved mempool conflicts around for
+static const int DEFAULT_CONFLICT_RETENTION_BLOCKS = 144;
+
+// How many seconds a Chronik HTTP request may take before it is answered with
+// 504 Gateway Timeout, must match DEFAULT_REQUEST_TIMEOUT in chronik-http
+static const int64_t DEFAULT_REQUEST_TIMEOUT_SECS = 30;
+
 // Registers Chronik indexer as ValidationInterface, listens to HTTP queries
 bool Start(const Config &config, const node::NodeContext &node, bool fWipe);
 
+// Reopens the -chroniklogfile before the next log message, e.g. for log
+// rotation. Safe to call from a signal handler.
+void RequestReopenLogFile();
+
 // Unregisters Chronik indexer as ValidationInterface, stops the HTTP server
 void Stop();
 
@@ -14,10 +14,16 @@
 #include <node/blockstorage.h>
 #include <node/coin.h>
 #include <node/context.h>
+#include <node/transaction.h>
 #include <node/ui_interface.h>
+#include <policy/policy.h>
+#include <primitives/block.h>
 #include <shutdown.h>
 #include <streams.h>
+#include <sync.h>
+#include <txmempool.h>
 #include <undo.h>
+#include <util/error.h>
 #include <validation.h>
 
 chronik_bridge::OutPoint BridgeOutPoint(const COutPoint &outpoint) {
@@ -143,6 +149,7 @@ chronik_bridge::Block BridgeBlock(const CBlock &block,
 
     return {.hash = chronik::util::HashToArray(block.GetHash()),
             .prev_hash = chronik::util::HashToArray(block.hashPrevBlock),
+            .merkle_root = chronik::util::HashToArray(block.hashMerkleRoot),
             .n_bits = block.nBits,
             .timestamp = block.GetBlockTime(),
             .height = bindex.nHeight,
@@ -199,22 +206,69 @@ ChronikBridge::load_block(const CBlockIndex &bindex) const {
     return std::make_unique<CBlock>(std::move(block));
 }
 
-Tx ChronikBridge::bridge_tx(const CTransaction &tx) const {
+BridgeTxResult ChronikBridge::bridge_tx(const CTransaction &tx,
+                                        bool wait_for_locks) const {
     std::map<COutPoint, ::Coin> coins;
     for (const CTxIn &input : tx.vin) {
         coins[input.prevout];
     }
-    FindCoins(m_node, coins);
+    if (wait_for_locks) {
+        FindCoins(m_node, coins);
+    } else {
+        // Both locks are recursive, so FindCoins can take them again
+        TRY_LOCK(cs_main, lock_main);
+        if (!lock_main) {
+            return {.status = BridgeTxStatus::Busy, .tx = {}};
+        }
+        TRY_LOCK(m_node.mempool->cs, lock_mempool);
+        if (!lock_mempool) {
+            return {.status = BridgeTxStatus::Busy, .tx = {}};
+        }
+        FindCoins(m_node, coins);
+    }
     std::vector<::Coin> spent_coins;
     spent_coins.reserve(tx.vin.size());
     for (const CTxIn &input : tx.vin) {
         const ::Coin &coin = coins[input.prevout];
         if (coin.GetTxOut().IsNull()) {
-            throw std::runtime_error("Couldn't find coin for input");
+            // The tx has been mined or evicted since it was added to the
+            // mempool, so its coins are gone for good
+            return {.status = BridgeTxStatus::MissingCoin, .tx = {}};
         }
         spent_coins.push_back(coin);
     }
-    return BridgeTx(false, tx, spent_coins);
+    return {.status = BridgeTxStatus::Ok,
+            .tx = BridgeTx(false, tx, spent_coins)};
+}
+
+std::array<uint8_t, 32>
+ChronikBridge::broadcast_tx(rust::Slice<const uint8_t> raw_tx) const {
+    std::vector<uint8_t> vec = chronik::util::FromRustSlice(raw_tx);
+    CDataStream stream{vec, SER_NETWORK, PROTOCOL_VERSION};
+    CMutableTransaction mtx;
+    stream >> mtx;
+    if (!stream.empty()) {
+        throw std::runtime_error("Unexpected trailing data after tx");
+    }
+    CTransactionRef tx = MakeTransactionRef(std::move(mtx));
+    const Amount max_fee = node::DEFAULT_MAX_RAW_TX_FEE_RATE.GetFee(
+        GetVirtualTransactionSize(*tx));
+    std::string err_str;
+    // BroadcastTransaction doesn't modify the NodeContext itself, it just
+    // requires a non-const reference to access the mempool and connman.
+    // Wait for the validation interface callbacks, so the tx is queued for
+    // Chronik's indexer once this returns.
+    const TransactionError error = node::BroadcastTransaction(
+        const_cast<node::NodeContext &>(m_node), GetConfig(), tx, err_str,
+        max_fee, /*relay=*/true, /*wait_callback=*/true);
+    if (error != TransactionError::OK) {
+        std::string msg = TransactionErrorString(error).original;
+        if (!err_str.empty()) {
+            msg += ": " + err_str;
+        }
+        throw std::runtime_error(msg);
+    }
+    return chronik::util::HashToArray(tx->GetId());
 }
 
 const CBlockIndex &ChronikBridge::find_fork(const CBlockIndex &index) const {
@@ -264,6 +318,40 @@ rust::Vec<uint8_t> load_raw_tx(uint32_t file_num, uint32_t data_pos) {
     return chronik::util::ToRustVec<uint8_t>(raw_tx);
 }
 
+rust::Vec<uint8_t> load_raw_block(uint32_t file_num, uint32_t data_pos) {
+    CAutoFile file(node::OpenBlockFile(FlatFilePos(file_num, data_pos),
+                                       /*fReadOnly=*/true),
+                   SER_DISK, CLIENT_VERSION);
+    if (file.IsNull()) {
+        throw std::runtime_error("Opening block file failed");
+    }
+    CBlock block;
+    file >> block;
+    CDataStream raw_block{SER_NETWORK, PROTOCOL_VERSION};
+    raw_block << block;
+    return chronik::util::ToRustVec<uint8_t>(raw_block);
+}
+
+std::array<uint8_t, 80> load_block_header(uint32_t file_num,
+                                          uint32_t data_pos) {
+    CAutoFile file(node::OpenBlockFile(FlatFilePos(file_num, data_pos),
+                                       /*fReadOnly=*/true),
+                   SER_DISK, CLIENT_VERSION);
+    if (file.IsNull()) {
+        throw std::runtime_error("Opening block file failed");
+    }
+    CBlockHeader header;
+    file >> header;
+    CDataStream raw_header{SER_NETWORK, PROTOCOL_VERSION};
+    raw_header << header;
+    std::array<uint8_t, 80> array;
+    if (raw_header.size() != array.size()) {
+        throw std::runtime_error("Unexpected block header size");
+    }
+    std::copy(raw_header.begin(), raw_header.end(), array.begin());
+    return array;
+}
+
 BlockInfo get_block_info(const CBlockIndex &bindex) {
     return {
         .hash = chronik::util::HashToArray(bindex.GetBlockHash()),
@@ -304,4 +392,8 @@ void abort_node(const rust::Str msg, const rust::Str user_msg) {
     AbortNode(std::string(msg), Untranslated(std::string(user_msg)));
 }
 
+bool shutdown_requested() {
+    return ShutdownRequested();
+}
+
 } // namespace chronik_bridge
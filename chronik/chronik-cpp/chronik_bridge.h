@@ -26,6 +26,7 @@ namespace chronik_bridge {
 
 struct BlockInfo;
 struct Block;
+struct BridgeTxResult;
 struct Tx;
 
 class block_index_not_found : public std::exception {
@@ -60,7 +61,11 @@ public:
 
     std::unique_ptr<CBlock> load_block(const CBlockIndex &bindex) const;
 
-    Tx bridge_tx(const CTransaction &tx) const;
+    BridgeTxResult bridge_tx(const CTransaction &tx,
+                             bool wait_for_locks) const;
+
+    std::array<uint8_t, 32>
+    broadcast_tx(rust::Slice<const uint8_t> raw_tx) const;
 
     const CBlockIndex &find_fork(const CBlockIndex &index) const;
 };
@@ -73,6 +78,11 @@ Block bridge_block(const CBlock &block, const CBlockIndex &bindex);
 Tx load_tx(uint32_t file_num, uint32_t data_pos, uint32_t undo_pos);
 rust::Vec<uint8_t> load_raw_tx(uint32_t file_num, uint32_t data_pos);
 
+rust::Vec<uint8_t> load_raw_block(uint32_t file_num, uint32_t data_pos);
+
+std::array<uint8_t, 80> load_block_header(uint32_t file_num,
+                                          uint32_t data_pos);
+
 BlockInfo get_block_info(const CBlockIndex &index);
 
 const CBlockIndex &get_block_ancestor(const CBlockIndex &index, int32_t height);
@@ -85,6 +95,8 @@ bool init_error(const rust::Str msg);
 
 void abort_node(const rust::Str msg, const rust::Str user_msg);
 
+bool shutdown_requested();
+
 } // namespace chronik_bridge
 
 #endif // BITCOIN_CHRONIK_CPP_CHRONIK_BRIDGE_H
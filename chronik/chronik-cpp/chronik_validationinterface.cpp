@@ -39,7 +39,8 @@ private:
                                        MemPoolRemovalReason reason,
                                        uint64_t mempool_sequence) override {
         m_chronik->handle_tx_removed_from_mempool(
-            chronik::util::HashToArray(ptx->GetId()));
+            chronik::util::HashToArray(ptx->GetId()),
+            reason == MemPoolRemovalReason::CONFLICT);
     }
 
     void BlockConnected(const std::shared_ptr<const CBlock> &block,
@@ -6,7 +6,11 @@
 //! Read and write operations should exclusively be done with dedicated writers
 //! and readers, such as [`crate::io::BlockWriter`].
 
-use std::path::Path;
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use abc_rust_error::Result;
 pub use rocksdb::WriteBatch;
@@ -54,6 +58,7 @@ pub(crate) type CF = rocksdb::ColumnFamily;
 pub struct Db {
     db: rocksdb::DB,
     cf_names: Vec<String>,
+    num_unflushed_batches: AtomicUsize,
 }
 
 /// Errors indicating something went wrong with the database itself.
@@ -70,10 +75,47 @@ pub enum DbError {
 
 use self::DbError::*;
 
+/// Size statistics of the [`Db`], as estimated by RocksDB.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DbStats {
+    /// Approximate size of the DB in bytes; size of the SST files on disk plus
+    /// the memtables not flushed yet.
+    pub approx_size_bytes: u64,
+    /// Estimated number of keys in each column family, by name.
+    pub num_keys_per_column: BTreeMap<String, u64>,
+    /// Approximate size of each column family in bytes, by name.
+    pub size_bytes_per_column: BTreeMap<String, u64>,
+}
+
 impl Db {
     /// Opens the database under the specified path.
     /// Creates the database file and necessary column families if necessary.
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_cfs(path, Self::all_cfs())
+    }
+
+    /// Opens an existing database under the specified path in read-only mode.
+    /// Fails if the database doesn't exist. Any write will fail.
+    pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self> {
+        let cf_names = Self::all_cfs()
+            .iter()
+            .map(|cf| cf.name().to_string())
+            .collect::<Vec<_>>();
+        let db = rocksdb::DB::open_cf_for_read_only(
+            &rocksdb::Options::default(),
+            path,
+            &cf_names,
+            false,
+        )
+        .map_err(RocksDb)?;
+        Ok(Db {
+            db,
+            cf_names,
+            num_unflushed_batches: AtomicUsize::new(0),
+        })
+    }
+
+    fn all_cfs() -> Vec<ColumnFamilyDescriptor> {
         let mut cfs = Vec::new();
         BlockWriter::add_cfs(&mut cfs);
         BlockStatsWriter::add_cfs(&mut cfs);
@@ -82,7 +124,7 @@ impl Db {
         ScriptHistoryWriter::add_cfs(&mut cfs);
         ScriptUtxoWriter::add_cfs(&mut cfs);
         SpentByWriter::add_cfs(&mut cfs);
-        Self::open_with_cfs(path, cfs)
+        cfs
     }
 
     pub(crate) fn open_with_cfs(
@@ -93,13 +135,20 @@ impl Db {
         let cf_names = cfs.iter().map(|cf| cf.name().to_string()).collect();
         let db = rocksdb::DB::open_cf_descriptors(&db_options, path, cfs)
             .map_err(RocksDb)?;
-        Ok(Db { db, cf_names })
+        Ok(Db {
+            db,
+            cf_names,
+            num_unflushed_batches: AtomicUsize::new(0),
+        })
     }
 
     fn db_options() -> rocksdb::Options {
         let mut db_options = rocksdb::Options::default();
         db_options.create_if_missing(true);
         db_options.create_missing_column_families(true);
+        // Keep the WAL in memory until explicitly flushed, so that writes can
+        // be grouped using Db::write_batch_deferred
+        db_options.set_manual_wal_flush(true);
         db_options
     }
 
@@ -125,6 +174,15 @@ impl Db {
             .ok_or_else(|| NoSuchColumnFamily(name.to_string()))?)
     }
 
+    /// Names of all the column families of the DB.
+    pub fn cf_names(&self) -> &[String] {
+        &self.cf_names
+    }
+
+    pub(crate) fn snapshot(&self) -> rocksdb::Snapshot<'_> {
+        self.db.snapshot()
+    }
+
     pub(crate) fn get(
         &self,
         cf: &CF,
@@ -155,7 +213,77 @@ impl Db {
 
     /// Writes the batch to the Db atomically.
     pub fn write_batch(&self, write_batch: WriteBatch) -> Result<()> {
+        self.write_batch_deferred(write_batch)?;
+        self.flush_wal()
+    }
+
+    /// Writes the batch to the Db atomically, but keeps it in the in-memory
+    /// write-ahead log until [`Db::flush_wal`] or [`Db::write_batch`] is
+    /// called.
+    ///
+    /// The batch is visible to readers immediately, but would be lost if the
+    /// process crashes before the WAL is flushed. As the WAL is replayed in
+    /// order, the DB is still consistent after a crash, it just ends at an
+    /// earlier batch.
+    pub fn write_batch_deferred(&self, write_batch: WriteBatch) -> Result<()> {
         self.db.write(write_batch).map_err(RocksDb)?;
+        self.num_unflushed_batches.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Flush the in-memory write-ahead log to the WAL file, making all writes
+    /// so far survive a crash of the process.
+    pub fn flush_wal(&self) -> Result<()> {
+        self.db.flush_wal(false).map_err(RocksDb)?;
+        self.num_unflushed_batches.store(0, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Number of batches written with [`Db::write_batch_deferred`] that
+    /// haven't been flushed to the WAL file yet.
+    pub fn num_unflushed_batches(&self) -> usize {
+        self.num_unflushed_batches.load(Ordering::SeqCst)
+    }
+
+    /// Estimate the size of the DB and the size and number of keys of each
+    /// column family.
+    ///
+    /// These are cheap estimates maintained by RocksDB, e.g. overwritten or
+    /// deleted keys are counted until they're compacted away.
+    pub fn stats(&self) -> Result<DbStats> {
+        let mut stats = DbStats::default();
+        for cf_name in &self.cf_names {
+            let cf = self.cf(cf_name)?;
+            let property = |name: &str| -> Result<u64> {
+                let value =
+                    self.db.property_int_value_cf(cf, name).map_err(RocksDb)?;
+                Ok(value.unwrap_or_default())
+            };
+            let sst_size = property("rocksdb.total-sst-files-size")?;
+            let memtable_size = property("rocksdb.size-all-mem-tables")?;
+            stats.approx_size_bytes += sst_size + memtable_size;
+            stats
+                .size_bytes_per_column
+                .insert(cf_name.clone(), sst_size + memtable_size);
+            stats.num_keys_per_column.insert(
+                cf_name.clone(),
+                property("rocksdb.estimate-num-keys")?,
+            );
+        }
+        Ok(stats)
+    }
+
+    /// Flush the memtables of all column families to disk and compact them,
+    /// removing overwritten and deleted entries.
+    ///
+    /// This can take a long time for big databases, and fails if the DB has
+    /// been opened read-only.
+    pub fn compact(&self) -> Result<()> {
+        for cf_name in &self.cf_names {
+            let cf = self.cf(cf_name)?;
+            self.db.flush_cf(cf).map_err(RocksDb)?;
+            self.db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+        }
         Ok(())
     }
 
@@ -174,3 +302,34 @@ impl Db {
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use abc_rust_error::Result;
+
+    use crate::db::{Db, WriteBatch, CF_META};
+
+    #[test]
+    fn test_write_batch_deferred() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-db--deferred")?;
+        let db = Db::open(tempdir.path())?;
+        let cf = db.cf(CF_META)?;
+        for key in [b"a", b"b"] {
+            let mut batch = WriteBatch::default();
+            batch.put_cf(cf, key, b"value");
+            db.write_batch_deferred(batch)?;
+            // Visible before the WAL is flushed
+            assert_eq!(db.get(cf, key)?.as_deref(), Some(b"value".as_ref()));
+        }
+        assert_eq!(db.num_unflushed_batches(), 2);
+        db.flush_wal()?;
+        assert_eq!(db.num_unflushed_batches(), 0);
+        std::mem::drop(db);
+
+        let db = Db::open(tempdir.path())?;
+        let cf = db.cf(CF_META)?;
+        assert_eq!(db.get(cf, b"a")?.as_deref(), Some(b"value".as_ref()));
+        assert_eq!(db.get(cf, b"b")?.as_deref(), Some(b"value".as_ref()));
+        Ok(())
+    }
+}
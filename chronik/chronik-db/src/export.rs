@@ -0,0 +1,404 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+//! Module for [`export_db`] and [`import_db`], to move a [`Db`] between
+//! machines without copying its files, which can be inconsistent if taken
+//! while the node is running.
+//!
+//! The export is a stream of:
+//! 1. [`EXPORT_MAGIC`]
+//! 2. For every entry of every column family: `0x01`, the column family name
+//!    (1-byte length + UTF-8), the key and the value (each 4-byte
+//!    little-endian length + bytes).
+//! 3. `0x00`, followed by the 8-byte little-endian SeaHash of all bytes
+//!    before it, which is verified on import.
+//!
+//! While importing, [`FIELD_IMPORT_IN_PROGRESS`] is set in [`CF_META`], and
+//! it's only removed once the checksum has been verified. This way, a DB left
+//! behind by an import that has been interrupted (e.g. by a crash) can be
+//! detected and rejected.
+
+use std::{
+    hash::Hasher,
+    io::{Read, Write},
+};
+
+use abc_rust_error::Result;
+use rocksdb::{Direction, IteratorMode, WriteBatch};
+use seahash::SeaHasher;
+use thiserror::Error;
+
+use crate::{
+    db::{Db, DbError, CF_META},
+    io::{MetadataWriter, FIELD_IMPORT_IN_PROGRESS},
+};
+
+/// Magic bytes at the start of every export.
+pub const EXPORT_MAGIC: [u8; 8] = *b"CHRNKEXP";
+
+/// Number of bytes of a key or value read at once when importing, so a
+/// corrupted length doesn't allocate more memory than there is data.
+const IMPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Number of entries written to the DB at once when importing.
+const IMPORT_BATCH_SIZE: usize = 10_000;
+
+const TAG_END: u8 = 0x00;
+const TAG_ENTRY: u8 = 0x01;
+
+/// Errors for [`export_db`] and [`import_db`].
+#[derive(Debug, Eq, Error, PartialEq)]
+pub enum ExportError {
+    /// Import data doesn't start with [`EXPORT_MAGIC`]
+    #[error("Invalid export: Doesn't start with the export magic bytes")]
+    InvalidMagic,
+
+    /// Import data has a record other than an entry or the end
+    #[error("Invalid export: Unknown record tag 0x{0:02x}")]
+    InvalidTag(u8),
+
+    /// Column family name in the import data isn't valid UTF-8
+    #[error("Invalid export: Column family name is not UTF-8")]
+    InvalidColumnFamilyName,
+
+    /// Import data ends in the middle of a key or value
+    #[error(
+        "Invalid export: Data ends after {actual} bytes, but expected \
+         {expected} bytes"
+    )]
+    UnexpectedEnd {
+        /// Length of the key or value according to the export
+        expected: usize,
+        /// Number of bytes left in the export
+        actual: usize,
+    },
+
+    /// Column family name, key or value too long to be exported
+    #[error("Cannot export: {0} bytes is too long")]
+    DataTooLong(usize),
+
+    /// Checksum at the end of the import data doesn't match the data
+    #[error(
+        "Invalid export: Checksum mismatch, expected {expected:016x} but the \
+         data has {actual:016x}"
+    )]
+    ChecksumMismatch {
+        /// Checksum stored at the end of the export
+        expected: u64,
+        /// Checksum computed from the data
+        actual: u64,
+    },
+
+    /// Importing would mix the imported entries with existing ones
+    #[error("Cannot import into a non-empty DB, column family {0} has data")]
+    ImportIntoNonEmptyDb(String),
+}
+
+use self::ExportError::*;
+
+/// Export all entries of the DB to `writer`, see the [module docs](self) for
+/// the format.
+///
+/// The entries are read from a snapshot, so the export is consistent even if
+/// the DB is written to at the same time.
+pub fn export_db(db: &Db, writer: impl Write) -> Result<()> {
+    let snapshot = db.snapshot();
+    let mut writer = HashingWriter {
+        writer,
+        hasher: SeaHasher::new(),
+    };
+    writer.write_all(&EXPORT_MAGIC)?;
+    for cf_name in db.cf_names() {
+        let cf = db.cf(cf_name)?;
+        for entry in snapshot.full_iterator_cf(cf, IteratorMode::Start) {
+            let (key, value) = entry.map_err(DbError::RocksDb)?;
+            writer.write_all(&[TAG_ENTRY])?;
+            let cf_name_len = u8::try_from(cf_name.len())
+                .map_err(|_| DataTooLong(cf_name.len()))?;
+            writer.write_all(&[cf_name_len])?;
+            writer.write_all(cf_name.as_bytes())?;
+            writer.write_data(&key)?;
+            writer.write_data(&value)?;
+        }
+    }
+    writer.write_all(&[TAG_END])?;
+    let checksum = writer.hasher.finish();
+    writer.writer.write_all(&checksum.to_le_bytes())?;
+    writer.writer.flush()?;
+    Ok(())
+}
+
+/// Import the entries exported with [`export_db`] from `reader` into the DB.
+///
+/// The DB must be empty, except for [`CF_META`], whose entries are deleted
+/// before importing, so no metadata of the DB (e.g. the network or the
+/// schema version) outlives the import. If the import fails, e.g. because
+/// the checksum doesn't match, all entries of the DB are deleted again,
+/// including [`CF_META`]. If the import is interrupted,
+/// [`FIELD_IMPORT_IN_PROGRESS`] stays set, see the [module docs](self).
+pub fn import_db(db: &Db, reader: impl Read) -> Result<()> {
+    for cf_name in db.cf_names() {
+        if cf_name == CF_META {
+            continue;
+        }
+        let cf = db.cf(cf_name)?;
+        if db.iterator(cf, &[], Direction::Forward).next().is_some() {
+            return Err(ImportIntoNonEmptyDb(cf_name.clone()).into());
+        }
+    }
+    clear_db(db)?;
+    let metadata_writer = MetadataWriter::new(db)?;
+    let mut batch = WriteBatch::default();
+    metadata_writer.update_import_in_progress(&mut batch, true)?;
+    db.write_batch(batch)?;
+    match import_entries(db, &metadata_writer, reader) {
+        Ok(()) => db.flush_wal(),
+        Err(err) => {
+            clear_db(db)?;
+            Err(err)
+        }
+    }
+}
+
+fn import_entries(
+    db: &Db,
+    metadata_writer: &MetadataWriter<'_>,
+    reader: impl Read,
+) -> Result<()> {
+    let mut reader = HashingReader {
+        reader,
+        hasher: SeaHasher::new(),
+    };
+    let mut magic = [0; EXPORT_MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if magic != EXPORT_MAGIC {
+        return Err(InvalidMagic.into());
+    }
+    let mut batch = WriteBatch::default();
+    loop {
+        match reader.read_u8()? {
+            TAG_END => break,
+            TAG_ENTRY => {
+                let mut cf_name = vec![0; reader.read_u8()? as usize];
+                reader.read_exact(&mut cf_name)?;
+                let cf_name = String::from_utf8(cf_name)
+                    .map_err(|_| InvalidColumnFamilyName)?;
+                let cf = db.cf(&cf_name)?;
+                let key = reader.read_data()?;
+                let value = reader.read_data()?;
+                if cf_name == CF_META && key == FIELD_IMPORT_IN_PROGRESS {
+                    // Exported while importing, the marker is ours to clear
+                    continue;
+                }
+                batch.put_cf(cf, key, value);
+            }
+            tag => return Err(InvalidTag(tag).into()),
+        }
+        if batch.len() >= IMPORT_BATCH_SIZE {
+            db.write_batch_deferred(std::mem::take(&mut batch))?;
+        }
+    }
+    let actual = reader.hasher.finish();
+    let mut expected = [0; 8];
+    reader.reader.read_exact(&mut expected)?;
+    let expected = u64::from_le_bytes(expected);
+    if expected != actual {
+        return Err(ChecksumMismatch { expected, actual }.into());
+    }
+    // In the same batch as the last entries, so once the marker is gone, all
+    // entries have been written
+    metadata_writer.update_import_in_progress(&mut batch, false)?;
+    db.write_batch_deferred(batch)?;
+    Ok(())
+}
+
+/// Delete all entries of all column families of the DB, including
+/// [`CF_META`].
+pub fn clear_db(db: &Db) -> Result<()> {
+    let mut batch = WriteBatch::default();
+    for cf_name in db.cf_names() {
+        let cf = db.cf(cf_name)?;
+        for entry in db.iterator(cf, &[], Direction::Forward) {
+            let (key, _) = entry?;
+            batch.delete_cf(cf, key);
+        }
+    }
+    db.write_batch(batch)
+}
+
+struct HashingWriter<W> {
+    writer: W,
+    hasher: SeaHasher,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn write_all(&mut self, data: &[u8]) -> Result<()> {
+        self.hasher.write(data);
+        self.writer.write_all(data)?;
+        Ok(())
+    }
+
+    fn write_data(&mut self, data: &[u8]) -> Result<()> {
+        let len =
+            u32::try_from(data.len()).map_err(|_| DataTooLong(data.len()))?;
+        self.write_all(&len.to_le_bytes())?;
+        self.write_all(data)
+    }
+}
+
+struct HashingReader<R> {
+    reader: R,
+    hasher: SeaHasher,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn read_exact(&mut self, data: &mut [u8]) -> Result<()> {
+        self.reader.read_exact(data)?;
+        self.hasher.write(data);
+        Ok(())
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        let mut byte = [0];
+        self.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn read_data(&mut self) -> Result<Vec<u8>> {
+        let mut len = [0; 4];
+        self.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+        // Read in chunks instead of allocating `len` bytes upfront, which can
+        // be up to 4 GiB for corrupted data
+        let mut data = Vec::with_capacity(len.min(IMPORT_CHUNK_SIZE));
+        while data.len() < len {
+            let chunk_len = (len - data.len()).min(IMPORT_CHUNK_SIZE);
+            let num_read = (&mut self.reader)
+                .take(chunk_len as u64)
+                .read_to_end(&mut data)?;
+            if num_read < chunk_len {
+                return Err(UnexpectedEnd {
+                    expected: len,
+                    actual: data.len(),
+                }
+                .into());
+            }
+        }
+        self.hasher.write(&data);
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use abc_rust_error::Result;
+    use rocksdb::Direction;
+
+    use crate::{
+        db::{Db, WriteBatch, CF_BLK, CF_META, CF_TX},
+        export::{export_db, import_db, ExportError, EXPORT_MAGIC},
+        io::MetadataReader,
+    };
+
+    fn db_entries(db: &Db) -> Result<Vec<(String, Box<[u8]>, Box<[u8]>)>> {
+        let mut entries = Vec::new();
+        for cf_name in db.cf_names() {
+            let cf = db.cf(cf_name)?;
+            for entry in db.iterator(cf, &[], Direction::Forward) {
+                let (key, value) = entry?;
+                entries.push((cf_name.clone(), key, value));
+            }
+        }
+        Ok(entries)
+    }
+
+    #[test]
+    fn test_export_import() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-db--export")?;
+        let db = Db::open(tempdir.path().join("db"))?;
+        let mut batch = WriteBatch::default();
+        batch.put_cf(db.cf(CF_META)?, b"version", b"7");
+        batch.put_cf(db.cf(CF_BLK)?, b"a", b"");
+        batch.put_cf(db.cf(CF_BLK)?, b"b", vec![0xab; 300]);
+        batch.put_cf(db.cf(CF_TX)?, b"", b"tx");
+        db.write_batch(batch)?;
+        let entries = db_entries(&db)?;
+        assert_eq!(entries.len(), 4);
+
+        let mut export = Vec::new();
+        export_db(&db, &mut export)?;
+        assert_eq!(export[..8], EXPORT_MAGIC);
+
+        // Import into a fresh DB (which may have metadata, which is dropped)
+        let import_db_at = |name: &str, data: &[u8]| -> Result<(Db, _)> {
+            let db = Db::open(tempdir.path().join(name))?;
+            let mut batch = WriteBatch::default();
+            batch.put_cf(db.cf(CF_META)?, b"version", b"8");
+            batch.put_cf(db.cf(CF_META)?, b"stale", b"meta");
+            db.write_batch(batch)?;
+            let result = import_db(&db, data);
+            Ok((db, result))
+        };
+        let (imported, result) = import_db_at("imported", &export)?;
+        result?;
+        assert_eq!(db_entries(&imported)?, entries);
+        assert!(!MetadataReader::new(&imported)?.is_import_in_progress()?);
+
+        // Importing into a DB with data fails and leaves it untouched
+        assert_eq!(
+            import_db(&imported, export.as_slice())
+                .unwrap_err()
+                .downcast::<ExportError>()?,
+            ExportError::ImportIntoNonEmptyDb("blk".to_string()),
+        );
+        assert_eq!(db_entries(&imported)?, entries);
+
+        // Flipped bit in the first key: checksum mismatch, DB is cleared
+        let mut corrupted = export.clone();
+        corrupted[17] ^= 1;
+        let (corrupted_db, result) = import_db_at("corrupted", &corrupted)?;
+        assert!(matches!(
+            result.unwrap_err().downcast::<ExportError>()?,
+            ExportError::ChecksumMismatch { .. },
+        ));
+        assert_eq!(db_entries(&corrupted_db)?, vec![]);
+
+        // Bad magic
+        let mut bad_magic = export.clone();
+        bad_magic[0] = b'X';
+        let (bad_magic_db, result) = import_db_at("bad_magic", &bad_magic)?;
+        assert_eq!(
+            result.unwrap_err().downcast::<ExportError>()?,
+            ExportError::InvalidMagic,
+        );
+        assert_eq!(db_entries(&bad_magic_db)?, vec![]);
+
+        // Truncated export fails reading, DB is cleared
+        let truncated = &export[..export.len() - 1];
+        let (truncated_db, result) = import_db_at("truncated", truncated)?;
+        assert!(result.is_err());
+        assert_eq!(db_entries(&truncated_db)?, vec![]);
+
+        // Key claiming to be 4 GiB long, but the data ends after 3 bytes
+        let huge_key = [
+            EXPORT_MAGIC.as_ref(),
+            &[0x01, 3],
+            b"blk",
+            &u32::MAX.to_le_bytes(),
+            b"abc",
+        ]
+        .concat();
+        let (huge_key_db, result) = import_db_at("huge_key", &huge_key)?;
+        assert_eq!(
+            result.unwrap_err().downcast::<ExportError>()?,
+            ExportError::UnexpectedEnd {
+                expected: u32::MAX as usize,
+                actual: 3,
+            },
+        );
+        assert_eq!(db_entries(&huge_key_db)?, vec![]);
+
+        Ok(())
+    }
+}
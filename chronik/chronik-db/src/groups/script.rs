@@ -2,7 +2,16 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
-use bitcoinsuite_core::script::Script;
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::Arc,
+};
+
+use bitcoinsuite_core::{
+    hash::{Hashed, Sha256},
+    script::Script,
+    ser::BitcoinSer,
+};
 
 use crate::{
     db::{CF_SCRIPT_HISTORY, CF_SCRIPT_UTXO},
@@ -26,14 +35,36 @@ pub type ScriptHistoryReader<'a> = GroupHistoryReader<'a, ScriptGroup>;
 pub type ScriptUtxoWriter<'a> = GroupUtxoWriter<'a, ScriptGroup>;
 /// Read the UTXOs of scripts in the DB
 pub type ScriptUtxoReader<'a> = GroupUtxoReader<'a, ScriptGroup>;
-/// Function ptr to compress scripts
-pub type FnCompressScript = fn(&Script) -> Vec<u8>;
+/// Function to compress scripts.
+///
+/// Usually wired to the node's script compression via FFI, but any closure
+/// can be used, e.g. an identity function in tests.
+pub type FnCompressScript = Arc<dyn Fn(&Script) -> Vec<u8> + Send + Sync>;
+/// Predicate deciding which scripts to index, see
+/// [`ScriptGroup::with_filter`].
+pub type FnFilterScript = Arc<dyn Fn(&Script) -> bool + Send + Sync>;
+
+/// Predicate deciding which scripts to index, together with a fingerprint
+/// identifying it.
+///
+/// The fingerprint is stored in the DB, so an index built with one filter
+/// isn't silently opened with another one.
+#[derive(Clone)]
+pub struct ScriptFilter {
+    /// Identifies the filter, filters with the same fingerprint must match
+    /// the same scripts. Must not be empty.
+    pub fingerprint: Vec<u8>,
+    /// Predicate deciding whether a script is indexed.
+    pub fn_filter_script: FnFilterScript,
+}
 
 /// Group txs by input/output scripts.
 #[derive(Clone)]
 pub struct ScriptGroup {
     /// Function to compress scripts.
     fn_compress_script: FnCompressScript,
+    /// If set, only scripts matching this predicate are members of the group.
+    fn_filter_script: Option<FnFilterScript>,
 }
 
 impl Group for ScriptGroup {
@@ -48,6 +79,9 @@ impl Group for ScriptGroup {
         let mut input_scripts = Vec::with_capacity(query.tx.inputs.len());
         for (idx, input) in query.tx.inputs.iter().enumerate() {
             if let Some(coin) = &input.coin {
+                if !self.is_indexed(&coin.output.script) {
+                    continue;
+                }
                 input_scripts.push(MemberItem {
                     idx,
                     member: &coin.output.script,
@@ -60,7 +94,7 @@ impl Group for ScriptGroup {
     fn output_members<'a>(&self, query: GroupQuery<'a>) -> Self::Iter<'a> {
         let mut output_scripts = Vec::with_capacity(query.tx.outputs.len());
         for (idx, output) in query.tx.outputs.iter().enumerate() {
-            if !output.script.is_opreturn() {
+            if !output.script.is_opreturn() && self.is_indexed(&output.script) {
                 output_scripts.push(MemberItem {
                     idx,
                     member: &output.script,
@@ -91,13 +125,63 @@ impl Group for ScriptGroup {
 impl ScriptGroup {
     /// Create a new [`ScriptGroup`].
     pub fn new(fn_compress_script: FnCompressScript) -> Self {
-        ScriptGroup { fn_compress_script }
+        ScriptGroup {
+            fn_compress_script,
+            fn_filter_script: None,
+        }
+    }
+
+    /// Only group txs by the scripts matching `fn_filter_script`, all other
+    /// inputs and outputs are ignored.
+    ///
+    /// Used to only index the history and UTXOs of a fixed set of scripts,
+    /// which makes the index much smaller. Changing the filter of an existing
+    /// index leaves it inconsistent, it has to be reindexed.
+    pub fn with_filter(self, fn_filter_script: FnFilterScript) -> Self {
+        ScriptGroup {
+            fn_filter_script: Some(fn_filter_script),
+            ..self
+        }
+    }
+
+    fn is_indexed(&self, script: &Script) -> bool {
+        match &self.fn_filter_script {
+            Some(fn_filter_script) => fn_filter_script(script),
+            None => true,
+        }
+    }
+}
+
+/// [`ScriptFilter`] that matches exactly the given scripts.
+///
+/// The fingerprint is the SHA-256 of the sorted, deduplicated scripts, so it
+/// doesn't depend on the order the scripts are given in.
+pub fn filter_scripts(
+    scripts: impl IntoIterator<Item = Script>,
+) -> ScriptFilter {
+    let scripts = scripts.into_iter().collect::<BTreeSet<_>>();
+    let fingerprint =
+        Sha256::digest(scripts.iter().cloned().collect::<Vec<_>>().ser());
+    let scripts = scripts.into_iter().collect::<HashSet<_>>();
+    ScriptFilter {
+        fingerprint: fingerprint.as_le_bytes().to_vec(),
+        fn_filter_script: Arc::new(move |script| scripts.contains(script)),
+    }
+}
+
+impl std::fmt::Debug for ScriptFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptFilter")
+            .field("fingerprint", &hex::encode(&self.fingerprint))
+            .finish_non_exhaustive()
     }
 }
 
 impl std::fmt::Debug for ScriptGroup {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ScriptGroup").finish_non_exhaustive()
+        f.debug_struct("ScriptGroup")
+            .field("is_filtered", &self.fn_filter_script.is_some())
+            .finish_non_exhaustive()
     }
 }
 
@@ -108,6 +192,8 @@ pub fn prefix_mock_compress(script: &Script) -> Vec<u8> {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use bitcoinsuite_core::{
         script::Script,
         tx::{Coin, Tx, TxId, TxInput, TxMut, TxOutput},
@@ -115,12 +201,12 @@ mod tests {
 
     use crate::{
         group::{tx_members_for_group, Group, GroupQuery, MemberItem},
-        groups::{prefix_mock_compress, ScriptGroup},
+        groups::{filter_scripts, prefix_mock_compress, ScriptGroup},
     };
 
     #[test]
     fn test_script_group() {
-        let script_group = ScriptGroup::new(prefix_mock_compress);
+        let script_group = ScriptGroup::new(Arc::new(prefix_mock_compress));
         let tx = Tx::with_txid(
             TxId::from([0; 32]),
             TxMut {
@@ -210,4 +296,87 @@ mod tests {
             [b"COMPRESS:".as_ref(), &[0x53]].concat(),
         );
     }
+
+    #[test]
+    fn test_script_group_closure() {
+        let script_group =
+            ScriptGroup::new(Arc::new(|script: &Script| script.to_vec()));
+        let script = Script::new(vec![0x51, 0x52].into());
+        assert_eq!(script_group.ser_member(&&script), vec![0x51, 0x52]);
+        // Cloning shares the same function
+        assert_eq!(script_group.clone().ser_member(&&script), vec![0x51, 0x52]);
+    }
+
+    #[test]
+    fn test_script_group_filter() {
+        let make_script = |script: Vec<u8>| Script::new(script.into());
+        let script_group = ScriptGroup::new(Arc::new(prefix_mock_compress))
+            .with_filter(
+                filter_scripts([make_script(vec![0x51])]).fn_filter_script,
+            );
+        let tx = Tx::with_txid(
+            TxId::from([0; 32]),
+            TxMut {
+                inputs: [[0x51].as_ref(), &[0x52]]
+                    .into_iter()
+                    .map(|script| TxInput {
+                        coin: Some(Coin {
+                            output: TxOutput {
+                                script: Script::new(script.into()),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    })
+                    .collect(),
+                outputs: [[0x53].as_ref(), &[0x51]]
+                    .into_iter()
+                    .map(|script| TxOutput {
+                        script: Script::new(script.into()),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+        );
+        let query = GroupQuery {
+            is_coinbase: false,
+            tx: &tx,
+        };
+        assert_eq!(
+            script_group.input_members(query),
+            vec![MemberItem {
+                idx: 0,
+                member: &make_script(vec![0x51]),
+            }],
+        );
+        assert_eq!(
+            script_group.output_members(query),
+            vec![MemberItem {
+                idx: 1,
+                member: &make_script(vec![0x51]),
+            }],
+        );
+    }
+
+    #[test]
+    fn test_filter_scripts_fingerprint() {
+        let make_script = |script: &[u8]| Script::new(script.to_vec().into());
+        let fingerprint = |scripts: &[&[u8]]| {
+            filter_scripts(scripts.iter().map(|script| make_script(script)))
+                .fingerprint
+        };
+        let filter = filter_scripts([make_script(&[0x51])]);
+        assert!((filter.fn_filter_script)(&make_script(&[0x51])));
+        assert!(!(filter.fn_filter_script)(&make_script(&[0x52])));
+        assert_eq!(filter.fingerprint.len(), 32);
+        // Independent of order and duplicates
+        assert_eq!(
+            fingerprint(&[&[0x51], &[0x52]]),
+            fingerprint(&[&[0x52], &[0x51], &[0x52]]),
+        );
+        assert_ne!(fingerprint(&[&[0x51]]), fingerprint(&[&[0x52]]));
+        assert_ne!(fingerprint(&[&[0x51]]), fingerprint(&[&[0x51], &[0x52]]));
+    }
 }
@@ -271,6 +271,43 @@ impl<'a, G: Group> GroupHistoryReader<'a, G> {
     pub fn page_size(&self) -> usize {
         self.conf.page_size
     }
+
+    /// Iterate all serialized members starting with the given `prefix`, in
+    /// ascending byte order.
+    ///
+    /// This seeks to `prefix` and walks the keys of the column forward until
+    /// the prefix no longer matches, so only the matching range of the DB is
+    /// read. Each member is yielded once, regardless of its number of pages.
+    pub fn members_with_prefix(
+        &self,
+        prefix: &[u8],
+    ) -> impl Iterator<Item = Result<Vec<u8>>> + 'a {
+        let db: &'a Db = self.col.db;
+        let prefix = prefix.to_vec();
+        let mut last_member = None::<Vec<u8>>;
+        db.iterator(self.col.cf, &prefix, rocksdb::Direction::Forward)
+            .take_while({
+                let prefix = prefix.clone();
+                move |result| match result {
+                    Ok((key, _)) => key.starts_with(&prefix),
+                    Err(_) => true,
+                }
+            })
+            .filter_map(move |result| {
+                let (key, _) = match result {
+                    Ok(entry) => entry,
+                    Err(err) => return Some(Err(err)),
+                };
+                let member = &key[..key.len() - PAGE_SER_SIZE];
+                if !member.starts_with(&prefix)
+                    || last_member.as_deref() == Some(member)
+                {
+                    return None;
+                }
+                last_member = Some(member.to_vec());
+                Some(Ok(member.to_vec()))
+            })
+    }
 }
 
 fn key_for_member_page(member_ser: &[u8], page_num: PageNum) -> Vec<u8> {
@@ -448,6 +485,20 @@ mod tests {
         assert_eq!(read_page(40, 1)?, None);
         assert_eq!(read_num_pages_and_txs(40)?, (1, 4));
 
+        let members_with_prefix = |prefix: &[u8]| -> Result<Vec<Vec<u8>>> {
+            group_reader.members_with_prefix(prefix).collect()
+        };
+        // All values share the 7 leading zero bytes
+        assert_eq!(
+            members_with_prefix(&[0; 7])?,
+            [10, 20, 30, 40].map(|val| ser_value(val).to_vec()),
+        );
+        assert_eq!(
+            members_with_prefix(&ser_value(30))?,
+            vec![ser_value(30).to_vec()],
+        );
+        assert_eq!(members_with_prefix(&[1])?, Vec::<Vec<u8>>::new());
+
         // Remove all blocks
         disconnect_block(&block2)?;
         assert_eq!(read_page(10, 0)?, Some(vec![0, 1, 2, 3]));
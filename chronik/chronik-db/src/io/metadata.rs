@@ -3,10 +3,13 @@
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
 use abc_rust_error::Result;
+use bitcoinsuite_core::network::Network;
 use rocksdb::ColumnFamilyDescriptor;
+use thiserror::Error;
 
 use crate::{
     db::{Db, CF, CF_META},
+    io::BlockHeight,
     ser::{db_deserialize, db_serialize},
 };
 
@@ -16,6 +19,21 @@ pub type SchemaVersion = u64;
 /// Field in the `meta` cf storing the schema version.
 pub const FIELD_SCHEMA_VERSION: &[u8] = b"SCHEMA_VERSION";
 
+/// Field in the `meta` cf storing the network the DB has been built for.
+pub const FIELD_NETWORK: &[u8] = b"NETWORK";
+
+/// Field in the `meta` cf storing the fingerprint of the script filter the DB
+/// has been built with, empty if all scripts are indexed.
+pub const FIELD_SCRIPT_FILTER: &[u8] = b"SCRIPT_FILTER";
+
+/// Field in the `meta` cf storing the height of the highest block finalized
+/// by Avalanche.
+pub const FIELD_AVALANCHE_HEIGHT: &[u8] = b"AVALANCHE_HEIGHT";
+
+/// Field in the `meta` cf marking that an import into the DB hasn't finished
+/// yet, see [`crate::export::import_db`].
+pub const FIELD_IMPORT_IN_PROGRESS: &[u8] = b"IMPORT_IN_PROGRESS";
+
 /// Write database metadata
 pub struct MetadataWriter<'a> {
     cf: &'a CF,
@@ -27,6 +45,16 @@ pub struct MetadataReader<'a> {
     cf: &'a CF,
 }
 
+/// Errors for [`MetadataReader`].
+#[derive(Debug, Eq, Error, PartialEq)]
+pub enum MetadataError {
+    /// Network stored in the DB is unknown
+    #[error("Inconsistent DB: Unknown network {0:?}")]
+    UnknownNetwork(String),
+}
+
+use self::MetadataError::*;
+
 impl<'a> MetadataWriter<'a> {
     /// Create a writer to the database for metadata
     pub fn new(db: &'a Db) -> Result<Self> {
@@ -48,6 +76,51 @@ impl<'a> MetadataWriter<'a> {
         Ok(())
     }
 
+    /// Update the network the database has been built for
+    pub fn update_network(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        network: Network,
+    ) -> Result<()> {
+        batch.put_cf(self.cf, FIELD_NETWORK, network.name());
+        Ok(())
+    }
+
+    /// Update the fingerprint of the script filter the database has been
+    /// built with, empty if all scripts are indexed
+    pub fn update_script_filter(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        fingerprint: &[u8],
+    ) -> Result<()> {
+        batch.put_cf(self.cf, FIELD_SCRIPT_FILTER, fingerprint);
+        Ok(())
+    }
+
+    /// Update the height of the highest block finalized by Avalanche
+    pub fn update_avalanche_height(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        height: BlockHeight,
+    ) -> Result<()> {
+        batch.put_cf(self.cf, FIELD_AVALANCHE_HEIGHT, db_serialize(&height)?);
+        Ok(())
+    }
+
+    /// Mark that an import into the database is in progress, or that it
+    /// finished
+    pub fn update_import_in_progress(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        in_progress: bool,
+    ) -> Result<()> {
+        match in_progress {
+            true => batch.put_cf(self.cf, FIELD_IMPORT_IN_PROGRESS, []),
+            false => batch.delete_cf(self.cf, FIELD_IMPORT_IN_PROGRESS),
+        }
+        Ok(())
+    }
+
     pub(crate) fn add_cfs(columns: &mut Vec<ColumnFamilyDescriptor>) {
         columns.push(ColumnFamilyDescriptor::new(
             CF_META,
@@ -78,6 +151,42 @@ impl<'a> MetadataReader<'a> {
             None => Ok(None),
         }
     }
+
+    /// Read the network the database has been built for
+    pub fn network(&self) -> Result<Option<Network>> {
+        match self.db.get(self.cf, FIELD_NETWORK)? {
+            Some(name) => {
+                let name = String::from_utf8_lossy(&name);
+                let network = Network::from_name(&name)
+                    .ok_or_else(|| UnknownNetwork(name.into_owned()))?;
+                Ok(Some(network))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Read the height of the highest block finalized by Avalanche
+    pub fn avalanche_height(&self) -> Result<Option<BlockHeight>> {
+        match self.db.get(self.cf, FIELD_AVALANCHE_HEIGHT)? {
+            Some(ser_height) => Ok(Some(db_deserialize(&ser_height)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether an import into the database has been started but hasn't
+    /// finished, e.g. because the node crashed while importing
+    pub fn is_import_in_progress(&self) -> Result<bool> {
+        Ok(self.db.get(self.cf, FIELD_IMPORT_IN_PROGRESS)?.is_some())
+    }
+
+    /// Read the fingerprint of the script filter the database has been built
+    /// with, empty if all scripts are indexed
+    pub fn script_filter(&self) -> Result<Option<Vec<u8>>> {
+        match self.db.get(self.cf, FIELD_SCRIPT_FILTER)? {
+            Some(fingerprint) => Ok(Some(fingerprint.to_vec())),
+            None => Ok(None),
+        }
+    }
 }
 
 impl std::fmt::Debug for MetadataReader<'_> {
@@ -6,6 +6,7 @@
 
 abc_rust_lint::lint! {
     pub mod db;
+    pub mod export;
     pub mod group;
     pub mod groups;
     pub mod io;
@@ -0,0 +1,303 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use bitcoinsuite_core::tx::{OutPoint, Tx, TxId};
+
+use crate::io::BlockHeight;
+
+/// For how many blocks the outputs of txs removed for conflicting with a
+/// block are kept if no mined tx spends them. The node connects up to 32
+/// blocks in one step, and sends all the removals before the blocks.
+const REMOVED_FOR_BLOCK_MAX_AGE: BlockHeight = 100;
+
+/// Store which txs have been found to conflict with each other, i.e. spend
+/// the same outputs.
+///
+/// Conflicts are found in two ways:
+/// - The node removes a mempool tx because a block mines a tx spending the
+///   same outputs. The removal comes before the block, so the outputs spent
+///   by the removed tx are remembered until the block is connected, see
+///   [`MempoolConflicts::remove_for_block`].
+/// - A tx conflicts with a tx already in Chronik's mempool. This means
+///   Chronik's mempool is out of sync with the node's; the tx is not indexed,
+///   but the conflict is recorded for both txs, so wallets can find out why
+///   the tx never showed up (or why it may not confirm).
+///
+/// The conflicts of a tx are resolved once it is mined or leaves the node's
+/// mempool; they are kept for `retention_blocks` more blocks and then
+/// forgotten, so the records don't grow without bound.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MempoolConflicts {
+    conflicts: HashMap<TxId, BTreeSet<TxId>>,
+    /// Height at which the conflicts of a tx have been resolved
+    resolved_heights: HashMap<TxId, BlockHeight>,
+    /// Txs by the height at which their conflicts have been resolved, in
+    /// order of pruning. May contain stale entries for txs that got new
+    /// conflicts afterwards, which are skipped when pruning.
+    resolved_by_height: BTreeMap<BlockHeight, Vec<TxId>>,
+    /// Outputs spent by txs removed for conflicting with a block, with the
+    /// tip height at the time of removal, until that block is connected
+    removed_for_block: HashMap<OutPoint, (TxId, BlockHeight)>,
+    retention_blocks: BlockHeight,
+    tip_height: BlockHeight,
+}
+
+impl MempoolConflicts {
+    /// Create an empty [`MempoolConflicts`], keeping resolved conflicts for
+    /// `retention_blocks` blocks; 0 forgets them with the next block.
+    pub fn new(retention_blocks: BlockHeight) -> Self {
+        MempoolConflicts {
+            retention_blocks,
+            ..Default::default()
+        }
+    }
+
+    /// Record that the tx `txid` conflicts with all of `conflicting_txids`.
+    ///
+    /// Conflicts are symmetric, so they are recorded for both sides. Any of
+    /// the txs whose conflicts were resolved become unresolved again.
+    pub fn insert(&mut self, txid: TxId, conflicting_txids: &BTreeSet<TxId>) {
+        for &conflicting_txid in conflicting_txids {
+            self.conflicts
+                .entry(conflicting_txid)
+                .or_default()
+                .insert(txid);
+            self.resolved_heights.remove(&conflicting_txid);
+        }
+        self.conflicts
+            .entry(txid)
+            .or_default()
+            .extend(conflicting_txids);
+        self.resolved_heights.remove(&txid);
+    }
+
+    /// Mark the conflicts of `txid` as resolved at the current tip, e.g.
+    /// because it left the mempool.
+    ///
+    /// Only resolves them for `txid` itself, the txs it conflicts with may
+    /// still be in the node's mempool and be removed later.
+    pub fn resolve(&mut self, txid: &TxId) {
+        if !self.conflicts.contains_key(txid)
+            || self.resolved_heights.contains_key(txid)
+        {
+            return;
+        }
+        self.resolved_heights.insert(*txid, self.tip_height);
+        self.resolved_by_height
+            .entry(self.tip_height)
+            .or_default()
+            .push(*txid);
+    }
+
+    /// Remember that the node removed `tx` from the mempool because a tx
+    /// spending the same outputs is mined in a block that is about to be
+    /// connected. The conflict is recorded once that block is connected, in
+    /// [`MempoolConflicts::connect_block_txs`].
+    pub fn remove_for_block(&mut self, tx: &Tx) {
+        let txid = tx.txid();
+        for input in &tx.inputs {
+            self.removed_for_block
+                .insert(input.prev_out, (txid, self.tip_height));
+        }
+    }
+
+    /// Handle the block at `height` mining `txs` being connected: Record the
+    /// conflicts of the txs with the txs removed for them with
+    /// [`MempoolConflicts::remove_for_block`], then resolve them like in
+    /// [`MempoolConflicts::connect_block`].
+    pub fn connect_block_txs(&mut self, height: BlockHeight, txs: &[Tx]) {
+        let mut removed_txids = Vec::new();
+        for tx in txs {
+            let conflicting_txids = tx
+                .inputs
+                .iter()
+                .filter_map(|input| {
+                    self.removed_for_block.remove(&input.prev_out)
+                })
+                .map(|(removed_txid, _)| removed_txid)
+                .collect::<BTreeSet<_>>();
+            if !conflicting_txids.is_empty() {
+                self.insert(tx.txid(), &conflicting_txids);
+                removed_txids.extend(conflicting_txids);
+            }
+        }
+        self.connect_block(
+            height,
+            txs.iter().map(|tx| tx.txid_ref()).chain(&removed_txids),
+        );
+    }
+
+    /// Handle the block at `height` being connected: Resolve the conflicts
+    /// of the txs it mines, and forget conflicts resolved more than
+    /// `retention_blocks` blocks ago.
+    pub fn connect_block<'a>(
+        &mut self,
+        height: BlockHeight,
+        mined_txids: impl IntoIterator<Item = &'a TxId>,
+    ) {
+        self.tip_height = height;
+        for txid in mined_txids {
+            self.resolve(txid);
+        }
+        // Forget removals that didn't match any mined tx, e.g. because the
+        // block got replaced by a reorg
+        self.removed_for_block.retain(|_, (_, removed_height)| {
+            *removed_height + REMOVED_FOR_BLOCK_MAX_AGE >= height
+        });
+        while let Some(entry) = self.resolved_by_height.first_entry() {
+            if *entry.key() > height.saturating_sub(self.retention_blocks) {
+                break;
+            }
+            let (resolved_height, txids) = entry.remove_entry();
+            for txid in txids {
+                if self.resolved_heights.get(&txid) == Some(&resolved_height) {
+                    self.resolved_heights.remove(&txid);
+                    self.conflicts.remove(&txid);
+                }
+            }
+        }
+    }
+
+    /// Txs that have been found to conflict with the given tx, or [`None`] if
+    /// there are no known conflicts.
+    pub fn conflicts(&self, txid: &TxId) -> Option<&BTreeSet<TxId>> {
+        self.conflicts.get(txid)
+    }
+
+    /// Number of txs with known conflicts.
+    pub fn len(&self) -> usize {
+        self.conflicts.len()
+    }
+
+    /// Whether no conflicts have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use bitcoinsuite_core::tx::{OutPoint, Tx, TxId, TxInput, TxMut};
+
+    use crate::mem::{conflicts::REMOVED_FOR_BLOCK_MAX_AGE, MempoolConflicts};
+
+    #[test]
+    fn test_mempool_conflicts() {
+        let txid = |num: u8| TxId::from([num; 32]);
+        let txids = |nums: &[u8]| {
+            nums.iter().copied().map(txid).collect::<BTreeSet<_>>()
+        };
+        let mut conflicts = MempoolConflicts::new(2);
+        assert!(conflicts.is_empty());
+        assert_eq!(conflicts.conflicts(&txid(1)), None);
+
+        conflicts.insert(txid(1), &txids(&[2]));
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts.conflicts(&txid(1)), Some(&txids(&[2])));
+        assert_eq!(conflicts.conflicts(&txid(2)), Some(&txids(&[1])));
+
+        // Tx 3 double-spends tx 1 and tx 4
+        conflicts.insert(txid(3), &txids(&[1, 4]));
+        assert_eq!(conflicts.len(), 4);
+        assert_eq!(conflicts.conflicts(&txid(1)), Some(&txids(&[2, 3])));
+        assert_eq!(conflicts.conflicts(&txid(2)), Some(&txids(&[1])));
+        assert_eq!(conflicts.conflicts(&txid(3)), Some(&txids(&[1, 4])));
+        assert_eq!(conflicts.conflicts(&txid(4)), Some(&txids(&[3])));
+        assert_eq!(conflicts.conflicts(&txid(5)), None);
+
+        // Tx 3 mined at height 10, resolving its conflicts
+        conflicts.connect_block(10, &[txid(3)]);
+        conflicts.connect_block(11, &[]);
+        assert_eq!(conflicts.len(), 4);
+
+        // Txs 1 and 2 leave the mempool at height 11
+        conflicts.resolve(&txid(1));
+        conflicts.resolve(&txid(2));
+        // Resolved conflicts are kept for 2 blocks
+        conflicts.connect_block(12, &[]);
+        assert_eq!(conflicts.len(), 3);
+        assert_eq!(conflicts.conflicts(&txid(1)), Some(&txids(&[2, 3])));
+        assert_eq!(conflicts.conflicts(&txid(3)), None);
+
+        // New conflict for tx 2 unresolves it
+        conflicts.insert(txid(5), &txids(&[2]));
+        conflicts.connect_block(13, &[]);
+        assert_eq!(conflicts.len(), 3);
+        assert_eq!(conflicts.conflicts(&txid(1)), None);
+        assert_eq!(conflicts.conflicts(&txid(2)), Some(&txids(&[1, 5])));
+        assert_eq!(conflicts.conflicts(&txid(4)), Some(&txids(&[3])));
+        assert_eq!(conflicts.conflicts(&txid(5)), Some(&txids(&[2])));
+
+        // Unresolved conflicts are kept indefinitely
+        conflicts.connect_block(20, &[]);
+        assert_eq!(conflicts.len(), 3);
+
+        // Resolved conflicts are forgotten with the next block for 0 blocks
+        let mut conflicts = MempoolConflicts::new(0);
+        conflicts.insert(txid(1), &txids(&[2]));
+        conflicts.resolve(&txid(1));
+        assert_eq!(conflicts.len(), 2);
+        conflicts.connect_block(1, &[]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts.conflicts(&txid(1)), None);
+    }
+
+    #[test]
+    fn test_mempool_conflicts_with_block() {
+        let txid = |num: u8| TxId::from([num; 32]);
+        let txids = |nums: &[u8]| {
+            nums.iter().copied().map(txid).collect::<BTreeSet<_>>()
+        };
+        let make_tx = |num: u8, out_idxs: &[u32]| {
+            Tx::with_txid(
+                txid(num),
+                TxMut {
+                    inputs: out_idxs
+                        .iter()
+                        .map(|&out_idx| TxInput {
+                            prev_out: OutPoint {
+                                txid: txid(9),
+                                out_idx,
+                            },
+                            ..Default::default()
+                        })
+                        .collect(),
+                    ..Default::default()
+                },
+            )
+        };
+        let mut conflicts = MempoolConflicts::new(2);
+        conflicts.connect_block(10, &[]);
+
+        // Node removes txs 1 and 2, as the next block mines tx 3, which
+        // spends outputs of both of them
+        conflicts.remove_for_block(&make_tx(1, &[0]));
+        conflicts.remove_for_block(&make_tx(2, &[1, 2]));
+        assert!(conflicts.is_empty());
+        conflicts
+            .connect_block_txs(11, &[make_tx(3, &[0, 2]), make_tx(4, &[3])]);
+        assert_eq!(conflicts.len(), 3);
+        assert_eq!(conflicts.conflicts(&txid(1)), Some(&txids(&[3])));
+        assert_eq!(conflicts.conflicts(&txid(2)), Some(&txids(&[3])));
+        assert_eq!(conflicts.conflicts(&txid(3)), Some(&txids(&[1, 2])));
+        assert_eq!(conflicts.conflicts(&txid(4)), None);
+
+        // All of them are resolved right away
+        conflicts.connect_block_txs(13, &[]);
+        assert!(conflicts.is_empty());
+
+        // Removals no block mined a conflicting tx for are forgotten
+        conflicts.remove_for_block(&make_tx(5, &[4]));
+        conflicts.connect_block_txs(13 + REMOVED_FOR_BLOCK_MAX_AGE + 1, &[]);
+        conflicts.connect_block_txs(
+            13 + REMOVED_FOR_BLOCK_MAX_AGE + 2,
+            &[make_tx(6, &[4])],
+        );
+        assert!(conflicts.is_empty());
+    }
+}
@@ -4,15 +4,19 @@
 
 //! Module for [`Mempool`], to index mempool txs.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use abc_rust_error::Result;
-use bitcoinsuite_core::tx::{Tx, TxId};
+use bitcoinsuite_core::{
+    ser::BitcoinSer,
+    tx::{Tx, TxId},
+};
 use thiserror::Error;
 
 use crate::{
     groups::{MempoolScriptHistory, MempoolScriptUtxos, ScriptGroup},
-    mem::MempoolSpentBy,
+    io::BlockHeight,
+    mem::{MempoolConflicts, MempoolSpentBy},
 };
 
 /// Mempool of the indexer. This stores txs from the node again, but having a
@@ -21,9 +25,11 @@ use crate::{
 #[derive(Debug)]
 pub struct Mempool {
     txs: HashMap<TxId, MempoolTx>,
+    total_size: usize,
     script_history: MempoolScriptHistory,
     script_utxos: MempoolScriptUtxos,
     spent_by: MempoolSpentBy,
+    conflicts: MempoolConflicts,
 }
 
 /// Transaction in the mempool.
@@ -50,26 +56,36 @@ pub enum MempoolError {
 use self::MempoolError::*;
 
 impl Mempool {
-    /// Create a new [`Mempool`].
-    pub fn new(script_group: ScriptGroup) -> Self {
+    /// Create a new [`Mempool`], keeping resolved conflicts for
+    /// `conflict_retention_blocks` blocks, see [`MempoolConflicts`].
+    pub fn new(
+        script_group: ScriptGroup,
+        conflict_retention_blocks: BlockHeight,
+    ) -> Self {
         Mempool {
             txs: HashMap::new(),
+            total_size: 0,
             script_history: MempoolScriptHistory::new(script_group.clone()),
             script_utxos: MempoolScriptUtxos::new(script_group),
             spent_by: MempoolSpentBy::default(),
+            conflicts: MempoolConflicts::new(conflict_retention_blocks),
         }
     }
 
     /// Insert tx into the mempool.
     pub fn insert(&mut self, mempool_tx: MempoolTx) -> Result<()> {
         let txid = mempool_tx.tx.txid();
+        // Check before touching any of the indices, so a duplicate doesn't
+        // leave them in an inconsistent state.
+        if self.txs.contains_key(&txid) {
+            return Err(DuplicateTx(txid).into());
+        }
         self.script_history.insert(&mempool_tx);
         self.script_utxos
             .insert(&mempool_tx, |txid| self.txs.contains_key(txid))?;
         self.spent_by.insert(&mempool_tx)?;
-        if self.txs.insert(txid, mempool_tx).is_some() {
-            return Err(DuplicateTx(txid).into());
-        }
+        self.total_size += mempool_tx.tx.ser_len();
+        self.txs.insert(txid, mempool_tx);
         Ok(())
     }
 
@@ -83,6 +99,18 @@ impl Mempool {
         self.script_utxos
             .remove(&mempool_tx, |txid| self.txs.contains_key(txid))?;
         self.spent_by.remove(&mempool_tx)?;
+        self.total_size -= mempool_tx.tx.ser_len();
+        self.conflicts.resolve(&txid);
+        Ok(mempool_tx)
+    }
+
+    /// Remove tx from the mempool, because the node removed it for
+    /// conflicting with a tx in a block that is about to be connected. The
+    /// conflict is recorded once the block is connected, see
+    /// [`MempoolConflicts::remove_for_block`].
+    pub fn remove_for_block(&mut self, txid: TxId) -> Result<MempoolTx> {
+        let mempool_tx = self.remove(txid)?;
+        self.conflicts.remove_for_block(&mempool_tx.tx);
         Ok(mempool_tx)
     }
 
@@ -92,16 +120,63 @@ impl Mempool {
             self.script_history.remove(&mempool_tx);
             self.script_utxos.remove_mined(&mempool_tx);
             self.spent_by.remove(&mempool_tx)?;
+            self.total_size -= mempool_tx.tx.ser_len();
             return Ok(Some(mempool_tx));
         }
         Ok(None)
     }
 
+    /// Mempool txs spending any of the outputs spent by `tx`, i.e. txs that
+    /// conflict with `tx`.
+    pub fn conflicting_txids(&self, tx: &Tx) -> BTreeSet<TxId> {
+        self.spent_by.conflicting_txids(tx)
+    }
+
+    /// Record that tx `txid` conflicts with the given mempool txs.
+    pub fn insert_conflict(
+        &mut self,
+        txid: TxId,
+        conflicting_txids: &BTreeSet<TxId>,
+    ) {
+        self.conflicts.insert(txid, conflicting_txids);
+    }
+
+    /// Resolve the conflicts of tx `txid`, which never made it into the
+    /// mempool, once the node removes it, see [`MempoolConflicts::resolve`].
+    pub fn resolve_conflicts(&mut self, txid: &TxId) {
+        self.conflicts.resolve(txid);
+    }
+
+    /// Record the conflicts of the txs mined in the block at `height` with
+    /// txs removed by [`Mempool::remove_for_block`], resolve them, and forget
+    /// conflicts that have been resolved long enough ago.
+    pub fn connect_block_conflicts(&mut self, height: BlockHeight, txs: &[Tx]) {
+        self.conflicts.connect_block_txs(height, txs);
+    }
+
     /// Get a tx by [`TxId`], or [`None`], if not found.
     pub fn tx(&self, txid: &TxId) -> Option<&MempoolTx> {
         self.txs.get(txid)
     }
 
+    /// Unix timestamp of when the tx was first seen in the mempool, or
+    /// [`None`], if the tx is not in the mempool.
+    pub fn time_first_seen(&self, txid: &TxId) -> Option<i64> {
+        self.txs
+            .get(txid)
+            .map(|mempool_tx| mempool_tx.time_first_seen)
+    }
+
+    /// Number of txs in the mempool.
+    pub fn num_txs(&self) -> usize {
+        self.txs.len()
+    }
+
+    /// Sum of the serialized sizes of all txs in the mempool, in bytes.
+    pub fn total_size(&self) -> usize {
+        self.total_size
+    }
+
     /// Tx history of scripts in the mempool.
     pub fn script_history(&self) -> &MempoolScriptHistory {
         &self.script_history
@@ -112,8 +187,83 @@ impl Mempool {
         &self.script_utxos
     }
 
+    /// Conflicts between txs, see [`MempoolConflicts`].
+    pub fn conflicts(&self) -> &MempoolConflicts {
+        &self.conflicts
+    }
+
     /// Which tx outputs have been spent by tx in the mempool.
     pub fn spent_by(&self) -> &MempoolSpentBy {
         &self.spent_by
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use abc_rust_error::Result;
+    use bitcoinsuite_core::{ser::BitcoinSer, tx::TxId};
+
+    use crate::{
+        groups::{prefix_mock_compress, ScriptGroup},
+        mem::{Mempool, MempoolTx},
+        test::{make_inputs_tx, utxo::make_mempool_tx},
+    };
+
+    #[test]
+    fn test_mempool_size() -> Result<()> {
+        let script_group = ScriptGroup::new(Arc::new(prefix_mock_compress));
+        let mut mempool = Mempool::new(script_group, 0);
+        assert_eq!(mempool.num_txs(), 0);
+        assert_eq!(mempool.total_size(), 0);
+
+        let tx1 =
+            make_mempool_tx!(txid_num = 1, inputs = [(10, 4)], num_outputs = 1);
+        let tx2 = make_mempool_tx!(
+            txid_num = 2,
+            inputs = [(1, 0), (10, 5)],
+            num_outputs = 2
+        );
+        let size1 = tx1.tx.ser_len();
+        let size2 = tx2.tx.ser_len();
+
+        mempool.insert(tx1)?;
+        assert_eq!(mempool.num_txs(), 1);
+        assert_eq!(mempool.total_size(), size1);
+
+        mempool.insert(tx2)?;
+        assert_eq!(mempool.num_txs(), 2);
+        assert_eq!(mempool.total_size(), size1 + size2);
+
+        mempool.remove(TxId::from([2; 32]))?;
+        assert_eq!(mempool.num_txs(), 1);
+        assert_eq!(mempool.total_size(), size1);
+
+        mempool.remove_mined(&TxId::from([1; 32]))?;
+        assert_eq!(mempool.num_txs(), 0);
+        assert_eq!(mempool.total_size(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mempool_time_first_seen() -> Result<()> {
+        let script_group = ScriptGroup::new(Arc::new(prefix_mock_compress));
+        let mut mempool = Mempool::new(script_group, 0);
+        let txid = TxId::from([1; 32]);
+        assert_eq!(mempool.time_first_seen(&txid), None);
+
+        let mut tx =
+            make_mempool_tx!(txid_num = 1, inputs = [(10, 4)], num_outputs = 1);
+        tx.time_first_seen = 1_700_000_000;
+        mempool.insert(tx)?;
+        assert_eq!(mempool.time_first_seen(&txid), Some(1_700_000_000));
+        assert_eq!(mempool.time_first_seen(&TxId::from([2; 32])), None);
+
+        mempool.remove(txid)?;
+        assert_eq!(mempool.time_first_seen(&txid), None);
+
+        Ok(())
+    }
+}
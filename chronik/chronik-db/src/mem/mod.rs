@@ -4,11 +4,13 @@
 
 //! Module containing structs to index the mempool.
 
+mod conflicts;
 mod group_history;
 mod group_utxos;
 mod mempool;
 mod spent_by;
 
+pub use self::conflicts::*;
 pub use self::group_history::*;
 pub use self::group_utxos::*;
 pub use self::mempool::*;
@@ -2,10 +2,10 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
-use std::collections::{btree_map::Entry, BTreeMap, HashMap};
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap};
 
 use abc_rust_error::Result;
-use bitcoinsuite_core::tx::{OutPoint, SpentBy, TxId};
+use bitcoinsuite_core::tx::{OutPoint, SpentBy, Tx, TxId};
 use thiserror::Error;
 
 use crate::mem::MempoolTx;
@@ -138,6 +138,21 @@ impl MempoolSpentBy {
         Ok(())
     }
 
+    /// Mempool txs (other than `tx` itself) spending any of the outputs
+    /// spent by `tx`, i.e. txs that `tx` conflicts with.
+    pub fn conflicting_txids(&self, tx: &Tx) -> BTreeSet<TxId> {
+        let txid = tx.txid_ref();
+        tx.inputs
+            .iter()
+            .filter_map(|input| {
+                let entries = self.spent_by.get(&input.prev_out.txid)?;
+                entries.get(&input.prev_out.out_idx)
+            })
+            .map(|spent_by| spent_by.txid)
+            .filter(|spent_by_txid| spent_by_txid != txid)
+            .collect()
+    }
+
     /// Return the outputs of the given tx that have been spent by mempool txs.
     pub fn outputs_spent(
         &self,
@@ -149,7 +164,7 @@ impl MempoolSpentBy {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeMap;
+    use std::collections::{BTreeMap, BTreeSet};
 
     use abc_rust_error::Result;
     use bitcoinsuite_core::tx::{OutPoint, SpentBy, TxId};
@@ -202,6 +217,12 @@ mod tests {
             inputs = [(0, 0), (10, 4)],
             num_outputs = 0
         );
+        assert_eq!(
+            mempool.conflicting_txids(&tx_conflict.tx),
+            BTreeSet::from([TxId::from([1; 32])]),
+        );
+        // A tx doesn't conflict with itself
+        assert_eq!(mempool.conflicting_txids(&tx1.tx), BTreeSet::new());
         assert_eq!(
             mempool
                 .insert(&tx_conflict)
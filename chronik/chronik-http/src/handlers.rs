@@ -3,7 +3,7 @@
 use std::{collections::HashMap, fmt::Display, str::FromStr};
 
 use abc_rust_error::{Report, Result};
-use chronik_indexer::indexer::ChronikIndexer;
+use chronik_indexer::{indexer::ChronikIndexer, query::TxHistoryCursor};
 use chronik_proto::proto;
 use hyper::Uri;
 use thiserror::Error;
@@ -52,7 +52,7 @@ pub async fn handle_not_found(uri: Uri) -> Result<(), ReportError> {
 }
 
 /// Return a page of the txs of a block.
-pub async fn handle_block_txs(
+pub fn handle_block_txs(
     hash_or_height: String,
     query_params: &HashMap<String, String>,
     indexer: &ChronikIndexer,
@@ -65,7 +65,7 @@ pub async fn handle_block_txs(
 
 /// Return a page of the confirmed txs of the given script.
 /// Scripts are identified by script_type and payload.
-pub async fn handle_script_confirmed_txs(
+pub fn handle_script_confirmed_txs(
     script_type: &str,
     payload: &str,
     query_params: &HashMap<String, String>,
@@ -79,10 +79,27 @@ pub async fn handle_script_confirmed_txs(
     script_history.confirmed_txs(&script, page_num as usize, page_size as usize)
 }
 
+/// Return a page of the confirmed txs of the given script, paginated using a
+/// cursor, which is returned with each page to fetch the next one.
+/// Scripts are identified by script_type and payload.
+pub fn handle_script_confirmed_txs_cursor(
+    script_type: &str,
+    payload: &str,
+    query_params: &HashMap<String, String>,
+    indexer: &ChronikIndexer,
+) -> Result<proto::TxHistoryCursorPage> {
+    let script_variant = parse_script_variant_hex(script_type, payload)?;
+    let script_history = indexer.script_history()?;
+    let cursor: Option<TxHistoryCursor> = get_param(query_params, "cursor")?;
+    let page_size: u32 = get_param(query_params, "page_size")?.unwrap_or(25);
+    let script = script_variant.to_script();
+    script_history.confirmed_txs_cursor(&script, cursor, page_size as usize)
+}
+
 /// Return a page of the tx history of the given script, in reverse
 /// chronological order, i.e. the latest transaction first and then going back
 /// in time. Scripts are identified by script_type and payload.
-pub async fn handle_script_history(
+pub fn handle_script_history(
     script_type: &str,
     payload: &str,
     query_params: &HashMap<String, String>,
@@ -98,7 +115,7 @@ pub async fn handle_script_history(
 
 /// Return a page of the confirmed txs of the given script.
 /// Scripts are identified by script_type and payload.
-pub async fn handle_script_unconfirmed_txs(
+pub fn handle_script_unconfirmed_txs(
     script_type: &str,
     payload: &str,
     indexer: &ChronikIndexer,
@@ -111,7 +128,7 @@ pub async fn handle_script_unconfirmed_txs(
 
 /// Return the UTXOs of the given script.
 /// Scripts are identified by script_type and payload.
-pub async fn handle_script_utxos(
+pub fn handle_script_utxos(
     script_type: &str,
     payload: &str,
     indexer: &ChronikIndexer,
@@ -9,7 +9,9 @@ abc_rust_lint::lint! {
     pub mod handlers;
     pub mod parse;
     pub mod protobuf;
+    pub mod range;
     pub mod server;
+    pub mod timeout;
     pub(crate) mod validation;
     pub mod ws;
 }
@@ -0,0 +1,216 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+//! Module for serving byte ranges of binary data, see [RFC 9110, section 14].
+//!
+//! [RFC 9110, section 14]: https://www.rfc-editor.org/rfc/rfc9110#section-14
+
+use std::ops::Range;
+
+use axum::{
+    http::{
+        header::{ACCEPT_RANGES, CONTENT_RANGE, CONTENT_TYPE, RANGE},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+
+/// 'Content-Type' header for binary responses
+pub const CONTENT_TYPE_OCTET_STREAM: &str = "application/octet-stream";
+
+/// What part of the data to respond with for a `Range` header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ByteRangeRequest {
+    /// Respond with all the data, using 200 OK.
+    Full,
+    /// Respond with only this byte range, using 206 Partial Content.
+    Partial(Range<usize>),
+    /// The range lies outside the data, respond with 416 Range Not
+    /// Satisfiable.
+    Unsatisfiable,
+}
+
+/// Parse the value of a `Range` header for data of the given `size`.
+///
+/// Only a single range is served. Multiple ranges would require a
+/// `multipart/byteranges` response, instead the header is ignored and the
+/// full data is served, as the spec allows. Syntactically invalid headers or
+/// unknown range units are ignored too, as required by the spec.
+pub fn parse_byte_range(range: &str, size: usize) -> ByteRangeRequest {
+    let specs = match range.trim().strip_prefix("bytes=") {
+        Some(specs) => specs,
+        None => return ByteRangeRequest::Full,
+    };
+    if specs.contains(',') {
+        return ByteRangeRequest::Full;
+    }
+    let (first, last) = match specs.trim().split_once('-') {
+        Some(spec) => spec,
+        None => return ByteRangeRequest::Full,
+    };
+    // usize::from_str would also accept a leading "+"
+    let parse_pos = |pos: &str| {
+        if pos.bytes().all(|byte| byte.is_ascii_digit()) {
+            pos.parse::<usize>().ok()
+        } else {
+            None
+        }
+    };
+    match (first, last) {
+        // "-n": last n bytes
+        ("", suffix_len) => match parse_pos(suffix_len) {
+            Some(0) => ByteRangeRequest::Unsatisfiable,
+            Some(_) if size == 0 => ByteRangeRequest::Unsatisfiable,
+            Some(suffix_len) => {
+                ByteRangeRequest::Partial(size.saturating_sub(suffix_len)..size)
+            }
+            None => ByteRangeRequest::Full,
+        },
+        // "a-": everything starting at a
+        (first, "") => match parse_pos(first) {
+            Some(first) if first >= size => ByteRangeRequest::Unsatisfiable,
+            Some(first) => ByteRangeRequest::Partial(first..size),
+            None => ByteRangeRequest::Full,
+        },
+        // "a-b": bytes a to b, inclusive, b may exceed the size
+        (first, last) => match (parse_pos(first), parse_pos(last)) {
+            (Some(first), Some(last)) if first > last => ByteRangeRequest::Full,
+            (Some(first), Some(_)) if first >= size => {
+                ByteRangeRequest::Unsatisfiable
+            }
+            (Some(first), Some(last)) => ByteRangeRequest::Partial(
+                first..last.saturating_add(1).min(size),
+            ),
+            _ => ByteRangeRequest::Full,
+        },
+    }
+}
+
+/// Build a response for the binary `data`, serving only the range requested
+/// by the `Range` header in `headers`, if any.
+pub fn byte_range_response(headers: &HeaderMap, data: Vec<u8>) -> Response {
+    let size = data.len();
+    let range_request = match headers.get(RANGE).map(|r| r.to_str()) {
+        Some(Ok(range)) => parse_byte_range(range, size),
+        _ => ByteRangeRequest::Full,
+    };
+    let mut response = match range_request {
+        ByteRangeRequest::Full => (StatusCode::OK, data).into_response(),
+        ByteRangeRequest::Partial(range) => {
+            let content_range =
+                format!("bytes {}-{}/{}", range.start, range.end - 1, size);
+            let mut response =
+                (StatusCode::PARTIAL_CONTENT, data[range].to_vec())
+                    .into_response();
+            response.headers_mut().insert(
+                CONTENT_RANGE,
+                HeaderValue::from_str(&content_range).unwrap(),
+            );
+            response
+        }
+        ByteRangeRequest::Unsatisfiable => {
+            let content_range = format!("bytes */{size}");
+            let mut response =
+                StatusCode::RANGE_NOT_SATISFIABLE.into_response();
+            response.headers_mut().insert(
+                CONTENT_RANGE,
+                HeaderValue::from_str(&content_range).unwrap(),
+            );
+            return response;
+        }
+    };
+    let headers = response.headers_mut();
+    headers.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(CONTENT_TYPE_OCTET_STREAM),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use abc_rust_error::Result;
+    use hyper::{
+        body::to_bytes,
+        header::{CONTENT_RANGE, RANGE},
+        HeaderMap, StatusCode,
+    };
+
+    use crate::range::{
+        byte_range_response, parse_byte_range, ByteRangeRequest,
+    };
+
+    #[test]
+    fn test_parse_byte_range() {
+        use ByteRangeRequest::*;
+        // Single ranges
+        assert_eq!(parse_byte_range("bytes=0-0", 10), Partial(0..1));
+        assert_eq!(parse_byte_range("bytes=2-5", 10), Partial(2..6));
+        assert_eq!(parse_byte_range("bytes=2-100", 10), Partial(2..10));
+        assert_eq!(parse_byte_range("bytes=7-", 10), Partial(7..10));
+        assert_eq!(parse_byte_range("bytes=-3", 10), Partial(7..10));
+        assert_eq!(parse_byte_range("bytes=-30", 10), Partial(0..10));
+        assert_eq!(parse_byte_range(" bytes= 2-5 ", 10), Partial(2..6));
+        // Unsatisfiable ranges
+        assert_eq!(parse_byte_range("bytes=10-", 10), Unsatisfiable);
+        assert_eq!(parse_byte_range("bytes=10-20", 10), Unsatisfiable);
+        assert_eq!(parse_byte_range("bytes=-0", 10), Unsatisfiable);
+        assert_eq!(parse_byte_range("bytes=-5", 0), Unsatisfiable);
+        // Multiple ranges are served in full
+        assert_eq!(parse_byte_range("bytes=0-1, 4-5", 10), Full);
+        // Invalid headers are ignored
+        assert_eq!(parse_byte_range("", 10), Full);
+        assert_eq!(parse_byte_range("items=0-5", 10), Full);
+        assert_eq!(parse_byte_range("bytes=5-2", 10), Full);
+        assert_eq!(parse_byte_range("bytes=5", 10), Full);
+        assert_eq!(parse_byte_range("bytes=-", 10), Full);
+        assert_eq!(parse_byte_range("bytes=+1-2", 10), Full);
+        assert_eq!(parse_byte_range("bytes=a-b", 10), Full);
+    }
+
+    #[tokio::test]
+    async fn test_byte_range_response() -> Result<()> {
+        let data = b"0123456789".to_vec();
+        let request = |range: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            if let Some(range) = range {
+                headers.insert(RANGE, range.parse().unwrap());
+            }
+            let response = byte_range_response(&headers, data.clone());
+            let content_range = response
+                .headers()
+                .get(CONTENT_RANGE)
+                .map(|value| value.to_str().unwrap().to_string());
+            (response.status(), content_range, response.into_body())
+        };
+
+        let (status, content_range, body) = request(None);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_range, None);
+        assert_eq!(to_bytes(body).await?.as_ref(), b"0123456789");
+
+        let (status, content_range, body) = request(Some("bytes=2-4"));
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(content_range.as_deref(), Some("bytes 2-4/10"));
+        assert_eq!(to_bytes(body).await?.as_ref(), b"234");
+
+        let (status, content_range, body) = request(Some("bytes=-2"));
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(content_range.as_deref(), Some("bytes 8-9/10"));
+        assert_eq!(to_bytes(body).await?.as_ref(), b"89");
+
+        let (status, content_range, body) = request(Some("bytes=0-1,3-4"));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_range, None);
+        assert_eq!(to_bytes(body).await?.as_ref(), b"0123456789");
+
+        let (status, content_range, body) = request(Some("bytes=20-"));
+        assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(content_range.as_deref(), Some("bytes */10"));
+        assert!(to_bytes(body).await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -5,44 +5,146 @@
 //! Module for [`ChronikServer`].
 
 use std::collections::HashMap;
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
-use abc_rust_error::{Result, WrapErr};
+use abc_rust_error::{Report, Result, WrapErr};
 use axum::{
-    extract::{Path, Query, WebSocketUpgrade},
-    response::IntoResponse,
+    extract::{Path, Query, State, WebSocketUpgrade},
+    http::{HeaderMap, Request},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
     routing, Extension, Router,
 };
 use bitcoinsuite_core::tx::TxId;
-use chronik_indexer::indexer::ChronikIndexer;
+use chronik_indexer::{
+    debug::DebugCounters, indexer::ChronikIndexer, queue::EventSync,
+};
 use chronik_proto::proto;
 use hyper::server::conn::AddrIncoming;
 use thiserror::Error;
 use tokio::sync::RwLock;
+use tower_http::compression::{
+    predicate::{And, NotForContentType, Predicate, SizeAbove},
+    CompressionLayer,
+};
 
 use crate::{
-    error::ReportError, handlers, protobuf::Protobuf,
+    error::ReportError,
+    handlers,
+    protobuf::Protobuf,
+    range::{byte_range_response, CONTENT_TYPE_OCTET_STREAM},
+    timeout::{timeout_requests, RequestTimeouts},
     ws::handle_subscribe_socket,
 };
 
 /// Ref-counted indexer with read or write access
 pub type ChronikIndexerRef = Arc<RwLock<ChronikIndexer>>;
 
+/// Run `query` against the indexer on Tokio's blocking thread pool.
+///
+/// Queries read from RocksDB (or the node's block files) synchronously, which
+/// can take a while, e.g. for big pages. Running them directly in a handler
+/// would block the executor thread, stalling all other requests and WebSockets
+/// scheduled on it. The read lock is awaited asynchronously, and then moved to
+/// the blocking task.
+///
+/// If the returned future is dropped, e.g. because the request timed out, the
+/// query is skipped if it hasn't started yet. A query that is already running
+/// can't be interrupted; it runs to completion and holds the read lock until
+/// then.
+pub async fn query_blocking<T, F>(
+    indexer: ChronikIndexerRef,
+    query: F,
+) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&ChronikIndexer) -> Result<T> + Send + 'static,
+{
+    let indexer = indexer.read_owned().await;
+    let cancel = CancelOnDrop::default();
+    let cancelled = Arc::clone(&cancel.0);
+    tokio::task::spawn_blocking(move || {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(QueryCancelled.into());
+        }
+        query(&indexer)
+    })
+    .await?
+}
+
+/// Sets the flag when dropped, to cancel a query not started yet.
+#[derive(Default)]
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Function to broadcast a serialized tx to the network, returning its txid,
+/// or why the node rejected it.
+///
+/// Usually wired to the node via FFI in chronik-lib, so this crate doesn't
+/// have to depend on the bridge.
+pub type FnBroadcastTx =
+    Arc<dyn Fn(&[u8]) -> std::result::Result<TxId, String> + Send + Sync>;
+
+/// Default minimum size of a response body (in bytes) to be compressed.
+/// Smaller responses are sent uncompressed, as compressing them would barely
+/// save any bandwidth, but still cost CPU.
+pub const DEFAULT_COMPRESSION_MIN_SIZE: u16 = 1024;
+
+/// Default time a request may take before it is answered with "504 Gateway
+/// Timeout". This bounds how long clients wait, but a DB query that's already
+/// running isn't interrupted, see [`query_blocking`].
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Params defining what and where to serve for [`ChronikServer`].
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct ChronikServerParams {
     /// Host address (port + IP) where to serve Chronik at.
     pub hosts: Vec<SocketAddr>,
     /// Indexer to read data from
     pub indexer: ChronikIndexerRef,
+    /// Function to submit txs to the node
+    pub fn_broadcast_tx: FnBroadcastTx,
+    /// Sync point for the node events queued for the indexer. Requests wait
+    /// until the events queued before them are indexed, so they see e.g. a
+    /// tx once the node returned from `sendrawtransaction`.
+    pub event_sync: EventSync,
+    /// Minimum size of a response body (in bytes) to be compressed using
+    /// gzip or br, negotiated through the `Accept-Encoding` header.
+    pub compression_min_size: u16,
+    /// Time a request may take before it is answered with "504 Gateway
+    /// Timeout", unless overridden in `route_timeouts`.
+    pub request_timeout: Duration,
+    /// Per-route overrides of `request_timeout`, keyed by route pattern,
+    /// e.g. "/blocks/:start/:end".
+    pub route_timeouts: HashMap<String, Duration>,
+    /// Internal counters to serve at `/debug/stats`. The endpoint is only
+    /// served if this is set, as it's meant for debugging, not for the
+    /// public.
+    pub debug_counters: Option<Arc<DebugCounters>>,
 }
 
 /// Chronik HTTP server, holding all the data/handles required to serve an
 /// instance.
-#[derive(Debug)]
 pub struct ChronikServer {
     server_builders: Vec<hyper::server::Builder<AddrIncoming>>,
     indexer: ChronikIndexerRef,
+    fn_broadcast_tx: FnBroadcastTx,
+    event_sync: EventSync,
+    compression_min_size: u16,
+    timeouts: RequestTimeouts,
+    debug_counters: Option<Arc<DebugCounters>>,
 }
 
 /// Errors for [`ChronikServer`].
@@ -67,6 +169,14 @@ pub enum ChronikServerError {
     /// Block not found in DB
     #[error("404: Block not found: {0}")]
     BlockNotFound(String),
+
+    /// Node rejected the tx to be broadcast
+    #[error("400: Broadcast failed: {0}")]
+    BroadcastFailed(String),
+
+    /// The request was dropped before its query started
+    #[error("Query cancelled")]
+    QueryCancelled,
 }
 
 use self::ChronikServerError::*;
@@ -86,12 +196,27 @@ impl ChronikServer {
         Ok(ChronikServer {
             server_builders,
             indexer: params.indexer,
+            fn_broadcast_tx: params.fn_broadcast_tx,
+            event_sync: params.event_sync,
+            compression_min_size: params.compression_min_size,
+            timeouts: RequestTimeouts {
+                default: params.request_timeout,
+                routes: Arc::new(params.route_timeouts),
+            },
+            debug_counters: params.debug_counters,
         })
     }
 
     /// Serve a Chronik HTTP endpoint with the given parameters.
     pub async fn serve(self) -> Result<()> {
-        let app = Self::make_router(self.indexer);
+        let app = Self::make_router(
+            self.indexer,
+            self.fn_broadcast_tx,
+            self.event_sync,
+            self.compression_min_size,
+            self.timeouts,
+            self.debug_counters,
+        );
         let servers = self
             .server_builders
             .into_iter()
@@ -109,18 +234,42 @@ impl ChronikServer {
         Ok(())
     }
 
-    fn make_router(indexer: ChronikIndexerRef) -> Router {
-        Router::new()
+    fn make_router(
+        indexer: ChronikIndexerRef,
+        fn_broadcast_tx: FnBroadcastTx,
+        event_sync: EventSync,
+        compression_min_size: u16,
+        timeouts: RequestTimeouts,
+        debug_counters: Option<Arc<DebugCounters>>,
+    ) -> Router {
+        let router = Router::new()
             .route("/blockchain-info", routing::get(handle_blockchain_info))
+            .route("/mempool-info", routing::get(handle_mempool_info))
+            .route("/db-stats", routing::get(handle_db_stats))
             .route("/block/:hash_or_height", routing::get(handle_block))
+            .route(
+                "/block-header/:hash_or_height",
+                routing::get(handle_block_header),
+            )
             .route("/block-txs/:hash_or_height", routing::get(handle_block_txs))
             .route("/blocks/:start/:end", routing::get(handle_block_range))
+            .route(
+                "/recent-blocks/:num_blocks",
+                routing::get(handle_recent_blocks),
+            )
             .route("/tx/:txid", routing::get(handle_tx))
             .route("/raw-tx/:txid", routing::get(handle_raw_tx))
+            .route("/tx-block/:txid", routing::get(handle_tx_block))
+            .route("/tx-conflicts/:txid", routing::get(handle_tx_conflicts))
+            .route("/tx/broadcast", routing::post(handle_broadcast_tx))
             .route(
                 "/script/:type/:payload/confirmed-txs",
                 routing::get(handle_script_confirmed_txs),
             )
+            .route(
+                "/script/:type/:payload/confirmed-txs-cursor",
+                routing::get(handle_script_confirmed_txs_cursor),
+            )
             .route(
                 "/script/:type/:payload/history",
                 routing::get(handle_script_history),
@@ -132,37 +281,193 @@ impl ChronikServer {
             .route(
                 "/script/:type/:payload/utxos",
                 routing::get(handle_script_utxos),
-            )
+            );
+        let router = match debug_counters {
+            Some(counters) => router.route(
+                "/debug/stats",
+                routing::get(move |indexer| {
+                    handle_debug_stats(indexer, Arc::clone(&counters))
+                }),
+            ),
+            None => router,
+        };
+        router
+            .route("/raw-block/:hash_or_height", routing::get(handle_raw_block))
             .route("/ws", routing::get(handle_ws))
             .fallback(handlers::handle_not_found)
+            // Inside the timeout, so waiting for the indexer counts towards it
+            .layer(middleware::from_fn_with_state(
+                event_sync.clone(),
+                sync_indexer,
+            ))
+            .layer(middleware::from_fn_with_state(timeouts, timeout_requests))
             .layer(Extension(indexer))
+            .layer(Extension(fn_broadcast_tx))
+            .layer(Extension(event_sync))
+            .layer(compression_layer(compression_min_size))
+    }
+}
+
+/// Middleware waiting until the node events queued before the request are
+/// indexed, so e.g. a tx can be queried once `sendrawtransaction` returned.
+async fn sync_indexer<B>(
+    State(event_sync): State<EventSync>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    event_sync.wait_processed().await;
+    next.run(req).await
+}
+
+/// Compress responses of at least `compression_min_size` bytes with gzip or
+/// br, depending on the request's `Accept-Encoding` header.
+fn compression_layer(
+    compression_min_size: u16,
+) -> CompressionLayer<And<SizeAbove, NotForContentType>> {
+    CompressionLayer::new().br(true).gzip(true).compress_when(
+        SizeAbove::new(compression_min_size).and(
+            // Block data barely compresses, and compressing would break
+            // serving byte ranges of it
+            NotForContentType::const_new(CONTENT_TYPE_OCTET_STREAM),
+        ),
+    )
+}
+
+impl std::fmt::Debug for ChronikServerParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChronikServerParams")
+            .field("hosts", &self.hosts)
+            .field("indexer", &self.indexer)
+            .field("fn_broadcast_tx", &"..")
+            .field("event_sync", &self.event_sync)
+            .field("compression_min_size", &self.compression_min_size)
+            .field("request_timeout", &self.request_timeout)
+            .field("route_timeouts", &self.route_timeouts)
+            .field("debug_counters", &self.debug_counters)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for ChronikServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChronikServer")
+            .field("server_builders", &self.server_builders)
+            .field("indexer", &self.indexer)
+            .field("fn_broadcast_tx", &"..")
+            .field("event_sync", &self.event_sync)
+            .field("compression_min_size", &self.compression_min_size)
+            .field("timeouts", &self.timeouts)
+            .field("debug_counters", &self.debug_counters)
+            .finish()
     }
 }
 
 async fn handle_blockchain_info(
     Extension(indexer): Extension<ChronikIndexerRef>,
 ) -> Result<Protobuf<proto::BlockchainInfo>, ReportError> {
-    let indexer = indexer.read().await;
-    let blocks = indexer.blocks();
-    Ok(Protobuf(blocks.blockchain_info()?))
+    let info =
+        query_blocking(indexer, |indexer| indexer.blocks().blockchain_info())
+            .await?;
+    Ok(Protobuf(info))
+}
+
+async fn handle_mempool_info(
+    Extension(indexer): Extension<ChronikIndexerRef>,
+) -> Result<Protobuf<proto::MempoolInfo>, ReportError> {
+    let mempool_info =
+        query_blocking(indexer, |indexer| Ok(indexer.mempool_info())).await?;
+    Ok(Protobuf(mempool_info))
+}
+
+async fn handle_db_stats(
+    Extension(indexer): Extension<ChronikIndexerRef>,
+) -> Result<Protobuf<proto::DbStats>, ReportError> {
+    let stats = query_blocking(indexer, |indexer| indexer.db_stats()).await?;
+    Ok(Protobuf(proto::DbStats {
+        approx_size_bytes: stats.approx_size_bytes,
+        num_keys_per_column: stats.num_keys_per_column.into_iter().collect(),
+    }))
+}
+
+async fn handle_debug_stats(
+    Extension(indexer): Extension<ChronikIndexerRef>,
+    counters: Arc<DebugCounters>,
+) -> Result<Protobuf<proto::DebugStats>, ReportError> {
+    // Read the counters before waiting for the lock, so they show what's
+    // blocking it
+    let counters = counters.snapshot();
+    let db_stats =
+        query_blocking(indexer, |indexer| indexer.db_stats()).await?;
+    Ok(Protobuf(proto::DebugStats {
+        num_write_lock_waits: counters.num_write_lock_waits,
+        total_write_lock_wait_micros: counters.total_write_lock_wait_micros,
+        max_write_lock_wait_micros: counters.max_write_lock_wait_micros,
+        num_queued_events: counters.num_queued_events,
+        max_queued_events: counters.max_queued_events,
+        size_bytes_per_column: db_stats
+            .size_bytes_per_column
+            .into_iter()
+            .collect(),
+        resync_start_height: counters.resync_start_height,
+        resync_height: counters.resync_height,
+        resync_tip_height: counters.resync_tip_height,
+    }))
 }
 
 async fn handle_block_range(
     Path((start_height, end_height)): Path<(i32, i32)>,
     Extension(indexer): Extension<ChronikIndexerRef>,
 ) -> Result<Protobuf<proto::Blocks>, ReportError> {
-    let indexer = indexer.read().await;
-    let blocks = indexer.blocks();
-    Ok(Protobuf(blocks.by_range(start_height, end_height)?))
+    let blocks = query_blocking(indexer, move |indexer| {
+        indexer.blocks().by_range(start_height, end_height)
+    })
+    .await?;
+    Ok(Protobuf(blocks))
+}
+
+async fn handle_recent_blocks(
+    Path(num_blocks): Path<usize>,
+    Extension(indexer): Extension<ChronikIndexerRef>,
+) -> Result<Protobuf<proto::Blocks>, ReportError> {
+    let blocks = query_blocking(indexer, move |indexer| {
+        indexer.recent_blocks(num_blocks)
+    })
+    .await?;
+    Ok(Protobuf(proto::Blocks { blocks }))
 }
 
 async fn handle_block(
     Path(hash_or_height): Path<String>,
     Extension(indexer): Extension<ChronikIndexerRef>,
 ) -> Result<Protobuf<proto::Block>, ReportError> {
-    let indexer = indexer.read().await;
-    let blocks = indexer.blocks();
-    Ok(Protobuf(blocks.by_hash_or_height(hash_or_height)?))
+    let block = query_blocking(indexer, move |indexer| {
+        indexer.blocks().by_hash_or_height(hash_or_height)
+    })
+    .await?;
+    Ok(Protobuf(block))
+}
+
+async fn handle_block_header(
+    Path(hash_or_height): Path<String>,
+    Extension(indexer): Extension<ChronikIndexerRef>,
+) -> Result<Protobuf<proto::BlockHeader>, ReportError> {
+    let header = query_blocking(indexer, move |indexer| {
+        indexer.blocks().header(hash_or_height)
+    })
+    .await?;
+    Ok(Protobuf(header))
+}
+
+async fn handle_raw_block(
+    Path(hash_or_height): Path<String>,
+    headers: HeaderMap,
+    Extension(indexer): Extension<ChronikIndexerRef>,
+) -> Result<Response, ReportError> {
+    let raw_block = query_blocking(indexer, move |indexer| {
+        indexer.blocks().raw_block(hash_or_height)
+    })
+    .await?;
+    Ok(byte_range_response(&headers, raw_block))
 }
 
 async fn handle_block_txs(
@@ -170,29 +475,79 @@ async fn handle_block_txs(
     Query(query_params): Query<HashMap<String, String>>,
     Extension(indexer): Extension<ChronikIndexerRef>,
 ) -> Result<Protobuf<proto::TxHistoryPage>, ReportError> {
-    let indexer = indexer.read().await;
-    Ok(Protobuf(
-        handlers::handle_block_txs(hash_or_height, &query_params, &indexer)
-            .await?,
-    ))
+    let page = query_blocking(indexer, move |indexer| {
+        handlers::handle_block_txs(hash_or_height, &query_params, indexer)
+    })
+    .await?;
+    Ok(Protobuf(page))
 }
 
 async fn handle_tx(
     Path(txid): Path<String>,
     Extension(indexer): Extension<ChronikIndexerRef>,
 ) -> Result<Protobuf<proto::Tx>, ReportError> {
-    let indexer = indexer.read().await;
     let txid = txid.parse::<TxId>().wrap_err(NotTxId(txid))?;
-    Ok(Protobuf(indexer.txs().tx_by_id(txid)?))
+    let tx =
+        query_blocking(indexer, move |indexer| indexer.txs().tx_by_id(txid))
+            .await?;
+    Ok(Protobuf(tx))
 }
 
 async fn handle_raw_tx(
     Path(txid): Path<String>,
     Extension(indexer): Extension<ChronikIndexerRef>,
 ) -> Result<Protobuf<proto::RawTx>, ReportError> {
-    let indexer = indexer.read().await;
     let txid = txid.parse::<TxId>().wrap_err(NotTxId(txid))?;
-    Ok(Protobuf(indexer.txs().raw_tx_by_id(&txid)?))
+    let raw_tx = query_blocking(indexer, move |indexer| {
+        indexer.txs().raw_tx_by_id(&txid)
+    })
+    .await?;
+    Ok(Protobuf(raw_tx))
+}
+
+async fn handle_tx_block(
+    Path(txid): Path<String>,
+    Extension(indexer): Extension<ChronikIndexerRef>,
+) -> Result<Protobuf<proto::BlockMetadata>, ReportError> {
+    let txid = txid.parse::<TxId>().wrap_err(NotTxId(txid))?;
+    let metadata = query_blocking(indexer, move |indexer| {
+        indexer.txs().tx_block_metadata(&txid)
+    })
+    .await?;
+    Ok(Protobuf(metadata))
+}
+
+async fn handle_tx_conflicts(
+    Path(txid): Path<String>,
+    Extension(indexer): Extension<ChronikIndexerRef>,
+) -> Result<Protobuf<proto::TxConflicts>, ReportError> {
+    let txid = txid.parse::<TxId>().wrap_err(NotTxId(txid))?;
+    let conflicts = query_blocking(indexer, move |indexer| {
+        Ok(indexer.txs().tx_conflicts(&txid))
+    })
+    .await?;
+    Ok(Protobuf(conflicts))
+}
+
+async fn handle_broadcast_tx(
+    Extension(fn_broadcast_tx): Extension<FnBroadcastTx>,
+    Extension(event_sync): Extension<EventSync>,
+    Protobuf(request): Protobuf<proto::BroadcastTxRequest>,
+) -> Result<Protobuf<proto::BroadcastTxResponse>, ReportError> {
+    // Broadcasting takes the node's locks and waits until the node's
+    // callbacks queued the tx, so it must run on the blocking thread pool,
+    // like DB queries
+    let txid = tokio::task::spawn_blocking(move || -> Result<TxId> {
+        let txid = fn_broadcast_tx(&request.raw_tx).map_err(BroadcastFailed)?;
+        Ok(txid)
+    })
+    .await
+    .map_err(Report::from)??;
+    // Respond once the tx is indexed, so clients can query it right away
+    event_sync.wait_processed().await;
+    Ok(Protobuf(proto::BroadcastTxResponse {
+        txid: txid.to_vec(),
+    }))
 }
 
 async fn handle_script_confirmed_txs(
@@ -200,16 +555,33 @@ async fn handle_script_confirmed_txs(
     Query(query_params): Query<HashMap<String, String>>,
     Extension(indexer): Extension<ChronikIndexerRef>,
 ) -> Result<Protobuf<proto::TxHistoryPage>, ReportError> {
-    let indexer = indexer.read().await;
-    Ok(Protobuf(
+    let page = query_blocking(indexer, move |indexer| {
         handlers::handle_script_confirmed_txs(
             &script_type,
             &payload,
             &query_params,
-            &indexer,
+            indexer,
         )
-        .await?,
-    ))
+    })
+    .await?;
+    Ok(Protobuf(page))
+}
+
+async fn handle_script_confirmed_txs_cursor(
+    Path((script_type, payload)): Path<(String, String)>,
+    Query(query_params): Query<HashMap<String, String>>,
+    Extension(indexer): Extension<ChronikIndexerRef>,
+) -> Result<Protobuf<proto::TxHistoryCursorPage>, ReportError> {
+    let page = query_blocking(indexer, move |indexer| {
+        handlers::handle_script_confirmed_txs_cursor(
+            &script_type,
+            &payload,
+            &query_params,
+            indexer,
+        )
+    })
+    .await?;
+    Ok(Protobuf(page))
 }
 
 async fn handle_script_history(
@@ -217,41 +589,38 @@ async fn handle_script_history(
     Query(query_params): Query<HashMap<String, String>>,
     Extension(indexer): Extension<ChronikIndexerRef>,
 ) -> Result<Protobuf<proto::TxHistoryPage>, ReportError> {
-    let indexer = indexer.read().await;
-    Ok(Protobuf(
+    let page = query_blocking(indexer, move |indexer| {
         handlers::handle_script_history(
             &script_type,
             &payload,
             &query_params,
-            &indexer,
+            indexer,
         )
-        .await?,
-    ))
+    })
+    .await?;
+    Ok(Protobuf(page))
 }
 
 async fn handle_script_unconfirmed_txs(
     Path((script_type, payload)): Path<(String, String)>,
     Extension(indexer): Extension<ChronikIndexerRef>,
 ) -> Result<Protobuf<proto::TxHistoryPage>, ReportError> {
-    let indexer = indexer.read().await;
-    Ok(Protobuf(
-        handlers::handle_script_unconfirmed_txs(
-            &script_type,
-            &payload,
-            &indexer,
-        )
-        .await?,
-    ))
+    let page = query_blocking(indexer, move |indexer| {
+        handlers::handle_script_unconfirmed_txs(&script_type, &payload, indexer)
+    })
+    .await?;
+    Ok(Protobuf(page))
 }
 
 async fn handle_script_utxos(
     Path((script_type, payload)): Path<(String, String)>,
     Extension(indexer): Extension<ChronikIndexerRef>,
 ) -> Result<Protobuf<proto::ScriptUtxos>, ReportError> {
-    let indexer = indexer.read().await;
-    Ok(Protobuf(
-        handlers::handle_script_utxos(&script_type, &payload, &indexer).await?,
-    ))
+    let utxos = query_blocking(indexer, move |indexer| {
+        handlers::handle_script_utxos(&script_type, &payload, indexer)
+    })
+    .await?;
+    Ok(Protobuf(utxos))
 }
 
 async fn handle_ws(
@@ -260,3 +629,78 @@ async fn handle_ws(
 ) -> impl IntoResponse {
     ws.on_upgrade(|ws| handle_subscribe_socket(ws, indexer))
 }
+
+#[cfg(test)]
+mod tests {
+    use abc_rust_error::{Report, Result};
+    use axum::{
+        http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE},
+        routing::get,
+        Router,
+    };
+    use hyper::{body::to_bytes, service::Service, Body, Request};
+
+    use crate::{range::CONTENT_TYPE_OCTET_STREAM, server::compression_layer};
+
+    fn big_body() -> String {
+        "compressible ".repeat(100)
+    }
+
+    #[tokio::test]
+    async fn test_compression() -> Result<()> {
+        let mut router = Router::new()
+            .route("/small", get(|| async { "small" }))
+            .route("/big", get(|| async { big_body() }))
+            .route(
+                "/octet-stream",
+                get(|| async {
+                    ([(CONTENT_TYPE, CONTENT_TYPE_OCTET_STREAM)], big_body())
+                }),
+            )
+            .layer(compression_layer(100));
+
+        let cases = [
+            // Compressed with the accepted encoding
+            ("/big", Some("gzip"), Some("gzip")),
+            ("/big", Some("br"), Some("br")),
+            // Highest q-value wins
+            ("/big", Some("gzip;q=0.5, br"), Some("br")),
+            ("/big", Some("gzip, br;q=0.5"), Some("gzip")),
+            // Identity fallback if no supported encoding is accepted
+            ("/big", None, None),
+            ("/big", Some("identity"), None),
+            ("/big", Some("deflate"), None),
+            // Below the size threshold
+            ("/small", Some("gzip"), None),
+            ("/small", Some("br"), None),
+            // Block data is never compressed
+            ("/octet-stream", Some("gzip"), None),
+        ];
+        for (uri, accept_encoding, expected_encoding) in cases {
+            let mut request = Request::builder().uri(uri);
+            if let Some(accept_encoding) = accept_encoding {
+                request = request.header(ACCEPT_ENCODING, accept_encoding);
+            }
+            let response = router.call(request.body(Body::empty())?).await?;
+            let encoding = response
+                .headers()
+                .get(CONTENT_ENCODING)
+                .map(|encoding| encoding.to_str())
+                .transpose()?;
+            assert_eq!(
+                encoding, expected_encoding,
+                "{uri} {accept_encoding:?}"
+            );
+            // Compressed bodies fail with a boxed error, which isn't an Error
+            let body =
+                to_bytes(response.into_body()).await.map_err(Report::msg)?;
+            match (uri, expected_encoding) {
+                ("/small", _) => assert_eq!(body.as_ref(), b"small"),
+                (_, None) => assert_eq!(body, big_body()),
+                (_, Some(_)) => assert!(body.len() < big_body().len()),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+//! Module for [`RequestTimeouts`] and [`timeout_requests`].
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use abc_rust_error::Report;
+use axum::{
+    extract::{MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use thiserror::Error;
+
+use crate::error::ReportError;
+
+/// Timeouts for requests, with optional overrides for individual routes.
+#[derive(Clone, Debug)]
+pub struct RequestTimeouts {
+    /// Timeout for routes without an override in `routes`.
+    pub default: Duration,
+    /// Timeouts for specific routes, keyed by the route pattern as registered
+    /// in the router, e.g. "/blocks/:start/:end".
+    pub routes: Arc<HashMap<String, Duration>>,
+}
+
+/// Errors indicating a request took too long.
+#[derive(Debug, Eq, Error, PartialEq)]
+pub enum ChronikTimeoutError {
+    /// Handling the request took longer than its configured timeout
+    #[error("504: Request timed out after {0:?}")]
+    RequestTimeout(Duration),
+}
+
+use self::ChronikTimeoutError::*;
+
+impl RequestTimeouts {
+    /// Timeout for the given route pattern, falling back to the default.
+    pub fn timeout_for(&self, route: Option<&str>) -> Duration {
+        route
+            .and_then(|route| self.routes.get(route))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Middleware answering requests that take longer than their timeout in
+/// [`RequestTimeouts`] with "504 Gateway Timeout".
+///
+/// This drops the request's future, so clients don't wait indefinitely and
+/// async work like waiting for locks stops. Queries already running on the
+/// blocking thread pool aren't interrupted though, see
+/// [`crate::server::query_blocking`].
+pub async fn timeout_requests<B>(
+    State(timeouts): State<RequestTimeouts>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let route = req.extensions().get::<MatchedPath>();
+    let timeout = timeouts.timeout_for(route.map(|path| path.as_str()));
+    match tokio::time::timeout(timeout, next.run(req)).await {
+        Ok(response) => response,
+        Err(_) => {
+            ReportError(Report::from(RequestTimeout(timeout))).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc, time::Duration};
+
+    use abc_rust_error::Result;
+    use axum::{middleware, routing::get, Router};
+    use chronik_proto::proto;
+    use hyper::{body::to_bytes, service::Service, Body, Request, StatusCode};
+    use prost::Message;
+
+    use crate::timeout::{timeout_requests, RequestTimeouts};
+
+    #[tokio::test]
+    async fn test_timeout_requests() -> Result<()> {
+        async fn handle_slow() -> &'static str {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            "done"
+        }
+
+        let timeouts = RequestTimeouts {
+            default: Duration::from_secs(10),
+            routes: Arc::new(HashMap::from([(
+                "/fast/:param".to_string(),
+                Duration::from_millis(20),
+            )])),
+        };
+        let mut router = Router::new()
+            .route("/slow", get(handle_slow))
+            .route("/fast/:param", get(handle_slow))
+            .layer(middleware::from_fn_with_state(timeouts, timeout_requests));
+
+        // Default timeout not exceeded
+        let response = router
+            .call(Request::builder().uri("/slow").body(Body::empty())?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(&mut response.into_body()).await?;
+        assert_eq!(body.as_ref(), b"done");
+
+        // Route timeout exceeded
+        let response = router
+            .call(Request::builder().uri("/fast/abc").body(Body::empty())?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        let body = to_bytes(&mut response.into_body()).await?;
+        assert_eq!(
+            proto::Error::decode(body)?,
+            proto::Error {
+                msg: "504: Request timed out after 20ms".to_string(),
+            },
+        );
+
+        Ok(())
+    }
+}
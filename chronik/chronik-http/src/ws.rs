@@ -8,8 +8,9 @@ use std::collections::HashMap;
 
 use abc_rust_error::Result;
 use axum::extract::ws::{self, WebSocket};
-use bitcoinsuite_core::script::ScriptVariant;
+use bitcoinsuite_core::script::{Script, ScriptVariant};
 use chronik_indexer::{
+    indexer::ChronikIndexer,
     subs::{BlockMsg, BlockMsgType},
     subs_group::{TxMsg, TxMsgType},
 };
@@ -39,6 +40,13 @@ pub enum ChronikWsError {
 
 use self::ChronikWsError::*;
 
+/// Number of txs of the script history sent in a [`proto::MsgScriptSnapshot`]
+const SNAPSHOT_HISTORY_PAGE_SIZE: usize = 25;
+
+/// Max. number of UTXOs sent in a [`proto::MsgScriptSnapshot`], so subscribing
+/// to a script with a huge number of UTXOs can't tie up the server.
+const SNAPSHOT_MAX_UTXOS: usize = 1000;
+
 enum WsAction {
     Close,
     Sub(WsSub),
@@ -53,7 +61,7 @@ struct WsSub {
 
 enum WsSubType {
     Blocks,
-    Script(ScriptVariant),
+    Script(ScriptVariant, proto::WsSubScript),
 }
 
 type SubRecvBlocks = Option<broadcast::Receiver<BlockMsg>>;
@@ -95,8 +103,37 @@ impl SubRecv {
         }
     }
 
-    async fn handle_sub(&mut self, sub: WsSub, indexer: &ChronikIndexerRef) {
-        let indexer = indexer.read().await;
+    /// Handle the subscription, and return the snapshot message for the
+    /// subscription, if one has been requested.
+    async fn handle_sub(
+        &mut self,
+        sub: WsSub,
+        indexer: &ChronikIndexerRef,
+    ) -> Result<Option<ws::Message>> {
+        // Holding the read lock while taking the snapshot and subscribing
+        // ensures no tx can be indexed in between.
+        let indexer = indexer.clone().read_owned().await;
+        let (indexer, snapshot) = match &sub.sub_type {
+            WsSubType::Script(script_variant, sub_script)
+                if !sub.is_unsub && sub_script.snapshot =>
+            {
+                // Reads from the DB, so keep it off the executor threads, like
+                // query_blocking, but move the read lock to the blocking task
+                // and back instead of releasing it.
+                let script = script_variant.to_script();
+                let sub_script = sub_script.clone();
+                tokio::task::spawn_blocking(move || {
+                    let snapshot =
+                        script_snapshot_msg(&indexer, &script, sub_script);
+                    (indexer, Some(snapshot))
+                })
+                .await?
+            }
+            _ => (indexer, None),
+        };
+        // Only lock the subs after taking the snapshot, so other connections
+        // can still (un)subscribe while it's taken
+        let snapshot = snapshot.transpose()?;
         let mut subs = indexer.subs().write().await;
         match sub.sub_type {
             WsSubType::Blocks => {
@@ -111,7 +148,7 @@ impl SubRecv {
                     }
                 }
             }
-            WsSubType::Script(script_variant) => {
+            WsSubType::Script(script_variant, sub_script) => {
                 let script = script_variant.to_script();
                 if sub.is_unsub {
                     log_chronik!("WS unsubscribe from {:?}\n", script_variant);
@@ -125,6 +162,7 @@ impl SubRecv {
                 }
             }
         }
+        Ok(snapshot)
     }
 
     async fn cleanup(self, indexer: &ChronikIndexerRef) {
@@ -157,12 +195,13 @@ fn sub_client_msg_action(
                 sub_type: match sub.sub_type {
                     None => return Err(MissingSubType.into()),
                     Some(SubType::Blocks(_)) => WsSubType::Blocks,
-                    Some(SubType::Script(script)) => {
-                        WsSubType::Script(parse_script_variant(
+                    Some(SubType::Script(script)) => WsSubType::Script(
+                        parse_script_variant(
                             &script.script_type,
                             &script.payload,
-                        )?)
-                    }
+                        )?,
+                        script,
+                    ),
                 },
             }))
         }
@@ -218,6 +257,33 @@ fn sub_script_msg_action(
     Ok(WsAction::Message(msg))
 }
 
+fn script_snapshot_msg(
+    indexer: &ChronikIndexer,
+    script: &Script,
+    sub_script: proto::WsSubScript,
+) -> Result<ws::Message> {
+    use proto::ws_msg::MsgType;
+    // Ask for one more UTXO to tell whether there are more
+    let mut utxos = indexer
+        .script_utxos()?
+        .first_utxos(script, SNAPSHOT_MAX_UTXOS + 1)?;
+    let utxos_truncated = utxos.len() > SNAPSHOT_MAX_UTXOS;
+    utxos.truncate(SNAPSHOT_MAX_UTXOS);
+    let history = indexer.script_history()?.rev_history(
+        script,
+        0,
+        SNAPSHOT_HISTORY_PAGE_SIZE,
+    )?;
+    let msg_type = Some(MsgType::ScriptSnapshot(proto::MsgScriptSnapshot {
+        script: Some(sub_script),
+        utxos,
+        history: Some(history),
+        utxos_truncated,
+    }));
+    let msg_proto = proto::WsMsg { msg_type };
+    Ok(ws::Message::Binary(msg_proto.encode_to_vec()))
+}
+
 /// Future for a WS connection, which will run indefinitely until the WS will be
 /// closed.
 pub async fn handle_subscribe_socket(
@@ -248,7 +314,21 @@ pub async fn handle_subscribe_socket(
                 recv.cleanup(&indexer).await;
                 return;
             }
-            WsAction::Sub(sub) => recv.handle_sub(sub, &indexer).await,
+            WsAction::Sub(sub) => match recv.handle_sub(sub, &indexer).await {
+                Ok(None) => {}
+                Ok(Some(msg)) => {
+                    if socket.send(msg).await.is_err() {
+                        return;
+                    }
+                }
+                Err(report) => {
+                    let (_, error_proto) = report_status_error(report);
+                    let msg = ws::Message::Binary(error_proto.encode_to_vec());
+                    if socket.send(msg).await.is_err() {
+                        return;
+                    }
+                }
+            },
             WsAction::Message(msg) => match socket.send(msg).await {
                 Ok(()) => {}
                 Err(_) => return,
@@ -9,6 +9,18 @@ use chronik_db::io::BlockHeight;
 use thiserror::Error;
 
 /// Struct containing the indexer's Avalanche state.
+///
+/// Avalanche finalizes blocks in order, so it's sufficient to track the
+/// height of the highest finalized block: every block at or below it is
+/// final, and this is what the `is_final` fields of blocks, txs and UTXOs
+/// returned by queries are derived from.
+///
+/// A block that has been connected but not finalized yet is reported with
+/// `is_final = false`; once the node finalizes it (or any block above it),
+/// the same query returns `is_final = true`. Mempool txs are never final.
+///
+/// The height is stored in the DB's metadata whenever it changes, so blocks
+/// stay final across restarts.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Avalanche {
     /// Height of the highest finalized block. Default: -1
@@ -0,0 +1,177 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+//! Module for [`DebugCounters`].
+
+use std::{
+    sync::atomic::{AtomicI32, AtomicU64, Ordering},
+    time::Duration,
+};
+
+use chronik_db::io::BlockHeight;
+
+/// Internal counters of Chronik, for diagnosing production issues.
+///
+/// They are updated by the indexer and the node bridge, and can be read
+/// without taking the indexer lock, so they can be inspected even while the
+/// indexer is stuck.
+#[derive(Debug)]
+pub struct DebugCounters {
+    num_write_lock_waits: AtomicU64,
+    total_write_lock_wait_micros: AtomicU64,
+    max_write_lock_wait_micros: AtomicU64,
+    num_queued_events: AtomicU64,
+    max_queued_events: AtomicU64,
+    resync_start_height: AtomicI32,
+    resync_height: AtomicI32,
+    resync_tip_height: AtomicI32,
+}
+
+/// Values of [`DebugCounters`] at one point in time.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DebugCountersSnapshot {
+    /// How often the indexer took the write lock.
+    pub num_write_lock_waits: u64,
+    /// Total time spent waiting for the write lock, in microseconds.
+    pub total_write_lock_wait_micros: u64,
+    /// Longest time spent waiting for the write lock, in microseconds.
+    pub max_write_lock_wait_micros: u64,
+    /// Number of node events queued but not yet taken by the indexer.
+    pub num_queued_events: u64,
+    /// Highest number of node events queued at once.
+    pub max_queued_events: u64,
+    /// Height the last resync started after, -1 if there was no resync.
+    pub resync_start_height: BlockHeight,
+    /// Height of the last block indexed by the resync, -1 if there was no
+    /// resync.
+    pub resync_height: BlockHeight,
+    /// Height of the node's tip the resync syncs to, -1 if there was no
+    /// resync.
+    pub resync_tip_height: BlockHeight,
+}
+
+impl DebugCounters {
+    /// Create counters with no recorded activity.
+    pub fn new() -> Self {
+        DebugCounters {
+            num_write_lock_waits: AtomicU64::new(0),
+            total_write_lock_wait_micros: AtomicU64::new(0),
+            max_write_lock_wait_micros: AtomicU64::new(0),
+            num_queued_events: AtomicU64::new(0),
+            max_queued_events: AtomicU64::new(0),
+            resync_start_height: AtomicI32::new(-1),
+            resync_height: AtomicI32::new(-1),
+            resync_tip_height: AtomicI32::new(-1),
+        }
+    }
+
+    /// Record that acquiring the indexer's write lock took `wait`.
+    pub fn record_write_lock_wait(&self, wait: Duration) {
+        let micros = u64::try_from(wait.as_micros()).unwrap_or(u64::MAX);
+        self.num_write_lock_waits.fetch_add(1, Ordering::Relaxed);
+        self.total_write_lock_wait_micros
+            .fetch_add(micros, Ordering::Relaxed);
+        self.max_write_lock_wait_micros
+            .fetch_max(micros, Ordering::Relaxed);
+    }
+
+    /// Record that a node event has been queued for the indexer.
+    pub fn event_queued(&self) {
+        let num_queued =
+            self.num_queued_events.fetch_add(1, Ordering::Relaxed) + 1;
+        self.max_queued_events
+            .fetch_max(num_queued, Ordering::Relaxed);
+    }
+
+    /// Record that a queued node event has been taken by the indexer, or
+    /// couldn't be queued after all.
+    pub fn event_dequeued(&self) {
+        self.num_queued_events.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Record that a resync from `start_height` to `tip_height` started.
+    pub fn start_resync(
+        &self,
+        start_height: BlockHeight,
+        tip_height: BlockHeight,
+    ) {
+        self.resync_start_height
+            .store(start_height, Ordering::Relaxed);
+        self.resync_height.store(start_height, Ordering::Relaxed);
+        self.resync_tip_height.store(tip_height, Ordering::Relaxed);
+    }
+
+    /// Record that the resync indexed the block at `height`.
+    pub fn set_resync_height(&self, height: BlockHeight) {
+        self.resync_height.store(height, Ordering::Relaxed);
+    }
+
+    /// Read the current values of the counters.
+    pub fn snapshot(&self) -> DebugCountersSnapshot {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        DebugCountersSnapshot {
+            num_write_lock_waits: load(&self.num_write_lock_waits),
+            total_write_lock_wait_micros: load(
+                &self.total_write_lock_wait_micros,
+            ),
+            max_write_lock_wait_micros: load(&self.max_write_lock_wait_micros),
+            num_queued_events: load(&self.num_queued_events),
+            max_queued_events: load(&self.max_queued_events),
+            resync_start_height: self
+                .resync_start_height
+                .load(Ordering::Relaxed),
+            resync_height: self.resync_height.load(Ordering::Relaxed),
+            resync_tip_height: self.resync_tip_height.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for DebugCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::debug::{DebugCounters, DebugCountersSnapshot};
+
+    #[test]
+    fn test_debug_counters() {
+        let counters = DebugCounters::new();
+        assert_eq!(
+            counters.snapshot(),
+            DebugCountersSnapshot {
+                resync_start_height: -1,
+                resync_height: -1,
+                resync_tip_height: -1,
+                ..Default::default()
+            },
+        );
+
+        counters.record_write_lock_wait(Duration::from_micros(30));
+        counters.record_write_lock_wait(Duration::from_micros(10));
+        counters.event_queued();
+        counters.event_queued();
+        counters.event_dequeued();
+        counters.event_queued();
+        counters.start_resync(4, 10);
+        counters.set_resync_height(7);
+        assert_eq!(
+            counters.snapshot(),
+            DebugCountersSnapshot {
+                num_write_lock_waits: 2,
+                total_write_lock_wait_micros: 40,
+                max_write_lock_wait_micros: 30,
+                num_queued_events: 2,
+                max_queued_events: 2,
+                resync_start_height: 4,
+                resync_height: 7,
+                resync_tip_height: 10,
+            },
+        );
+    }
+}
@@ -4,18 +4,27 @@
 
 //! Module containing [`ChronikIndexer`] to index blocks and txs.
 
-use std::path::PathBuf;
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use abc_rust_error::{Result, WrapErr};
 use bitcoinsuite_core::{
     block::BlockHash,
-    tx::{Tx, TxId},
+    hash::{merkle_root, Hashed, Sha256d},
+    network::Network,
+    script::Script,
+    tx::{OutPoint, Tx, TxId},
 };
 use chronik_bridge::{ffi, util::expect_unique_ptr};
 use chronik_db::{
-    db::{Db, WriteBatch},
+    db::{Db, DbStats, WriteBatch},
+    export::{clear_db, export_db, import_db},
     groups::{
-        FnCompressScript, ScriptGroup, ScriptHistoryWriter, ScriptUtxoWriter,
+        FnCompressScript, ScriptFilter, ScriptGroup, ScriptHistoryReader,
+        ScriptHistoryWriter, ScriptUtxoWriter,
     },
     index_tx::prepare_indexed_txs,
     io::{
@@ -25,38 +34,101 @@ use chronik_db::{
     },
     mem::{Mempool, MempoolTx},
 };
+use chronik_proto::proto;
 use chronik_util::{log, log_chronik};
 use thiserror::Error;
 use tokio::sync::RwLock;
 
 use crate::{
     avalanche::Avalanche,
-    query::{QueryBlocks, QueryGroupHistory, QueryGroupUtxos, QueryTxs},
+    debug::DebugCounters,
+    query::{
+        GroupActivity, QueryBlocks, QueryGroupHistory, QueryGroupUtxos,
+        QueryTxs, SpentInfo,
+    },
     subs::{BlockMsg, BlockMsgType, Subs},
     subs_group::TxMsgType,
 };
 
+/// Schema version of the DB written by this version of Chronik.
+///
+/// Stored in the DB's metadata on first setup and compared against on every
+/// subsequent [`ChronikIndexer::setup`]; bump it whenever the storage format
+/// changes so that old databases are rejected instead of misread.
 const CURRENT_INDEXER_VERSION: SchemaVersion = 7;
 
+/// Default for [`ChronikIndexerParams::resync_commit_interval`].
+pub const DEFAULT_RESYNC_COMMIT_INTERVAL: u32 = 100;
+
+/// Default for [`ChronikIndexerParams::conflict_retention_blocks`], about a
+/// day of blocks.
+pub const DEFAULT_CONFLICT_RETENTION_BLOCKS: BlockHeight = 144;
+
 /// Params for setting up a [`ChronikIndexer`] instance.
 #[derive(Clone)]
 pub struct ChronikIndexerParams {
     /// Folder where the node stores its data, net-dependent.
     pub datadir_net: PathBuf,
+    /// Network the node is running on. Stored in the DB on first setup, and
+    /// opening a DB built for a different network fails.
+    pub network: Network,
     /// Whether to clear the DB before opening the DB, e.g. when reindexing.
+    /// Required when the DB's schema version doesn't match this Chronik's.
     pub wipe_db: bool,
-    /// Function ptr to compress scripts.
+    /// Whether to open an existing DB read-only, e.g. to serve queries from a
+    /// copy of another node's DB. All `handle_*` methods will fail.
+    pub read_only: bool,
+    /// Function to compress scripts.
     pub fn_compress_script: FnCompressScript,
+    /// How many blocks to index during [`ChronikIndexer::resync_indexer`]
+    /// before committing them durably to disk; 0 and 1 commit every block.
+    ///
+    /// Every block is still written atomically, but only every n-th block
+    /// flushes the write-ahead log, which makes the initial sync faster. If
+    /// the node crashes during resync, the index will end up to n - 1 blocks
+    /// below the last indexed block; it stays consistent, and the missing
+    /// blocks are indexed again on the next startup.
+    pub resync_commit_interval: u32,
+    /// For how many blocks to keep the conflicts between mempool txs after
+    /// they've been resolved, i.e. one of the txs has been mined or has left
+    /// the mempool; 0 forgets them with the next block.
+    ///
+    /// Conflicts are only kept in memory, this bounds how much memory they
+    /// take up on long-running nodes.
+    pub conflict_retention_blocks: BlockHeight,
+    /// Whether to recompute the Merkle root of the txs of every block bridged
+    /// from the node and compare it against the block header, to catch
+    /// corrupted block data before it is indexed.
+    pub verify_merkle_root: bool,
+    /// If set, only the history and UTXOs of scripts matching this filter are
+    /// indexed, e.g. [`chronik_db::groups::filter_scripts`] for a fixed set of
+    /// scripts. Blocks and txs are still indexed in full.
+    ///
+    /// The fingerprint of the filter is stored in the DB on first setup, and
+    /// opening a DB built with a different filter fails.
+    pub script_filter: Option<ScriptFilter>,
 }
 
 /// Struct for indexing blocks and txs. Maintains db handles and mempool.
 #[derive(Debug)]
 pub struct ChronikIndexer {
-    db: Db,
+    db: Arc<Db>,
+    network: Network,
+    script_filter_fingerprint: Vec<u8>,
     mempool: Mempool,
     script_group: ScriptGroup,
     avalanche: Avalanche,
     subs: RwLock<Subs>,
+    read_only: bool,
+    resync_commit_interval: u32,
+    verify_merkle_root: bool,
+    debug_counters: Arc<DebugCounters>,
+}
+
+/// Exports the index, see [`ChronikIndexer::exporter`].
+#[derive(Clone, Debug)]
+pub struct IndexExporter {
+    db: Arc<Db>,
 }
 
 /// Block to be indexed by Chronik.
@@ -79,6 +151,20 @@ pub enum ChronikIndexerError {
     #[error("Failed creating path {0}")]
     CreateIndexesDirFailed(PathBuf),
 
+    /// The datadir of the network doesn't exist or isn't writable
+    #[error(
+        "Chronik datadir not writable: {0} doesn't exist or isn't writable"
+    )]
+    DatadirNotWritable(PathBuf),
+
+    /// The datadir of the network doesn't exist, for a read-only indexer
+    #[error("Chronik datadir not found: {0} doesn't exist")]
+    DatadirNotFound(PathBuf),
+
+    /// Folder for the indexes doesn't exist or isn't writable
+    #[error("Path {0} doesn't exist or isn't writable")]
+    PathNotWritable(PathBuf),
+
     /// Cannot rewind blocks that bitcoind doesn't have
     #[error(
         "Cannot rewind Chronik, it contains block {0} that the node doesn't \
@@ -128,6 +214,73 @@ pub enum ChronikIndexerError {
         CURRENT_INDEXER_VERSION
     )]
     DatabaseOutdated(SchemaVersion),
+
+    /// An import into the DB has been started, but hasn't finished
+    #[error(
+        "Chronik database has an unfinished import, e.g. because the node \
+         crashed while importing. -reindex/-chronikreindex to wipe the \
+         database, then import again."
+    )]
+    UnfinishedImport,
+
+    /// Database has been built for a different network
+    #[error(
+        "Chronik database has been built for network {db}, but the node is \
+         running on {node}. Check the datadir, or -reindex/-chronikreindex to \
+         rebuild the database."
+    )]
+    WrongNetwork {
+        /// Network stored in the DB
+        db: Network,
+        /// Network of the node
+        node: Network,
+    },
+
+    /// Database has been built with a different script filter
+    #[error(
+        "Chronik database has been built with script filter {db}, but the \
+         node uses script filter {node}. Check the script filter, or \
+         -reindex/-chronikreindex to rebuild the database."
+    )]
+    WrongScriptFilter {
+        /// Hex of the script filter fingerprint stored in the DB, or "none"
+        db: String,
+        /// Hex of the script filter fingerprint of the node, or "none"
+        node: String,
+    },
+
+    /// Merkle root of the bridged txs doesn't match the block header
+    #[error(
+        "Corrupted block data: Merkle root of the txs of block {hash} is {}, \
+         but the header has {}",
+        .actual.hex_be(),
+        .expected.hex_be()
+    )]
+    MerkleRootMismatch {
+        /// Hash of the block with the mismatch
+        hash: BlockHash,
+        /// Merkle root in the block header
+        expected: Sha256d,
+        /// Merkle root computed from the txs
+        actual: Sha256d,
+    },
+
+    /// Attempted to disconnect a block from an empty index
+    #[error("Cannot disconnect block {0}, the index has no blocks")]
+    DisconnectBlockEmptyIndex(BlockHash),
+
+    /// Attempted to disconnect a block that isn't the tip of the index
+    #[error("Cannot disconnect block {hash}, the tip of the index is {tip}")]
+    DisconnectBlockNotTip {
+        /// Hash of the block that should be disconnected
+        hash: BlockHash,
+        /// Hash of the actual tip of the index
+        tip: BlockHash,
+    },
+
+    /// Attempted to modify the index of an indexer opened read-only
+    #[error("Chronik was opened read-only, cannot {0}")]
+    ReadOnly(&'static str),
 }
 
 use self::ChronikIndexerError::*;
@@ -136,6 +289,15 @@ impl ChronikIndexer {
     /// Setup the indexer with the given parameters, e.g. open the DB etc.
     pub fn setup(params: ChronikIndexerParams) -> Result<Self> {
         let indexes_path = params.datadir_net.join("indexes");
+        if params.read_only {
+            if !params.datadir_net.is_dir() {
+                return Err(DatadirNotFound(params.datadir_net).into());
+            }
+            return Self::setup_read_only(params, &indexes_path);
+        }
+        if !is_writable_dir(&params.datadir_net) {
+            return Err(DatadirNotWritable(params.datadir_net).into());
+        }
         if !indexes_path.exists() {
             std::fs::create_dir(&indexes_path).wrap_err_with(|| {
                 CreateIndexesDirFailed(indexes_path.clone())
@@ -148,23 +310,114 @@ impl ChronikIndexer {
         }
         log_chronik!("Opening Chronik at {}\n", db_path.to_string_lossy());
         let db = Db::open(&db_path)?;
-        verify_schema_version(&db)?;
-        let script_group = ScriptGroup::new(params.fn_compress_script);
-        let mempool = Mempool::new(script_group.clone());
+        verify_import_finished(&db)?;
+        verify_schema_version(&db, false)?;
+        verify_network(&db, params.network, false)?;
+        verify_script_filter(&db, params.script_filter_fingerprint(), false)?;
+        let avalanche = load_avalanche(&db)?;
+        let script_group = params.script_group();
+        let mempool = Mempool::new(
+            script_group.clone(),
+            params.conflict_retention_blocks,
+        );
+        Ok(ChronikIndexer {
+            db: Arc::new(db),
+            network: params.network,
+            script_filter_fingerprint: params
+                .script_filter_fingerprint()
+                .to_vec(),
+            mempool,
+            script_group: script_group.clone(),
+            avalanche,
+            subs: RwLock::new(Subs::new(script_group)),
+            read_only: false,
+            resync_commit_interval: params.resync_commit_interval,
+            verify_merkle_root: params.verify_merkle_root,
+            debug_counters: Arc::new(DebugCounters::new()),
+        })
+    }
+
+    fn setup_read_only(
+        params: ChronikIndexerParams,
+        indexes_path: &Path,
+    ) -> Result<Self> {
+        if params.wipe_db {
+            return Err(ReadOnly("wipe the DB").into());
+        }
+        let db_path = indexes_path.join("chronik");
+        log_chronik!(
+            "Opening Chronik read-only at {}\n",
+            db_path.to_string_lossy(),
+        );
+        let db = Db::open_read_only(&db_path)?;
+        verify_import_finished(&db)?;
+        verify_schema_version(&db, true)?;
+        verify_network(&db, params.network, true)?;
+        verify_script_filter(&db, params.script_filter_fingerprint(), true)?;
+        let avalanche = load_avalanche(&db)?;
+        let script_group = params.script_group();
+        let mempool = Mempool::new(
+            script_group.clone(),
+            params.conflict_retention_blocks,
+        );
         Ok(ChronikIndexer {
-            db,
+            db: Arc::new(db),
+            network: params.network,
+            script_filter_fingerprint: params
+                .script_filter_fingerprint()
+                .to_vec(),
             mempool,
             script_group: script_group.clone(),
-            avalanche: Avalanche::default(),
+            avalanche,
             subs: RwLock::new(Subs::new(script_group)),
+            read_only: true,
+            resync_commit_interval: params.resync_commit_interval,
+            verify_merkle_root: params.verify_merkle_root,
+            debug_counters: Arc::new(DebugCounters::new()),
         })
     }
 
+    /// Check whether [`ChronikIndexer::setup`] is likely to succeed with the
+    /// given params, without modifying anything on disk.
+    ///
+    /// The indexes folder (or the datadir, if it doesn't exist yet) has to be
+    /// writable, and an existing DB has to open and have a matching schema
+    /// version, network and script filter and no unfinished import, unless it
+    /// would be wiped anyway.
+    pub fn validate_setup(params: &ChronikIndexerParams) -> Result<()> {
+        let indexes_path = params.datadir_net.join("indexes");
+        let parent_path = match indexes_path.exists() {
+            true => &indexes_path,
+            false => &params.datadir_net,
+        };
+        if !is_writable_dir(parent_path) {
+            return Err(PathNotWritable(parent_path.clone()).into());
+        }
+        let db_path = indexes_path.join("chronik");
+        if !params.wipe_db && db_path.exists() {
+            let db = Db::open_read_only(&db_path)?;
+            verify_import_finished(&db)?;
+            verify_schema_version(&db, true)?;
+            verify_network(&db, params.network, true)?;
+            verify_script_filter(
+                &db,
+                params.script_filter_fingerprint(),
+                true,
+            )?;
+        }
+        Ok(())
+    }
+
     /// Resync Chronik index to the node
+    ///
+    /// Stops early if the node is shutting down, e.g. because it has been
+    /// started with the wrong datadir. The blocks indexed so far are
+    /// committed, and the remaining ones are indexed on the next startup.
     pub fn resync_indexer(
         &mut self,
         bridge: &ffi::ChronikBridge,
     ) -> Result<()> {
+        self.ensure_writable("resync the index")?;
         let block_reader = BlockReader::new(&self.db)?;
         let indexer_tip = block_reader.tip()?;
         let Ok(node_tip_index) = bridge.get_chain_tip() else {
@@ -200,14 +453,59 @@ impl ChronikIndexer {
                 -1
             }
         };
-        let tip_height = node_tip_info.height;
+        let is_complete = self.resync_blocks(
+            fork_height,
+            node_height,
+            |indexer, height| {
+                let block_index =
+                    ffi::get_block_ancestor(node_tip_index, height)?;
+                indexer.load_chronik_block(bridge, block_index)
+            },
+            ffi::shutdown_requested,
+        )?;
+        if is_complete {
+            log!(
+                "Chronik completed re-syncing with the node, both are now at \
+                 block {node_tip_hash} at height {node_height}.\n"
+            );
+        }
+        Ok(())
+    }
+
+    /// Connect the blocks after `fork_height` up to `tip_height`, loaded by
+    /// `load_block`, committing them every
+    /// [`ChronikIndexerParams::resync_commit_interval`] blocks.
+    ///
+    /// The WAL is flushed on every exit path, so the blocks connected so far
+    /// are committed even if loading or connecting a block fails. Returns
+    /// false if it stopped early because `shutdown_requested` returned true.
+    fn resync_blocks(
+        &mut self,
+        fork_height: BlockHeight,
+        tip_height: BlockHeight,
+        mut load_block: impl FnMut(&Self, BlockHeight) -> Result<ChronikBlock>,
+        shutdown_requested: impl Fn() -> bool,
+    ) -> Result<bool> {
+        let commit_interval = self.resync_commit_interval.max(1);
+        let flush_guard = FlushWalGuard(Some(Arc::clone(&self.db)));
+        self.debug_counters.start_resync(fork_height, tip_height);
         for height in fork_height + 1..=tip_height {
-            let block_index = ffi::get_block_ancestor(node_tip_index, height)?;
-            let ffi_block = bridge.load_block(block_index)?;
-            let ffi_block = expect_unique_ptr("load_block", &ffi_block);
-            let block = self.make_chronik_block(ffi_block, block_index)?;
+            if shutdown_requested() {
+                // The blocks indexed so far are committed, the rest are
+                // indexed on the next startup
+                flush_guard.flush()?;
+                log!(
+                    "Stopped re-syncing Chronik at height \
+                     {}/{tip_height} because the node is shutting down.\n",
+                    height - 1,
+                );
+                return Ok(false);
+            }
+            let block = load_block(self, height)?;
             let hash = block.db_block.hash.clone();
-            self.handle_block_connected(block)?;
+            let is_commit = height as u32 % commit_interval == 0;
+            self.connect_block(block, is_commit)?;
+            self.debug_counters.set_resync_height(height);
             log_chronik!(
                 "Added block {hash}, height {height}/{tip_height} to Chronik\n"
             );
@@ -218,11 +516,8 @@ impl ChronikIndexer {
                 );
             }
         }
-        log!(
-            "Chronik completed re-syncing with the node, both are now at \
-             block {node_tip_hash} at height {node_height}.\n"
-        );
-        Ok(())
+        flush_guard.flush()?;
+        Ok(true)
     }
 
     fn rewind_indexer(
@@ -254,19 +549,49 @@ impl ChronikIndexer {
             let block_index = bridge
                 .lookup_block_index(db_block.hash.to_bytes())
                 .map_err(|_| CannotRewindChronik(db_block.hash))?;
-            let ffi_block = bridge.load_block(block_index)?;
-            let ffi_block = expect_unique_ptr("load_block", &ffi_block);
-            let block = self.make_chronik_block(ffi_block, block_index)?;
+            let block = self.load_chronik_block(bridge, block_index)?;
             self.handle_block_disconnected(block)?;
         }
         Ok(fork_info.height)
     }
 
     /// Add transaction to the indexer's mempool.
+    ///
+    /// If the tx is already in the mempool, e.g. because a notification was
+    /// replayed, this is logged and the tx is skipped, without notifying
+    /// subscribers again.
+    ///
+    /// If the tx double-spends txs in the mempool, the conflict is recorded
+    /// and logged, but the tx is not indexed, see [`chronik_db::mem::MempoolConflicts`].
     pub fn handle_tx_added_to_mempool(
         &mut self,
         mempool_tx: MempoolTx,
     ) -> Result<()> {
+        self.ensure_writable("add txs to the mempool")?;
+        let txid = mempool_tx.tx.txid_ref();
+        if self.mempool.tx(txid).is_some() {
+            log_chronik!(
+                "Chronik: tx {} is already in the mempool, skipping it\n",
+                txid,
+            );
+            return Ok(());
+        }
+        // Indexing a double-spend would corrupt the mempool indices. The node
+        // only accepts a double-spend after removing the txs it conflicts
+        // with, so Chronik's mempool is out of sync; record the conflict so
+        // it can be inspected, but keep running, the node removes the
+        // conflicting txs eventually.
+        let conflicting_txids = self.mempool.conflicting_txids(&mempool_tx.tx);
+        if !conflicting_txids.is_empty() {
+            log!(
+                "Chronik: tx {} conflicts with mempool txs {:?}, not indexing \
+                 it\n",
+                txid,
+                conflicting_txids,
+            );
+            self.mempool.insert_conflict(*txid, &conflicting_txids);
+            return Ok(());
+        }
         self.subs
             .get_mut()
             .handle_tx_event(&mempool_tx.tx, TxMsgType::AddedToMempool);
@@ -278,7 +603,30 @@ impl ChronikIndexer {
     /// etc. This is not called when the transaction has been mined (and thus
     /// also removed from the mempool).
     pub fn handle_tx_removed_from_mempool(&mut self, txid: TxId) -> Result<()> {
-        let mempool_tx = self.mempool.remove(txid)?;
+        self.remove_mempool_tx(txid, false)
+    }
+
+    /// Remove tx from the indexer's mempool because a tx spending the same
+    /// outputs is mined in a block that is about to be connected. The
+    /// conflict between the two txs is recorded once the block is connected.
+    pub fn handle_tx_removed_for_block(&mut self, txid: TxId) -> Result<()> {
+        self.remove_mempool_tx(txid, true)
+    }
+
+    fn remove_mempool_tx(&mut self, txid: TxId, for_block: bool) -> Result<()> {
+        self.ensure_writable("remove txs from the mempool")?;
+        let conflicts = self.mempool.conflicts();
+        if self.mempool.tx(&txid).is_none()
+            && conflicts.conflicts(&txid).is_some()
+        {
+            // Tx has never been indexed because it was a conflict
+            self.mempool.resolve_conflicts(&txid);
+            return Ok(());
+        }
+        let mempool_tx = match for_block {
+            true => self.mempool.remove_for_block(txid)?,
+            false => self.mempool.remove(txid)?,
+        };
         self.subs
             .get_mut()
             .handle_tx_event(&mempool_tx.tx, TxMsgType::RemovedFromMempool);
@@ -286,10 +634,33 @@ impl ChronikIndexer {
     }
 
     /// Add the block to the index.
+    ///
+    /// The `time_first_seen` of txs that are missing it is taken from the
+    /// mempool, as the block may have been built before the txs were added
+    /// to the mempool of this indexer (e.g. if those events were still
+    /// queued).
     pub fn handle_block_connected(
         &mut self,
         block: ChronikBlock,
     ) -> Result<()> {
+        self.connect_block(block, true)
+    }
+
+    /// Add the block to the index; if `commit` is false, it isn't flushed to
+    /// the write-ahead log yet, see [`Db::write_batch_deferred`].
+    fn connect_block(
+        &mut self,
+        mut block: ChronikBlock,
+        commit: bool,
+    ) -> Result<()> {
+        self.ensure_writable("connect blocks")?;
+        for tx in &mut block.block_txs.txs {
+            if tx.time_first_seen == 0 {
+                if let Some(mempool_tx) = self.mempool.tx(&tx.txid) {
+                    tx.time_first_seen = mempool_tx.time_first_seen;
+                }
+            }
+        }
         let height = block.db_block.height;
         let mut batch = WriteBatch::default();
         let block_writer = BlockWriter::new(&self.db)?;
@@ -309,10 +680,15 @@ impl ChronikIndexer {
         script_history_writer.insert(&mut batch, &index_txs)?;
         script_utxo_writer.insert(&mut batch, &index_txs)?;
         spent_by_writer.insert(&mut batch, &index_txs)?;
-        self.db.write_batch(batch)?;
+        if commit {
+            self.db.write_batch(batch)?;
+        } else {
+            self.db.write_batch_deferred(batch)?;
+        }
         for tx in &block.block_txs.txs {
             self.mempool.remove_mined(&tx.txid)?;
         }
+        self.mempool.connect_block_conflicts(height, &block.txs);
         let subs = self.subs.get_mut();
         subs.broadcast_block_msg(BlockMsg {
             msg_type: BlockMsgType::Connected,
@@ -330,6 +706,20 @@ impl ChronikIndexer {
         &mut self,
         block: ChronikBlock,
     ) -> Result<()> {
+        self.ensure_writable("disconnect blocks")?;
+        // Disconnecting any other block than the tip would corrupt the index
+        let hash = &block.db_block.hash;
+        match BlockReader::new(&self.db)?.tip()? {
+            Some(tip) if &tip.hash == hash => {}
+            Some(tip) => {
+                return Err(DisconnectBlockNotTip {
+                    hash: hash.clone(),
+                    tip: tip.hash,
+                }
+                .into());
+            }
+            None => return Err(DisconnectBlockEmptyIndex(hash.clone()).into()),
+        }
         let mut batch = WriteBatch::default();
         let block_writer = BlockWriter::new(&self.db)?;
         let tx_writer = TxWriter::new(&self.db)?;
@@ -348,6 +738,8 @@ impl ChronikIndexer {
         script_utxo_writer.delete(&mut batch, &index_txs)?;
         spent_by_writer.delete(&mut batch, &index_txs)?;
         self.avalanche.disconnect_block(block.db_block.height)?;
+        MetadataWriter::new(&self.db)?
+            .update_avalanche_height(&mut batch, self.avalanche.height)?;
         self.db.write_batch(batch)?;
         let subs = self.subs.get_mut();
         subs.broadcast_block_msg(BlockMsg {
@@ -363,7 +755,12 @@ impl ChronikIndexer {
         &mut self,
         block: ChronikBlock,
     ) -> Result<()> {
+        self.ensure_writable("finalize blocks")?;
         self.avalanche.finalize_block(block.db_block.height)?;
+        let mut batch = WriteBatch::default();
+        MetadataWriter::new(&self.db)?
+            .update_avalanche_height(&mut batch, self.avalanche.height)?;
+        self.db.write_batch(batch)?;
         let subs = self.subs.get_mut();
         subs.broadcast_block_msg(BlockMsg {
             msg_type: BlockMsgType::Finalized,
@@ -376,6 +773,13 @@ impl ChronikIndexer {
         Ok(())
     }
 
+    fn ensure_writable(&self, action: &'static str) -> Result<()> {
+        if self.read_only {
+            return Err(ReadOnly(action).into());
+        }
+        Ok(())
+    }
+
     /// Return [`QueryBlocks`] to read blocks from the DB.
     pub fn blocks(&self) -> QueryBlocks<'_> {
         QueryBlocks {
@@ -385,6 +789,12 @@ impl ChronikIndexer {
         }
     }
 
+    /// Info of the `n` most recent blocks, tip first, see
+    /// [`QueryBlocks::recent`].
+    pub fn recent_blocks(&self, n: usize) -> Result<Vec<proto::BlockInfo>> {
+        Ok(self.blocks().recent(n)?.blocks)
+    }
+
     /// Return [`QueryTxs`] to return txs from mempool/DB.
     pub fn txs(&self) -> QueryTxs<'_> {
         QueryTxs {
@@ -394,6 +804,84 @@ impl ChronikIndexer {
         }
     }
 
+    /// Which tx spent the given output, and whether that tx is mined or in
+    /// the mempool, or [`None`] if it's unspent or unknown to the index. See
+    /// [`QueryTxs::spent_info`].
+    pub fn is_spent(&self, outpoint: &OutPoint) -> Result<Option<SpentInfo>> {
+        self.txs().spent_info(outpoint)
+    }
+
+    /// Number of txs and total size of the mempool.
+    pub fn mempool_info(&self) -> proto::MempoolInfo {
+        proto::MempoolInfo {
+            tx_count: self.mempool.num_txs() as u64,
+            total_size: self.mempool.total_size() as u64,
+        }
+    }
+
+    /// Approximate size of the DB and estimated number of keys in each of its
+    /// column families, see [`Db::stats`].
+    pub fn db_stats(&self) -> Result<DbStats> {
+        self.db.stats()
+    }
+
+    /// Internal counters for debugging, e.g. the progress of the last
+    /// resync. Shared, so they can be read and updated without holding a lock
+    /// on the indexer.
+    pub fn debug_counters(&self) -> &Arc<DebugCounters> {
+        &self.debug_counters
+    }
+
+    /// Flush and compact the DB, e.g. to reclaim disk space after a lot of
+    /// reorgs. Blocks indexing until done, which can take a long time.
+    pub fn compact(&mut self) -> Result<()> {
+        self.ensure_writable("compact the DB")?;
+        self.db.compact()
+    }
+
+    /// Handle to export the index, which doesn't borrow the indexer. This way,
+    /// a lock on the indexer can be released before starting the export,
+    /// instead of blocking indexing until it's done.
+    pub fn exporter(&self) -> IndexExporter {
+        IndexExporter {
+            db: Arc::clone(&self.db),
+        }
+    }
+
+    /// Import an index exported with [`IndexExporter::export`] from
+    /// `reader`, see [`import_db`]. The index must be empty, and the export
+    /// must be of the same schema version, network and script filter. If the
+    /// import fails, e.g. because the checksum doesn't match, the index is
+    /// left empty.
+    pub fn import(&mut self, reader: impl Read) -> Result<()> {
+        self.ensure_writable("import an index")?;
+        let result = import_db(&self.db, reader).and_then(|()| {
+            verify_schema_version(&self.db, false)?;
+            verify_network(&self.db, self.network, false)?;
+            verify_script_filter(
+                &self.db,
+                &self.script_filter_fingerprint,
+                false,
+            )
+        });
+        if let Err(err) = result {
+            // Don't keep an index we can't use, and restore the metadata of
+            // the now empty DB
+            clear_db(&self.db)?;
+            verify_schema_version(&self.db, false)?;
+            verify_network(&self.db, self.network, false)?;
+            verify_script_filter(
+                &self.db,
+                &self.script_filter_fingerprint,
+                false,
+            )?;
+            self.avalanche = Avalanche::default();
+            return Err(err);
+        }
+        self.avalanche = load_avalanche(&self.db)?;
+        Ok(())
+    }
+
     /// Return [`QueryGroupHistory`] for scripts to query the tx history of
     /// scripts.
     pub fn script_history(&self) -> Result<QueryGroupHistory<'_, ScriptGroup>> {
@@ -406,6 +894,15 @@ impl ChronikIndexer {
         })
     }
 
+    /// When the script was first and last active, and in how many txs, see
+    /// [`QueryGroupHistory::activity`].
+    pub fn script_activity(
+        &self,
+        script: &Script,
+    ) -> Result<Option<GroupActivity>> {
+        self.script_history()?.activity(script)
+    }
+
     /// Return [`QueryGroupUtxos`] for scripts to query the utxos of scripts.
     pub fn script_utxos(&self) -> Result<QueryGroupUtxos<'_, ScriptGroup>> {
         Ok(QueryGroupUtxos {
@@ -417,18 +914,68 @@ impl ChronikIndexer {
         })
     }
 
+    /// Scan the DB for all scripts whose serialized form starts with
+    /// `prefix`, yielding each serialized script with its number of
+    /// confirmed txs.
+    ///
+    /// This is an ordered range scan over the script history column, so only
+    /// the keys matching the prefix are read, not the entire column.
+    ///
+    /// Note that scripts are stored compressed (see [`FnCompressScript`]), so
+    /// `prefix` has to be given in compressed form too. For the node's
+    /// compression, scripts that don't match a compressible template are
+    /// stored as their size followed by the raw bytecode, so a raw prefix only
+    /// matches scripts of one specific size. Mempool txs are not included, and
+    /// OP_RETURN outputs are never indexed as script members.
+    pub fn scan_scripts_prefix<'a>(
+        &'a self,
+        prefix: &[u8],
+    ) -> Result<impl Iterator<Item = Result<(Vec<u8>, usize)>> + 'a> {
+        let reader = ScriptHistoryReader::new(&self.db)?;
+        let members = reader.members_with_prefix(prefix);
+        Ok(members.map(move |member_ser| {
+            let member_ser = member_ser?;
+            let (_, num_txs) = reader.member_num_pages_and_txs(&member_ser)?;
+            Ok((member_ser, num_txs))
+        }))
+    }
+
     /// Subscribers, behind read/write lock
     pub fn subs(&self) -> &RwLock<Subs> {
         &self.subs
     }
 
     /// Build the ChronikBlock from the CBlockIndex
+    ///
+    /// The returned block holds all txs of the block in memory; blocks can't
+    /// be indexed in chunks, as the DB writers need all txs of a block at
+    /// once (e.g. for txs spending outputs of the same block). To keep peak
+    /// memory usage low for large blocks, callers should drop the
+    /// [`ffi::CBlock`] before indexing the returned block, so the block
+    /// doesn't exist in memory twice.
     pub fn make_chronik_block(
         &self,
         block: &ffi::CBlock,
         bindex: &ffi::CBlockIndex,
     ) -> Result<ChronikBlock> {
         let block = ffi::bridge_block(block, bindex)?;
+        if self.verify_merkle_root {
+            let expected = Sha256d::from_le_bytes(block.merkle_root);
+            let txids = block
+                .txs
+                .iter()
+                .map(|tx| Sha256d(tx.tx.txid))
+                .collect::<Vec<_>>();
+            let actual = merkle_root(&txids);
+            if actual != expected {
+                return Err(MerkleRootMismatch {
+                    hash: BlockHash::from(block.hash),
+                    expected,
+                    actual,
+                }
+                .into());
+            }
+        }
         let db_block = DbBlock {
             hash: BlockHash::from(block.hash),
             prev_hash: BlockHash::from(block.prev_hash),
@@ -470,9 +1017,49 @@ impl ChronikIndexer {
             txs,
         })
     }
+
+    /// Load the block from the node's disk and convert it into a
+    /// [`ChronikBlock`], freeing the [`ffi::CBlock`] before returning.
+    fn load_chronik_block(
+        &self,
+        bridge: &ffi::ChronikBridge,
+        block_index: &ffi::CBlockIndex,
+    ) -> Result<ChronikBlock> {
+        let ffi_block = bridge.load_block(block_index)?;
+        let ffi_block = expect_unique_ptr("load_block", &ffi_block);
+        self.make_chronik_block(ffi_block, block_index)
+    }
 }
 
-fn verify_schema_version(db: &Db) -> Result<()> {
+/// Whether `path` is an existing directory we can create files in.
+///
+/// The permission bits alone can't tell, e.g. they ignore the owner of the
+/// directory, ACLs and read-only mounts, so this actually creates (and then
+/// removes) a file in it.
+fn is_writable_dir(path: &Path) -> bool {
+    if !path.is_dir() {
+        return false;
+    }
+    let probe_path =
+        path.join(format!(".chronik-write-probe-{}", std::process::id()));
+    let probe = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&probe_path);
+    match probe {
+        Ok(file) => {
+            drop(file);
+            let _ = std::fs::remove_file(&probe_path);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Verify the schema version of the DB matches this Chronik's. Sets it for an
+/// empty DB, unless `read_only` is set.
+fn verify_schema_version(db: &Db, read_only: bool) -> Result<()> {
     let metadata_reader = MetadataReader::new(db)?;
     let metadata_writer = MetadataWriter::new(db)?;
     let is_empty = db.is_db_empty()?;
@@ -493,6 +1080,9 @@ fn verify_schema_version(db: &Db) -> Result<()> {
             if !is_empty {
                 return Err(MissingSchemaVersion.into());
             }
+            if read_only {
+                return Ok(());
+            }
             let mut batch = WriteBatch::default();
             metadata_writer
                 .update_schema_version(&mut batch, CURRENT_INDEXER_VERSION)?;
@@ -503,50 +1093,278 @@ fn verify_schema_version(db: &Db) -> Result<()> {
     Ok(())
 }
 
+impl IndexExporter {
+    /// Export the whole index to `writer`, with a checksum at the end, see
+    /// [`export_db`]. Reads from a snapshot taken when the export starts, so
+    /// this is consistent even while indexing.
+    pub fn export(&self, writer: impl Write) -> Result<()> {
+        export_db(&self.db, writer)
+    }
+}
+
+/// Flushes the WAL of the DB when dropped, so blocks connected with deferred
+/// writes are committed even if resyncing fails.
+struct FlushWalGuard(Option<Arc<Db>>);
+
+impl FlushWalGuard {
+    /// Flush the WAL now, reporting failures to the caller.
+    fn flush(mut self) -> Result<()> {
+        match self.0.take() {
+            Some(db) => db.flush_wal(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for FlushWalGuard {
+    fn drop(&mut self) {
+        if let Some(db) = self.0.take() {
+            if let Err(err) = db.flush_wal() {
+                log!("Failed to flush Chronik's WAL: {err}\n");
+            }
+        }
+    }
+}
+
+/// Load the Avalanche state persisted in the DB, nothing is finalized for a
+/// DB without one.
+fn load_avalanche(db: &Db) -> Result<Avalanche> {
+    let height = MetadataReader::new(db)?.avalanche_height()?;
+    Ok(Avalanche {
+        height: height.unwrap_or(Avalanche::default().height),
+    })
+}
+
+/// Verify the DB isn't left behind by an import that has been interrupted.
+fn verify_import_finished(db: &Db) -> Result<()> {
+    if MetadataReader::new(db)?.is_import_in_progress()? {
+        return Err(UnfinishedImport.into());
+    }
+    Ok(())
+}
+
+/// Verify the DB has been built for the node's network. Sets it for a DB
+/// without a network, i.e. an empty DB or one built by an older version,
+/// unless `read_only` is set.
+fn verify_network(db: &Db, network: Network, read_only: bool) -> Result<()> {
+    match MetadataReader::new(db)?.network()? {
+        Some(db_network) if db_network != network => Err(WrongNetwork {
+            db: db_network,
+            node: network,
+        }
+        .into()),
+        Some(_) => Ok(()),
+        None if read_only => Ok(()),
+        None => {
+            let mut batch = WriteBatch::default();
+            MetadataWriter::new(db)?.update_network(&mut batch, network)?;
+            db.write_batch(batch)
+        }
+    }
+}
+
+/// Verify the DB has been built with the given script filter fingerprint
+/// (empty if unfiltered). Sets it for a DB without one, i.e. an empty DB or one
+/// built by an older version, unless `read_only` is set.
+fn verify_script_filter(
+    db: &Db,
+    fingerprint: &[u8],
+    read_only: bool,
+) -> Result<()> {
+    match MetadataReader::new(db)?.script_filter()? {
+        Some(db_fingerprint) if db_fingerprint != fingerprint => {
+            let name = |fingerprint: &[u8]| match fingerprint.is_empty() {
+                true => "none".to_string(),
+                false => hex::encode(fingerprint),
+            };
+            Err(WrongScriptFilter {
+                db: name(&db_fingerprint),
+                node: name(fingerprint),
+            }
+            .into())
+        }
+        Some(_) => Ok(()),
+        None if read_only => Ok(()),
+        None => {
+            let mut batch = WriteBatch::default();
+            MetadataWriter::new(db)?
+                .update_script_filter(&mut batch, fingerprint)?;
+            db.write_batch(batch)
+        }
+    }
+}
+
+impl ChronikIndexerParams {
+    fn script_group(&self) -> ScriptGroup {
+        let script_group = ScriptGroup::new(self.fn_compress_script.clone());
+        match &self.script_filter {
+            Some(script_filter) => {
+                script_group.with_filter(script_filter.fn_filter_script.clone())
+            }
+            None => script_group,
+        }
+    }
+
+    fn script_filter_fingerprint(&self) -> &[u8] {
+        match &self.script_filter {
+            Some(script_filter) => &script_filter.fingerprint,
+            None => &[],
+        }
+    }
+}
+
 impl std::fmt::Debug for ChronikIndexerParams {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ChronikIndexerParams")
             .field("datadir_net", &self.datadir_net)
+            .field("network", &self.network)
             .field("wipe_db", &self.wipe_db)
+            .field("read_only", &self.read_only)
             .field("fn_compress_script", &"..")
+            .field("resync_commit_interval", &self.resync_commit_interval)
+            .field("conflict_retention_blocks", &self.conflict_retention_blocks)
+            .field("verify_merkle_root", &self.verify_merkle_root)
+            .field("script_filter", &self.script_filter)
             .finish()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{cell::Cell, collections::BTreeSet, path::Path, sync::Arc};
+
     use abc_rust_error::Result;
-    use bitcoinsuite_core::block::BlockHash;
+    use bitcoinsuite_core::{
+        block::BlockHash,
+        network::Network,
+        script::Script,
+        ser::BitcoinSer,
+        tx::{Coin, OutPoint, SpentBy, Tx, TxId, TxInput, TxMut, TxOutput},
+    };
     use chronik_db::{
-        db::{Db, WriteBatch, CF_META},
-        groups::prefix_mock_compress,
-        io::{BlockReader, BlockTxs, DbBlock, MetadataReader, MetadataWriter},
+        db::{Db, WriteBatch, CF_BLK, CF_META},
+        export::ExportError,
+        groups::{filter_scripts, prefix_mock_compress, ScriptFilter},
+        io::{
+            BlockHeight, BlockReader, BlockTxs, DbBlock, MetadataReader,
+            MetadataWriter, TxEntry, FIELD_NETWORK,
+        },
+        mem::MempoolTx,
     };
+    use chronik_proto::proto;
     use pretty_assertions::assert_eq;
 
-    use crate::indexer::{
-        ChronikBlock, ChronikIndexer, ChronikIndexerError,
-        ChronikIndexerParams, CURRENT_INDEXER_VERSION,
+    use crate::{
+        indexer::{
+            ChronikBlock, ChronikIndexer, ChronikIndexerError,
+            ChronikIndexerParams, CURRENT_INDEXER_VERSION,
+            DEFAULT_CONFLICT_RETENTION_BLOCKS, DEFAULT_RESYNC_COMMIT_INTERVAL,
+        },
+        query::{GroupActivity, SpentInfo},
     };
 
+    fn test_params(datadir_net: &Path) -> ChronikIndexerParams {
+        ChronikIndexerParams {
+            datadir_net: datadir_net.to_path_buf(),
+            network: Network::Regtest,
+            wipe_db: false,
+            read_only: false,
+            fn_compress_script: Arc::new(prefix_mock_compress),
+            resync_commit_interval: DEFAULT_RESYNC_COMMIT_INTERVAL,
+            conflict_retention_blocks: DEFAULT_CONFLICT_RETENTION_BLOCKS,
+            verify_merkle_root: true,
+            script_filter: None,
+        }
+    }
+
+    /// Tx with txid `[txid_num; 32]`, spending the `(txid_num, out_idx)`
+    /// outputs in `prev_outs`.
+    fn make_tx(
+        txid_num: u8,
+        prev_outs: &[(u8, u32)],
+        outputs: Vec<TxOutput>,
+    ) -> Tx {
+        Tx::with_txid(
+            TxId::from([txid_num; 32]),
+            TxMut {
+                version: 1,
+                inputs: prev_outs
+                    .iter()
+                    .map(|&(prev_txid_num, out_idx)| TxInput {
+                        prev_out: OutPoint {
+                            txid: TxId::from([prev_txid_num; 32]),
+                            out_idx,
+                        },
+                        ..Default::default()
+                    })
+                    .collect(),
+                outputs,
+                locktime: 0,
+            },
+        )
+    }
+
+    /// Block at `height` with hash `[height + 1; 32]`, so blocks made for
+    /// consecutive heights form a chain. The first of `txs` is the coinbase.
+    fn make_block(height: BlockHeight, txs: Vec<Tx>) -> ChronikBlock {
+        ChronikBlock {
+            db_block: DbBlock {
+                hash: BlockHash::from([height as u8 + 1; 32]),
+                prev_hash: BlockHash::from([height as u8; 32]),
+                height,
+                n_bits: 0x1deadbef,
+                timestamp: 1234567890,
+                file_num: 0,
+                data_pos: 1337,
+            },
+            block_txs: BlockTxs {
+                block_height: height,
+                txs: txs
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, tx)| TxEntry {
+                        txid: tx.txid(),
+                        is_coinbase: idx == 0,
+                        ..Default::default()
+                    })
+                    .collect(),
+            },
+            size: 285,
+            txs,
+        }
+    }
+
     #[test]
     fn test_indexer() -> Result<()> {
         let tempdir = tempdir::TempDir::new("chronik-indexer--indexer")?;
         let datadir_net = tempdir.path().join("regtest");
-        let params = ChronikIndexerParams {
-            datadir_net: datadir_net.clone(),
-            wipe_db: false,
-            fn_compress_script: prefix_mock_compress,
-        };
+        let params = test_params(&datadir_net);
         // regtest folder doesn't exist yet -> error
         assert_eq!(
             ChronikIndexer::setup(params.clone())
                 .unwrap_err()
                 .downcast::<ChronikIndexerError>()?,
-            ChronikIndexerError::CreateIndexesDirFailed(
-                datadir_net.join("indexes"),
-            ),
+            ChronikIndexerError::DatadirNotWritable(datadir_net.clone()),
+        );
+        // Also fails read-only, with a different error
+        assert_eq!(
+            ChronikIndexer::setup(ChronikIndexerParams {
+                read_only: true,
+                ..params.clone()
+            })
+            .unwrap_err()
+            .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::DatadirNotFound(datadir_net.clone()),
+        );
+        // A file isn't a valid datadir either
+        std::fs::write(&datadir_net, b"")?;
+        assert_eq!(
+            ChronikIndexer::setup(params.clone())
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::DatadirNotWritable(datadir_net.clone()),
         );
+        std::fs::remove_file(&datadir_net)?;
 
         // create regtest folder, setup will work now
         std::fs::create_dir(&datadir_net)?;
@@ -557,23 +1375,7 @@ mod tests {
 
         // DB is empty
         assert_eq!(BlockReader::new(&indexer.db)?.by_height(0)?, None);
-        let block = ChronikBlock {
-            db_block: DbBlock {
-                hash: BlockHash::from([4; 32]),
-                prev_hash: BlockHash::from([0; 32]),
-                height: 0,
-                n_bits: 0x1deadbef,
-                timestamp: 1234567890,
-                file_num: 0,
-                data_pos: 1337,
-            },
-            block_txs: BlockTxs {
-                block_height: 0,
-                txs: vec![],
-            },
-            size: 285,
-            txs: vec![],
-        };
+        let block = make_block(0, vec![]);
 
         // Add block
         indexer.handle_block_connected(block.clone())?;
@@ -599,35 +1401,686 @@ mod tests {
     }
 
     #[test]
-    fn test_schema_version() -> Result<()> {
-        let dir = tempdir::TempDir::new("chronik-indexer--schema_version")?;
-        let chronik_path = dir.path().join("indexes").join("chronik");
+    fn test_read_only() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--read_only")?;
         let params = ChronikIndexerParams {
-            datadir_net: dir.path().to_path_buf(),
-            wipe_db: false,
-            fn_compress_script: prefix_mock_compress,
+            read_only: true,
+            ..test_params(tempdir.path())
         };
+        // DB has to exist already
+        assert!(ChronikIndexer::setup(params.clone()).is_err());
 
-        // Setting up DB first time sets the schema version
-        ChronikIndexer::setup(params.clone())?;
-        {
-            let db = Db::open(&chronik_path)?;
-            assert_eq!(
-                MetadataReader::new(&db)?.schema_version()?,
-                Some(CURRENT_INDEXER_VERSION)
-            );
-        }
-        // Opening DB again works fine
-        ChronikIndexer::setup(params.clone())?;
+        let block = make_block(0, vec![]);
+        let mut indexer = ChronikIndexer::setup(ChronikIndexerParams {
+            read_only: false,
+            ..params.clone()
+        })?;
+        indexer.handle_block_connected(block.clone())?;
+        std::mem::drop(indexer);
 
-        // Override DB schema version to 0
-        {
-            let db = Db::open(&chronik_path)?;
-            let mut batch = WriteBatch::default();
-            MetadataWriter::new(&db)?.update_schema_version(&mut batch, 0)?;
-            db.write_batch(batch)?;
-        }
-        // -> DB too old
+        // Read-only indexer can read the DB, but not modify it
+        let mut indexer = ChronikIndexer::setup(params.clone())?;
+        assert_eq!(
+            BlockReader::new(&indexer.db)?.by_height(0)?,
+            Some(block.db_block.clone()),
+        );
+        assert_eq!(
+            indexer
+                .handle_block_disconnected(block)
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::ReadOnly("disconnect blocks"),
+        );
+        assert_eq!(
+            indexer
+                .handle_tx_removed_from_mempool(TxId::from([1; 32]))
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::ReadOnly("remove txs from the mempool"),
+        );
+        std::mem::drop(indexer);
+
+        // Can't wipe a read-only DB
+        assert_eq!(
+            ChronikIndexer::setup(ChronikIndexerParams {
+                wipe_db: true,
+                ..params
+            })
+            .unwrap_err()
+            .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::ReadOnly("wipe the DB"),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_db_stats_compact() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--db_stats")?;
+        let params = test_params(tempdir.path());
+        let mut indexer = ChronikIndexer::setup(params.clone())?;
+        let block = make_block(0, vec![]);
+        indexer.handle_block_connected(block)?;
+        indexer.compact()?;
+
+        let stats = indexer.db_stats()?;
+        assert!(stats.approx_size_bytes > 0);
+        assert!(stats.num_keys_per_column.contains_key(CF_META));
+        assert!(stats.num_keys_per_column[CF_BLK] > 0);
+        assert!(stats.size_bytes_per_column[CF_BLK] > 0);
+        assert_eq!(
+            stats.size_bytes_per_column.values().sum::<u64>(),
+            stats.approx_size_bytes,
+        );
+        std::mem::drop(indexer);
+
+        // Read-only indexer has stats, but can't compact
+        let mut indexer = ChronikIndexer::setup(ChronikIndexerParams {
+            read_only: true,
+            ..params
+        })?;
+        assert!(indexer.db_stats()?.num_keys_per_column[CF_BLK] > 0);
+        assert_eq!(
+            indexer
+                .compact()
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::ReadOnly("compact the DB"),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_import() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--export")?;
+        let params = |name: &str| test_params(&tempdir.path().join(name));
+        let mut indexer = ChronikIndexer::setup(params("source"))?;
+        // Exporter doesn't borrow the indexer, so it can keep indexing
+        let exporter = indexer.exporter();
+        let block = make_block(0, vec![]);
+        indexer.handle_block_connected(block.clone())?;
+        let mut export = Vec::new();
+        exporter.export(&mut export)?;
+
+        // Corrupted export is rejected, and leaves the index empty
+        let mut replica = ChronikIndexer::setup(params("replica"))?;
+        let mut corrupted = export.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            replica
+                .import(corrupted.as_slice())
+                .unwrap_err()
+                .downcast::<ExportError>()?,
+            ExportError::ChecksumMismatch { .. },
+        ));
+        assert_eq!(BlockReader::new(&replica.db)?.by_height(0)?, None);
+
+        replica.import(export.as_slice())?;
+        assert_eq!(
+            BlockReader::new(&replica.db)?.by_height(0)?,
+            Some(block.db_block),
+        );
+        std::mem::drop(replica);
+
+        // Imported index has the right schema version and can be reopened
+        let replica = ChronikIndexer::setup(params("replica"))?;
+        assert_eq!(
+            BlockReader::new(&replica.db)?.tip()?,
+            BlockReader::new(&indexer.db)?.tip()?,
+        );
+        std::mem::drop(replica);
+
+        // Index left behind by an interrupted import is rejected
+        {
+            let db_path = tempdir.path().join("replica").join("indexes");
+            let db = Db::open(db_path.join("chronik"))?;
+            let mut batch = WriteBatch::default();
+            MetadataWriter::new(&db)?
+                .update_import_in_progress(&mut batch, true)?;
+            db.write_batch(batch)?;
+        }
+        assert_eq!(
+            ChronikIndexer::setup(params("replica"))
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::UnfinishedImport,
+        );
+        assert_eq!(
+            ChronikIndexer::validate_setup(&params("replica"))
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::UnfinishedImport,
+        );
+        ChronikIndexer::setup(ChronikIndexerParams {
+            wipe_db: true,
+            ..params("replica")
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resync_commit_interval() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--resync")?;
+        let mut indexer = ChronikIndexer::setup(ChronikIndexerParams {
+            resync_commit_interval: 3,
+            ..test_params(tempdir.path())
+        })?;
+
+        // Only every 3rd block is committed, and the rest at the end
+        let mut num_unflushed = Vec::new();
+        let is_complete = indexer.resync_blocks(
+            -1,
+            7,
+            |indexer, height| {
+                num_unflushed.push(indexer.db.num_unflushed_batches());
+                Ok(make_block(height, vec![]))
+            },
+            || false,
+        )?;
+        assert!(is_complete);
+        assert_eq!(num_unflushed, vec![0, 0, 1, 2, 0, 1, 2, 0]);
+        assert_eq!(indexer.db.num_unflushed_batches(), 0);
+
+        // Failing to load a block still commits the blocks connected so far
+        let result = indexer.resync_blocks(
+            7,
+            12,
+            |_, height| match height {
+                11 => Err(ChronikIndexerError::CannotRewindChronik(
+                    BlockHash::default(),
+                )
+                .into()),
+                _ => Ok(make_block(height, vec![])),
+            },
+            || false,
+        );
+        assert_eq!(
+            result.unwrap_err().downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::CannotRewindChronik(BlockHash::default()),
+        );
+        assert_eq!(indexer.db.num_unflushed_batches(), 0);
+        assert_eq!(BlockReader::new(&indexer.db)?.tip()?.unwrap().height, 10);
+
+        // Stopping because of a shutdown commits the blocks, too
+        let num_checks = Cell::new(0);
+        let is_complete = indexer.resync_blocks(
+            10,
+            20,
+            |_, height| Ok(make_block(height, vec![])),
+            || {
+                num_checks.set(num_checks.get() + 1);
+                num_checks.get() > 3
+            },
+        )?;
+        assert!(!is_complete);
+        assert_eq!(indexer.db.num_unflushed_batches(), 0);
+        assert_eq!(BlockReader::new(&indexer.db)?.tip()?.unwrap().height, 13);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disconnect_non_tip() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--disconnect")?;
+        let mut indexer = ChronikIndexer::setup(test_params(tempdir.path()))?;
+        let block0 = make_block(0, vec![]);
+        let block1 = make_block(1, vec![]);
+
+        // Disconnecting from an empty index fails
+        assert_eq!(
+            indexer
+                .handle_block_disconnected(block0.clone())
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::DisconnectBlockEmptyIndex(
+                block0.db_block.hash.clone(),
+            ),
+        );
+
+        indexer.handle_block_connected(block0.clone())?;
+        indexer.handle_block_connected(block1.clone())?;
+
+        // Disconnecting a block below the tip fails and leaves the index as is
+        assert_eq!(
+            indexer
+                .handle_block_disconnected(block0.clone())
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::DisconnectBlockNotTip {
+                hash: block0.db_block.hash.clone(),
+                tip: block1.db_block.hash.clone(),
+            },
+        );
+        let block_reader = BlockReader::new(&indexer.db)?;
+        assert_eq!(block_reader.by_height(0)?, Some(block0.db_block.clone()));
+        assert_eq!(block_reader.tip()?, Some(block1.db_block.clone()));
+
+        // Disconnecting in order works
+        indexer.handle_block_disconnected(block1)?;
+        indexer.handle_block_disconnected(block0)?;
+        assert_eq!(BlockReader::new(&indexer.db)?.tip()?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_avalanche_persisted() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--avalanche")?;
+        let params = test_params(tempdir.path());
+        let mut indexer = ChronikIndexer::setup(params.clone())?;
+        let block0 = make_block(0, vec![]);
+        let block1 = make_block(1, vec![]);
+        indexer.handle_block_connected(block0.clone())?;
+        indexer.handle_block_connected(block1.clone())?;
+        indexer.handle_block_finalized(block1.clone())?;
+        std::mem::drop(indexer);
+
+        // Finalized height survives a restart
+        let mut indexer = ChronikIndexer::setup(params.clone())?;
+        assert_eq!(indexer.avalanche.height, 1);
+
+        // Disconnecting the finalized block lowers the persisted height
+        indexer.handle_block_disconnected(block1)?;
+        std::mem::drop(indexer);
+        let indexer = ChronikIndexer::setup(params)?;
+        assert_eq!(indexer.avalanche.height, 0);
+        assert!(indexer.avalanche.is_final_height(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recent_blocks() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--recent")?;
+        let mut indexer = ChronikIndexer::setup(test_params(tempdir.path()))?;
+        assert_eq!(indexer.recent_blocks(10)?, vec![]);
+        for height in 0..3 {
+            indexer.handle_block_connected(ChronikBlock {
+                db_block: DbBlock {
+                    hash: BlockHash::from([height as u8 + 1; 32]),
+                    prev_hash: BlockHash::from([height as u8; 32]),
+                    height,
+                    ..Default::default()
+                },
+                block_txs: BlockTxs {
+                    block_height: height,
+                    txs: vec![],
+                },
+                ..Default::default()
+            })?;
+        }
+        let heights = |n: usize| -> Result<Vec<BlockHeight>> {
+            Ok(indexer
+                .recent_blocks(n)?
+                .into_iter()
+                .map(|block| block.height)
+                .collect())
+        };
+        assert_eq!(heights(0)?, vec![]);
+        assert_eq!(heights(2)?, vec![2, 1]);
+        assert_eq!(heights(10)?, vec![2, 1, 0]);
+        assert!(indexer.recent_blocks(501).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_mempool_tx() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--duplicate")?;
+        let mut indexer = ChronikIndexer::setup(test_params(tempdir.path()))?;
+        let txid = TxId::from([1; 32]);
+        let make_mempool_tx = || MempoolTx {
+            tx: Tx::with_txid(
+                txid,
+                TxMut {
+                    version: 1,
+                    inputs: vec![TxInput {
+                        prev_out: OutPoint {
+                            txid: TxId::from([2; 32]),
+                            out_idx: 0,
+                        },
+                        coin: Some(Coin::default()),
+                        ..Default::default()
+                    }],
+                    outputs: vec![TxOutput::default()],
+                    locktime: 0,
+                },
+            ),
+            time_first_seen: 1234,
+        };
+        let tx_size = make_mempool_tx().tx.ser_len() as u64;
+
+        indexer.handle_tx_added_to_mempool(make_mempool_tx())?;
+        assert_eq!(
+            indexer.mempool_info(),
+            proto::MempoolInfo {
+                tx_count: 1,
+                total_size: tx_size,
+            },
+        );
+
+        // Adding the same tx again is skipped and leaves the mempool untouched
+        indexer.handle_tx_added_to_mempool(make_mempool_tx())?;
+        assert_eq!(
+            indexer.mempool_info(),
+            proto::MempoolInfo {
+                tx_count: 1,
+                total_size: tx_size,
+            },
+        );
+        assert_eq!(indexer.mempool.tx(&txid), Some(&make_mempool_tx()));
+        assert_eq!(indexer.txs().mempool_time_first_seen(&txid), Some(1234));
+
+        // Removing it once leaves the mempool empty
+        indexer.handle_tx_removed_from_mempool(txid)?;
+        assert_eq!(indexer.txs().mempool_time_first_seen(&txid), None);
+        assert_eq!(
+            indexer.mempool_info(),
+            proto::MempoolInfo {
+                tx_count: 0,
+                total_size: 0,
+            },
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_conflicting_mempool_tx() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--conflict")?;
+        let mut indexer = ChronikIndexer::setup(test_params(tempdir.path()))?;
+        let make_mempool_tx = |txid: TxId, out_idx: u32| MempoolTx {
+            tx: Tx::with_txid(
+                txid,
+                TxMut {
+                    version: 1,
+                    inputs: vec![TxInput {
+                        prev_out: OutPoint {
+                            txid: TxId::from([9; 32]),
+                            out_idx,
+                        },
+                        coin: Some(Coin::default()),
+                        ..Default::default()
+                    }],
+                    outputs: vec![TxOutput::default()],
+                    locktime: 0,
+                },
+            ),
+            time_first_seen: 1234,
+        };
+        let txid1 = TxId::from([1; 32]);
+        let txid2 = TxId::from([2; 32]);
+        let txid3 = TxId::from([3; 32]);
+        indexer.handle_tx_added_to_mempool(make_mempool_tx(txid1, 0))?;
+        indexer.handle_tx_added_to_mempool(make_mempool_tx(txid2, 1))?;
+
+        // Tx 3 double-spends tx 1, which means the mempool is out of sync
+        // with the node; it's not indexed but the conflict is known
+        indexer.handle_tx_added_to_mempool(make_mempool_tx(txid3, 0))?;
+        assert_eq!(indexer.mempool.num_txs(), 2);
+        assert_eq!(indexer.mempool.tx(&txid3), None);
+        let conflicts = indexer.mempool.conflicts();
+        assert_eq!(conflicts.conflicts(&txid1), Some(&BTreeSet::from([txid3])));
+        assert_eq!(conflicts.conflicts(&txid2), None);
+        assert_eq!(conflicts.conflicts(&txid3), Some(&BTreeSet::from([txid1])));
+
+        // Removing the conflicting tx is fine, even though it's not indexed
+        indexer.handle_tx_removed_from_mempool(txid3)?;
+        indexer.handle_tx_removed_from_mempool(txid1)?;
+        assert_eq!(indexer.mempool.num_txs(), 1);
+
+        // Resolved conflicts are still kept for a while
+        let conflicts = indexer.mempool.conflicts();
+        assert_eq!(conflicts.conflicts(&txid1), Some(&BTreeSet::from([txid3])));
+        assert_eq!(conflicts.conflicts(&txid3), Some(&BTreeSet::from([txid1])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_spent() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--is-spent")?;
+        let mut indexer = ChronikIndexer::setup(test_params(tempdir.path()))?;
+        // All txs have two outputs
+        let spend_tx = |txid_num: u8, prev_outs: &[(u8, u32)]| {
+            make_tx(txid_num, prev_outs, vec![TxOutput::default(); 2])
+        };
+        let outpoint = |txid_num: u8, out_idx: u32| OutPoint {
+            txid: TxId::from([txid_num; 32]),
+            out_idx,
+        };
+        let spent_info = |txid_num: u8, input_idx: u32, is_mempool: bool| {
+            Some(SpentInfo {
+                spent_by: SpentBy {
+                    txid: TxId::from([txid_num; 32]),
+                    input_idx,
+                },
+                is_mempool,
+            })
+        };
+
+        // Coinbase tx 1 in block 0, tx 3 in block 1 spends its output 0, and
+        // output 0 of coinbase tx 2 of the same block
+        let block0 = make_block(0, vec![spend_tx(1, &[(0, 0)])]);
+        let block1 = make_block(
+            1,
+            vec![spend_tx(2, &[(0, 0)]), spend_tx(3, &[(2, 0), (1, 0)])],
+        );
+        indexer.handle_block_connected(block0)?;
+        assert_eq!(indexer.is_spent(&outpoint(1, 0))?, None);
+        indexer.handle_block_connected(block1.clone())?;
+        assert_eq!(indexer.is_spent(&outpoint(1, 0))?, spent_info(3, 1, false));
+        assert_eq!(indexer.is_spent(&outpoint(1, 1))?, None);
+        assert_eq!(indexer.is_spent(&outpoint(2, 0))?, spent_info(3, 0, false));
+        // Coinbase txs don't spend anything
+        assert_eq!(indexer.is_spent(&outpoint(0, 0))?, None);
+        // Unknown tx
+        assert_eq!(indexer.is_spent(&outpoint(9, 9))?, None);
+
+        // Mempool tx 4 spends output 1 of tx 1 and output 0 of tx 3
+        indexer.handle_tx_added_to_mempool(MempoolTx {
+            tx: spend_tx(4, &[(1, 1), (3, 0)]),
+            time_first_seen: 1234,
+        })?;
+        assert_eq!(indexer.is_spent(&outpoint(1, 0))?, spent_info(3, 1, false));
+        assert_eq!(indexer.is_spent(&outpoint(1, 1))?, spent_info(4, 0, true));
+        assert_eq!(indexer.is_spent(&outpoint(3, 0))?, spent_info(4, 1, true));
+        assert_eq!(indexer.is_spent(&outpoint(4, 0))?, None);
+
+        // Disconnecting block 1 (after the node evicted tx 4) rolls back the
+        // spent marker of output 0 of tx 1
+        indexer.handle_tx_removed_from_mempool(TxId::from([4; 32]))?;
+        assert_eq!(indexer.is_spent(&outpoint(1, 1))?, None);
+        indexer.handle_block_disconnected(block1.clone())?;
+        assert_eq!(indexer.is_spent(&outpoint(1, 0))?, None);
+        assert_eq!(indexer.is_spent(&outpoint(1, 1))?, None);
+
+        // Reconnecting it marks the output spent again
+        indexer.handle_block_connected(block1)?;
+        assert_eq!(indexer.is_spent(&outpoint(1, 0))?, spent_info(3, 1, false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tx_block() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--tx-block")?;
+        let mut indexer = ChronikIndexer::setup(test_params(tempdir.path()))?;
+        // Each tx spends a different output of a tx that isn't indexed
+        let independent_tx = |txid_num: u8| {
+            make_tx(
+                txid_num,
+                &[(0xff, txid_num.into())],
+                vec![TxOutput::default()],
+            )
+        };
+
+        let block0 = make_block(0, vec![independent_tx(1)]);
+        let block1 = make_block(1, vec![independent_tx(2), independent_tx(3)]);
+        indexer.handle_block_connected(block0)?;
+        indexer.handle_block_connected(block1.clone())?;
+        let txs = indexer.txs();
+        assert_eq!(
+            txs.tx_block(&TxId::from([1; 32]))?,
+            Some((BlockHash::from([1; 32]), 0)),
+        );
+        assert_eq!(
+            txs.tx_block(&TxId::from([3; 32]))?,
+            Some((BlockHash::from([2; 32]), 1)),
+        );
+        // Unknown tx
+        assert_eq!(txs.tx_block(&TxId::from([9; 32]))?, None);
+
+        // Mempool tx has no block
+        indexer.handle_tx_added_to_mempool(MempoolTx {
+            tx: independent_tx(4),
+            time_first_seen: 1234,
+        })?;
+        assert_eq!(indexer.txs().tx_block(&TxId::from([4; 32]))?, None);
+
+        // Disconnected block's txs have no block anymore
+        indexer.handle_tx_removed_from_mempool(TxId::from([4; 32]))?;
+        indexer.handle_block_disconnected(block1)?;
+        assert_eq!(indexer.txs().tx_block(&TxId::from([3; 32]))?, None);
+        assert_eq!(
+            indexer.txs().tx_block(&TxId::from([1; 32]))?,
+            Some((BlockHash::from([1; 32]), 0)),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_script_activity() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--activity")?;
+        let mut indexer = ChronikIndexer::setup(test_params(tempdir.path()))?;
+        let script1 = Script::new(vec![0x51].into());
+        let script2 = Script::new(vec![0x52].into());
+        // Each tx has one output paying to `script`
+        let pay_tx = |txid_num: u8, script: &Script| {
+            make_tx(
+                txid_num,
+                &[(0, 0)],
+                vec![TxOutput {
+                    script: script.clone(),
+                    ..Default::default()
+                }],
+            )
+        };
+        assert_eq!(indexer.script_activity(&script1)?, None);
+
+        // Only in the mempool: no heights yet
+        indexer.handle_tx_added_to_mempool(MempoolTx {
+            tx: pay_tx(9, &script2),
+            time_first_seen: 1234,
+        })?;
+        assert_eq!(
+            indexer.script_activity(&script2)?,
+            Some(GroupActivity {
+                first_height: None,
+                last_height: None,
+                tx_count: 1,
+            }),
+        );
+
+        indexer
+            .handle_block_connected(make_block(0, vec![pay_tx(1, &script1)]))?;
+        indexer
+            .handle_block_connected(make_block(1, vec![pay_tx(2, &script2)]))?;
+        indexer
+            .handle_block_connected(make_block(2, vec![pay_tx(3, &script1)]))?;
+        assert_eq!(
+            indexer.script_activity(&script1)?,
+            Some(GroupActivity {
+                first_height: Some(0),
+                last_height: Some(2),
+                tx_count: 2,
+            }),
+        );
+        assert_eq!(
+            indexer.script_activity(&script2)?,
+            Some(GroupActivity {
+                first_height: Some(1),
+                last_height: Some(1),
+                tx_count: 2,
+            }),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_setup() -> Result<()> {
+        let tempdir = tempdir::TempDir::new("chronik-indexer--validate")?;
+        let datadir_net = tempdir.path().join("regtest");
+        let params = test_params(&datadir_net);
+        // regtest folder doesn't exist yet
+        assert_eq!(
+            ChronikIndexer::validate_setup(&params)
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::PathNotWritable(datadir_net.clone()),
+        );
+
+        // Validating doesn't create anything
+        std::fs::create_dir(&datadir_net)?;
+        ChronikIndexer::validate_setup(&params)?;
+        assert!(!datadir_net.join("indexes").exists());
+
+        // Existing DB is opened read-only and checked
+        std::mem::drop(ChronikIndexer::setup(params.clone())?);
+        ChronikIndexer::validate_setup(&params)?;
+        {
+            let db = Db::open(datadir_net.join("indexes").join("chronik"))?;
+            let mut batch = WriteBatch::default();
+            MetadataWriter::new(&db)?.update_schema_version(
+                &mut batch,
+                CURRENT_INDEXER_VERSION + 1,
+            )?;
+            db.write_batch(batch)?;
+        }
+        assert_eq!(
+            ChronikIndexer::validate_setup(&params)
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::ChronikOutdated(CURRENT_INDEXER_VERSION + 1),
+        );
+        // DB would be wiped, so its version doesn't matter
+        ChronikIndexer::validate_setup(&ChronikIndexerParams {
+            wipe_db: true,
+            ..params
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_version() -> Result<()> {
+        let dir = tempdir::TempDir::new("chronik-indexer--schema_version")?;
+        let chronik_path = dir.path().join("indexes").join("chronik");
+        let params = test_params(dir.path());
+
+        // Setting up DB first time sets the schema version
+        ChronikIndexer::setup(params.clone())?;
+        {
+            let db = Db::open(&chronik_path)?;
+            assert_eq!(
+                MetadataReader::new(&db)?.schema_version()?,
+                Some(CURRENT_INDEXER_VERSION)
+            );
+        }
+        // Opening DB again works fine
+        ChronikIndexer::setup(params.clone())?;
+
+        // Override DB schema version to 0
+        {
+            let db = Db::open(&chronik_path)?;
+            let mut batch = WriteBatch::default();
+            MetadataWriter::new(&db)?.update_schema_version(&mut batch, 0)?;
+            db.write_batch(batch)?;
+        }
+        // -> DB too old
         assert_eq!(
             ChronikIndexer::setup(params.clone())
                 .unwrap_err()
@@ -699,4 +2152,178 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_network() -> Result<()> {
+        let dir = tempdir::TempDir::new("chronik-indexer--network")?;
+        let chronik_path = dir.path().join("indexes").join("chronik");
+        let params = |network: Network| ChronikIndexerParams {
+            network,
+            ..test_params(dir.path())
+        };
+        let wrong_network = ChronikIndexerError::WrongNetwork {
+            db: Network::Regtest,
+            node: Network::Mainnet,
+        };
+
+        // Setting up DB first time sets the network
+        ChronikIndexer::setup(params(Network::Regtest))?;
+        {
+            let db = Db::open(&chronik_path)?;
+            let network = MetadataReader::new(&db)?.network()?;
+            assert_eq!(network, Some(Network::Regtest));
+        }
+        ChronikIndexer::setup(params(Network::Regtest))?;
+
+        // Opening it for another network fails, also read-only
+        assert_eq!(
+            ChronikIndexer::setup(params(Network::Mainnet))
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            wrong_network,
+        );
+        assert_eq!(
+            ChronikIndexer::setup(ChronikIndexerParams {
+                read_only: true,
+                ..params(Network::Mainnet)
+            })
+            .unwrap_err()
+            .downcast::<ChronikIndexerError>()?,
+            wrong_network,
+        );
+        assert_eq!(
+            ChronikIndexer::validate_setup(&params(Network::Mainnet))
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            wrong_network,
+        );
+
+        // DB without network (built by an older version) gets it set
+        {
+            let db = Db::open(&chronik_path)?;
+            let mut batch = WriteBatch::default();
+            batch.delete_cf(db.cf(CF_META)?, FIELD_NETWORK);
+            db.write_batch(batch)?;
+        }
+        ChronikIndexer::setup(params(Network::Mainnet))?;
+        {
+            let db = Db::open(&chronik_path)?;
+            let network = MetadataReader::new(&db)?.network()?;
+            assert_eq!(network, Some(Network::Mainnet));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_script_filter() -> Result<()> {
+        let dir = tempdir::TempDir::new("chronik-indexer--script_filter")?;
+        let chronik_path = dir.path().join("indexes").join("chronik");
+        let script1 = Script::new(vec![0x51].into());
+        let script2 = Script::new(vec![0x52].into());
+        let filter1 = filter_scripts([script1.clone()]);
+        let filter2 = filter_scripts([script2.clone()]);
+        let params =
+            |script_filter: Option<ScriptFilter>| ChronikIndexerParams {
+                script_filter,
+                ..test_params(dir.path())
+            };
+
+        // Only the history of scripts matching the filter is indexed
+        let mut indexer = ChronikIndexer::setup(params(Some(filter1.clone())))?;
+        let tx = Tx::with_txid(
+            TxId::from([1; 32]),
+            TxMut {
+                version: 1,
+                inputs: vec![TxInput::default()],
+                outputs: [&script1, &script2]
+                    .into_iter()
+                    .map(|script| TxOutput {
+                        script: script.clone(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                locktime: 0,
+            },
+        );
+        indexer.handle_block_connected(ChronikBlock {
+            db_block: DbBlock {
+                hash: BlockHash::from([1; 32]),
+                ..Default::default()
+            },
+            block_txs: BlockTxs {
+                block_height: 0,
+                txs: vec![TxEntry {
+                    txid: tx.txid(),
+                    is_coinbase: true,
+                    ..Default::default()
+                }],
+            },
+            txs: vec![tx],
+            ..Default::default()
+        })?;
+        assert_eq!(
+            indexer.script_activity(&script1)?,
+            Some(GroupActivity {
+                first_height: Some(0),
+                last_height: Some(0),
+                tx_count: 1,
+            }),
+        );
+        assert_eq!(indexer.script_activity(&script2)?, None);
+        drop(indexer);
+
+        // Setting up the DB stored the fingerprint of the filter
+        {
+            let db = Db::open(&chronik_path)?;
+            let fingerprint = MetadataReader::new(&db)?.script_filter()?;
+            assert_eq!(fingerprint, Some(filter1.fingerprint.clone()));
+        }
+        ChronikIndexer::setup(params(Some(filter1.clone())))?;
+
+        // Opening it with another filter or without a filter fails
+        let wrong_filter =
+            |node: String| ChronikIndexerError::WrongScriptFilter {
+                db: hex::encode(&filter1.fingerprint),
+                node,
+            };
+        assert_eq!(
+            ChronikIndexer::setup(params(Some(filter2.clone())))
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            wrong_filter(hex::encode(&filter2.fingerprint)),
+        );
+        assert_eq!(
+            ChronikIndexer::setup(ChronikIndexerParams {
+                read_only: true,
+                ..params(None)
+            })
+            .unwrap_err()
+            .downcast::<ChronikIndexerError>()?,
+            wrong_filter("none".to_string()),
+        );
+        assert_eq!(
+            ChronikIndexer::validate_setup(&params(None))
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            wrong_filter("none".to_string()),
+        );
+
+        // Wiping the DB allows changing the filter
+        ChronikIndexer::setup(ChronikIndexerParams {
+            wipe_db: true,
+            ..params(None)
+        })?;
+        assert_eq!(
+            ChronikIndexer::setup(params(Some(filter1.clone())))
+                .unwrap_err()
+                .downcast::<ChronikIndexerError>()?,
+            ChronikIndexerError::WrongScriptFilter {
+                db: "none".to_string(),
+                node: hex::encode(&filter1.fingerprint),
+            },
+        );
+
+        Ok(())
+    }
 }
@@ -6,8 +6,10 @@
 
 abc_rust_lint::lint! {
     pub mod avalanche;
+    pub mod debug;
     pub mod subs_group;
     pub mod indexer;
     pub mod query;
+    pub mod queue;
     pub mod subs;
 }
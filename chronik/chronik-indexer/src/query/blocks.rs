@@ -95,6 +95,14 @@ pub enum QueryBlockError {
     /// Reading failed, likely corrupted block data
     #[error("500: Reading {0} failed")]
     ReadFailure(TxId),
+
+    /// Reading the block failed, likely corrupted block data
+    #[error("500: Reading block {0} failed")]
+    ReadBlockFailure(BlockHash),
+
+    /// Reading the block header failed, likely corrupted block data
+    #[error("500: Reading header of block {0} failed")]
+    ReadHeaderFailure(BlockHash),
 }
 
 use self::QueryBlockError::*;
@@ -125,6 +133,44 @@ impl<'a> QueryBlocks<'a> {
         })
     }
 
+    /// Query the header of a block by hash or height.
+    ///
+    /// Only the 80-byte header is read from the node's block files, no txs.
+    /// Only blocks of the main chain are indexed, so hashes of unknown or
+    /// orphaned (e.g. reorged out) blocks fail with "404: Block not found".
+    pub fn header(&self, hash_or_height: String) -> Result<proto::BlockHeader> {
+        let block_reader = BlockReader::new(self.db)?;
+        let db_block = match hash_or_height.parse::<HashOrHeight>()? {
+            HashOrHeight::Hash(hash) => block_reader.by_hash(&hash)?,
+            HashOrHeight::Height(height) => block_reader.by_height(height)?,
+        };
+        let db_block = db_block.ok_or(BlockNotFound(hash_or_height))?;
+        let raw_header =
+            ffi::load_block_header(db_block.file_num, db_block.data_pos)
+                .wrap_err(ReadHeaderFailure(db_block.hash.clone()))?;
+        let tip_height = block_reader.height()?;
+        Ok(proto::BlockHeader {
+            raw_header: raw_header.to_vec(),
+            height: db_block.height,
+            num_confirmations: tip_height - db_block.height + 1,
+            is_final: self.avalanche.is_final_height(db_block.height),
+        })
+    }
+
+    /// Query the serialized block by hash or height, as stored by the node.
+    pub fn raw_block(&self, hash_or_height: String) -> Result<Vec<u8>> {
+        let block_reader = BlockReader::new(self.db)?;
+        let db_block = match hash_or_height.parse::<HashOrHeight>()? {
+            HashOrHeight::Hash(hash) => block_reader.by_hash(&hash)?,
+            HashOrHeight::Height(height) => block_reader.by_height(height)?,
+        };
+        let db_block = db_block.ok_or(BlockNotFound(hash_or_height))?;
+        let raw_block =
+            ffi::load_raw_block(db_block.file_num, db_block.data_pos)
+                .wrap_err(ReadBlockFailure(db_block.hash))?;
+        Ok(raw_block)
+    }
+
     /// Query blocks by a range of heights. Start and end height are inclusive.
     pub fn by_range(
         &self,
@@ -158,6 +204,29 @@ impl<'a> QueryBlocks<'a> {
         Ok(proto::Blocks { blocks })
     }
 
+    /// Query the `num_blocks` most recent blocks, starting with the tip and
+    /// walking back towards genesis. Returns fewer blocks if the chain is
+    /// shorter.
+    pub fn recent(&self, num_blocks: usize) -> Result<proto::Blocks> {
+        if num_blocks > MAX_BLOCKS_PAGE_SIZE {
+            return Err(BlocksPageSizeTooLarge(num_blocks).into());
+        }
+        let block_reader = BlockReader::new(self.db)?;
+        let block_stats_reader = BlockStatsReader::new(self.db)?;
+        let tip_height = block_reader.height()?;
+        let mut blocks = Vec::with_capacity(num_blocks);
+        for block_height in (0..=tip_height).rev().take(num_blocks) {
+            let block = block_reader
+                .by_height(block_height)?
+                .ok_or(BlockNotFound(block_height.to_string()))?;
+            let block_stats = block_stats_reader
+                .by_height(block_height)?
+                .ok_or(MissingBlockStats(block_height))?;
+            blocks.push(self.make_block_info_proto(&block, &block_stats));
+        }
+        Ok(proto::Blocks { blocks })
+    }
+
     /// Query the txs of a block, paginated.
     pub fn block_txs(
         &self,
@@ -4,15 +4,22 @@
 
 //! Module for [`QueryGroupHistory`], to query the tx history of a group.
 
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, num::ParseIntError, str::FromStr};
 
 use abc_rust_error::Result;
-use bitcoinsuite_core::tx::{Tx, TxId};
+use bitcoinsuite_core::{
+    block::BlockHash,
+    error::DataError,
+    tx::{Tx, TxId},
+};
 use chronik_bridge::ffi;
 use chronik_db::{
     db::Db,
     group::Group,
-    io::{BlockReader, GroupHistoryReader, SpentByReader, TxNum, TxReader},
+    io::{
+        BlockHeight, BlockReader, GroupHistoryReader, SpentByReader, TxNum,
+        TxReader,
+    },
     mem::{Mempool, MempoolGroupHistory},
 };
 use chronik_proto::proto;
@@ -46,6 +53,49 @@ pub struct QueryGroupHistory<'a, G: Group> {
     pub group: G,
 }
 
+/// When a member of a group was first and last active, and in how many txs.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GroupActivity {
+    /// Height of the block of the member's first confirmed tx, or [`None`] if
+    /// it only has mempool txs.
+    pub first_height: Option<BlockHeight>,
+    /// Height of the block of the member's last confirmed tx, or [`None`] if
+    /// it only has mempool txs.
+    pub last_height: Option<BlockHeight>,
+    /// Number of txs of the member, confirmed and in the mempool.
+    pub tx_count: usize,
+}
+
+/// Cursor for [`QueryGroupHistory::confirmed_txs_cursor`], pointing to the
+/// last tx of the previous page.
+///
+/// Formatted as `<tx num>:<block hash>`. The tx num alone would point to a
+/// different position on the blockchain once its block is reorged out, so the
+/// block hash is included to detect and reject such stale cursors.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TxHistoryCursor {
+    /// Tx num of the last tx of the previous page
+    pub tx_num: TxNum,
+    /// Hash of the block containing that tx
+    pub block_hash: BlockHash,
+}
+
+/// Errors for parsing a [`TxHistoryCursor`].
+#[derive(Debug, Error, PartialEq)]
+pub enum ParseTxHistoryCursorError {
+    /// Cursor isn't of the form `<tx num>:<block hash>`
+    #[error("expected <tx num>:<block hash>")]
+    InvalidFormat,
+
+    /// Tx num of the cursor isn't a number
+    #[error("invalid tx num, {0}")]
+    InvalidTxNum(ParseIntError),
+
+    /// Block hash of the cursor isn't a valid hash
+    #[error("invalid block hash, {0}")]
+    InvalidBlockHash(DataError),
+}
+
 /// Errors indicating something went wrong with reading txs.
 #[derive(Debug, Error, PartialEq)]
 pub enum QueryGroupHistoryError {
@@ -74,10 +124,41 @@ pub enum QueryGroupHistoryError {
         MIN_HISTORY_PAGE_SIZE
     )]
     RequestPageSizeTooSmall(usize),
+
+    /// The block of the cursor's tx is not (or no longer) in the index.
+    #[error(
+        "400: Cursor {0} is stale, its block is not on the chain anymore; \
+         restart from the first page"
+    )]
+    StaleCursor(String),
 }
 
 use self::QueryGroupHistoryError::*;
 
+impl std::fmt::Display for TxHistoryCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.tx_num, self.block_hash)
+    }
+}
+
+impl FromStr for TxHistoryCursor {
+    type Err = ParseTxHistoryCursorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (tx_num, block_hash) = s
+            .split_once(':')
+            .ok_or(ParseTxHistoryCursorError::InvalidFormat)?;
+        Ok(TxHistoryCursor {
+            tx_num: tx_num
+                .parse()
+                .map_err(ParseTxHistoryCursorError::InvalidTxNum)?,
+            block_hash: block_hash
+                .parse()
+                .map_err(ParseTxHistoryCursorError::InvalidBlockHash)?,
+        })
+    }
+}
+
 impl<'a, G: Group> QueryGroupHistory<'a, G> {
     /// Return the confirmed txs of the group in the order as txs occur on the
     /// blockchain, i.e.:
@@ -158,6 +239,116 @@ impl<'a, G: Group> QueryGroupHistory<'a, G> {
         Ok(make_result(page_txs))
     }
 
+    /// Like [`QueryGroupHistory::confirmed_txs`], but paginated using a cursor
+    /// instead of a page number.
+    ///
+    /// The cursor points to the last tx of the previous page, or is [`None`]
+    /// to get the first page. Since tx nums increase with the position of txs
+    /// on the blockchain, i.e. (block height, index within the block), the
+    /// cursor keeps pointing to the same position when new txs are added, so
+    /// fetching the following pages never returns duplicates or skips txs.
+    ///
+    /// If the block of the cursor's tx has been reorged out, the position
+    /// isn't meaningful anymore, and the cursor is rejected as stale. Txs of
+    /// later blocks can still change through a reorg after they have been
+    /// returned; clients have to start again from the first page to see them.
+    ///
+    /// Returns the txs following the cursor together with the cursor for the
+    /// next page, which is empty if there are no more txs.
+    pub fn confirmed_txs_cursor(
+        &self,
+        member: G::Member<'_>,
+        cursor: Option<TxHistoryCursor>,
+        request_page_size: usize,
+    ) -> Result<proto::TxHistoryCursorPage> {
+        if request_page_size < MIN_HISTORY_PAGE_SIZE {
+            return Err(RequestPageSizeTooSmall(request_page_size).into());
+        }
+        if request_page_size > MAX_HISTORY_PAGE_SIZE {
+            return Err(RequestPageSizeTooBig(request_page_size).into());
+        }
+        if let Some(cursor) = &cursor {
+            self.verify_cursor(cursor)?;
+        }
+        let db_reader = GroupHistoryReader::<G>::new(self.db)?;
+        let member_ser = self.group.ser_member(&member);
+        let (num_db_pages, num_db_txs) =
+            db_reader.member_num_pages_and_txs(member_ser.as_ref())?;
+
+        // Index of the first tx after the cursor in the list of all txs of
+        // this member.
+        let first_tx_idx = match cursor {
+            Some(cursor) => db_tx_idx_after(
+                &db_reader,
+                member_ser.as_ref(),
+                num_db_pages,
+                cursor.tx_num,
+            )?,
+            None => 0,
+        };
+        let num_returned_txs =
+            request_page_size.min(num_db_txs.saturating_sub(first_tx_idx));
+        let mut page_tx_nums = Vec::with_capacity(num_returned_txs);
+
+        if num_returned_txs > 0 {
+            let db_page_num_start = first_tx_idx / db_reader.page_size();
+            let mut first_inner_idx = first_tx_idx % db_reader.page_size();
+            'outer: for current_page_num in db_page_num_start..num_db_pages {
+                let db_page_tx_nums = db_reader
+                    .page_txs(member_ser.as_ref(), current_page_num as u32)?
+                    .unwrap_or_default();
+                for &tx_num in db_page_tx_nums.iter().skip(first_inner_idx) {
+                    page_tx_nums.push(tx_num);
+                    if page_tx_nums.len() == num_returned_txs {
+                        break 'outer;
+                    }
+                }
+                first_inner_idx = 0;
+            }
+        }
+
+        let has_more_txs = first_tx_idx + page_tx_nums.len() < num_db_txs;
+        let next_cursor = match (page_tx_nums.last(), has_more_txs) {
+            (Some(&tx_num), true) => TxHistoryCursor {
+                tx_num,
+                block_hash: self.tx_block_hash(tx_num)?,
+            }
+            .to_string(),
+            _ => String::new(),
+        };
+        let txs = page_tx_nums
+            .into_iter()
+            .map(|tx_num| self.read_block_tx(tx_num))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(proto::TxHistoryCursorPage { txs, next_cursor })
+    }
+
+    fn verify_cursor(&self, cursor: &TxHistoryCursor) -> Result<()> {
+        let tx_reader = TxReader::new(self.db)?;
+        let block_reader = BlockReader::new(self.db)?;
+        let block_hash = match tx_reader.tx_by_tx_num(cursor.tx_num)? {
+            Some(block_tx) => block_reader
+                .by_height(block_tx.block_height)?
+                .map(|block| block.hash),
+            None => None,
+        };
+        if block_hash.as_ref() != Some(&cursor.block_hash) {
+            return Err(StaleCursor(cursor.to_string()).into());
+        }
+        Ok(())
+    }
+
+    fn tx_block_hash(&self, tx_num: TxNum) -> Result<BlockHash> {
+        let tx_reader = TxReader::new(self.db)?;
+        let block_reader = BlockReader::new(self.db)?;
+        let block_tx =
+            tx_reader.tx_by_tx_num(tx_num)?.ok_or(MissingDbTx(tx_num))?;
+        let block = block_reader
+            .by_height(block_tx.block_height)?
+            .ok_or(MissingDbTxBlock(tx_num))?;
+        Ok(block.hash)
+    }
+
     /// Return the group history in reverse chronological order, i.e. the latest
     /// one first, including mempool txs.
     ///
@@ -363,6 +554,58 @@ impl<'a, G: Group> QueryGroupHistory<'a, G> {
         })
     }
 
+    /// When the member was first and last active, or [`None`] if it has no
+    /// txs at all.
+    ///
+    /// This is derived from the tx history index, so it only reads the first
+    /// and the last page of the member's history and the entries of those two
+    /// txs, and it's always in sync with the indexed blocks and mempool.
+    pub fn activity(
+        &self,
+        member: G::Member<'_>,
+    ) -> Result<Option<GroupActivity>> {
+        let db_reader = GroupHistoryReader::<G>::new(self.db)?;
+        let tx_reader = TxReader::new(self.db)?;
+        let member_ser = self.group.ser_member(&member);
+        let (num_db_pages, num_db_txs) =
+            db_reader.member_num_pages_and_txs(member_ser.as_ref())?;
+        let num_mempool_txs = self
+            .mempool_history
+            .member_history(member_ser.as_ref())
+            .map_or(0, |txs| txs.len());
+        if num_db_txs + num_mempool_txs == 0 {
+            return Ok(None);
+        }
+        let block_height = |tx_num: Option<&TxNum>| -> Result<_> {
+            let Some(&tx_num) = tx_num else {
+                return Ok(None);
+            };
+            let block_tx =
+                tx_reader.tx_by_tx_num(tx_num)?.ok_or(MissingDbTx(tx_num))?;
+            Ok(Some(block_tx.block_height))
+        };
+        let (first_height, last_height) = match num_db_pages {
+            0 => (None, None),
+            _ => {
+                let first_page = db_reader
+                    .page_txs(member_ser.as_ref(), 0)?
+                    .unwrap_or_default();
+                let last_page = db_reader
+                    .page_txs(member_ser.as_ref(), num_db_pages as u32 - 1)?
+                    .unwrap_or_default();
+                (
+                    block_height(first_page.first())?,
+                    block_height(last_page.last())?,
+                )
+            }
+        };
+        Ok(Some(GroupActivity {
+            first_height,
+            last_height,
+            tx_count: num_db_txs + num_mempool_txs,
+        }))
+    }
+
     fn read_block_tx(&self, tx_num: TxNum) -> Result<proto::Tx> {
         let tx_reader = TxReader::new(self.db)?;
         let block_reader = BlockReader::new(self.db)?;
@@ -393,3 +636,38 @@ impl<'a, G: Group> QueryGroupHistory<'a, G> {
         ))
     }
 }
+
+/// Index of the first tx with a tx num greater than `cursor`, in the list of
+/// all DB txs of the member.
+///
+/// DB pages are sorted by tx num and all but the last page are full, so we
+/// can binary search the pages and then the page itself.
+fn db_tx_idx_after<G: Group>(
+    db_reader: &GroupHistoryReader<'_, G>,
+    member_ser: &[u8],
+    num_db_pages: usize,
+    cursor: TxNum,
+) -> Result<usize> {
+    let read_page = |page_num: usize| -> Result<Vec<TxNum>> {
+        Ok(db_reader
+            .page_txs(member_ser, page_num as u32)?
+            .unwrap_or_default())
+    };
+    // Find the first page which has a tx after the cursor
+    let mut lo = 0;
+    let mut hi = num_db_pages;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match read_page(mid)?.last() {
+            Some(&last_tx_num) if last_tx_num > cursor => hi = mid,
+            _ => lo = mid + 1,
+        }
+    }
+    if lo == num_db_pages {
+        // All txs are at or before the cursor
+        return Ok(lo * db_reader.page_size());
+    }
+    let page_tx_nums = read_page(lo)?;
+    let inner_idx = page_tx_nums.partition_point(|&tx_num| tx_num <= cursor);
+    Ok(lo * db_reader.page_size() + inner_idx)
+}
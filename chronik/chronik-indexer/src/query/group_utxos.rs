@@ -11,13 +11,16 @@ use bitcoinsuite_core::tx::{OutPoint, TxId};
 use chronik_db::{
     db::Db,
     group::Group,
-    io::{GroupUtxoReader, TxNum, TxReader},
+    io::{BlockReader, GroupUtxoReader, TxNum, TxReader},
     mem::{Mempool, MempoolGroupUtxos},
 };
 use chronik_proto::proto;
 use thiserror::Error;
 
-use crate::{avalanche::Avalanche, query::make_outpoint_proto};
+use crate::{
+    avalanche::Avalanche,
+    query::{is_output_mature, make_outpoint_proto},
+};
 
 static EMPTY_MEMBER_UTXOS: BTreeSet<OutPoint> = BTreeSet::new();
 
@@ -64,14 +67,40 @@ impl<'a, G: Group> QueryGroupUtxos<'a, G> {
     /// - DB UTXOs first, ordered as they appear on the blockchain
     /// - Mempool UTXOs second, ordered by txid:out_idx.
     ///
+    /// Confirmation status is given by `block_height`, which is -1 for UTXOs
+    /// created in the mempool. UTXOs spent in the mempool are excluded.
+    /// `is_mature` tells whether a coinbase UTXO can already be spent in the
+    /// next block; UTXOs of other txs are always mature.
+    ///
     /// Note: This call can potentially be expensive on members with many UTXOs.
+    /// Results are intentionally not paginated: the DB stores all UTXOs of a
+    /// member in a single entry, so every page would have to read and filter
+    /// the entire set anyway, and pages would be inconsistent as soon as a
+    /// UTXO is spent between two requests. Clients that need to page through
+    /// a large wallet should use the paginated tx history instead.
     pub fn utxos(
         &self,
         member: G::Member<'_>,
+    ) -> Result<Vec<proto::ScriptUtxo>> {
+        self.first_utxos(member, usize::MAX)
+    }
+
+    /// Like [`QueryGroupUtxos::utxos`], but stops after `max_utxos` UTXOs,
+    /// so only that many are read and converted, e.g. to bound the work of a
+    /// WebSocket snapshot. Whether UTXOs have been left out can be detected by
+    /// asking for one more than needed.
+    pub fn first_utxos(
+        &self,
+        member: G::Member<'_>,
+        max_utxos: usize,
     ) -> Result<Vec<proto::ScriptUtxo>> {
         let tx_reader = TxReader::new(self.db)?;
         let utxo_reader = GroupUtxoReader::<G>::new(self.db)?;
         let member_ser = self.group.ser_member(&member);
+        let tip_height = match BlockReader::new(self.db)?.tip()? {
+            Some(tip) => tip.height,
+            None => -1,
+        };
 
         // Read UTXO entries from DB and mempool
         let db_utxos =
@@ -85,11 +114,16 @@ impl<'a, G: Group> QueryGroupUtxos<'a, G> {
         // as many DB UTXOs as are spent in the mempool. Since these are
         // expensive and short-lived, this doesn't really pose a DoS
         // attack vector.
-        let mut utxos =
-            Vec::with_capacity(db_utxos.len() + mempool_utxos.len());
+        let mut utxos = Vec::with_capacity(usize::min(
+            db_utxos.len() + mempool_utxos.len(),
+            max_utxos,
+        ));
 
         // Read + add DB UTXOs
         for db_utxo in db_utxos {
+            if utxos.len() >= max_utxos {
+                return Ok(utxos);
+            }
             let tx_num = db_utxo.outpoint.tx_num;
             let out_idx = db_utxo.outpoint.out_idx;
 
@@ -114,11 +148,19 @@ impl<'a, G: Group> QueryGroupUtxos<'a, G> {
                 is_coinbase: db_tx.entry.is_coinbase,
                 value: db_utxo.value,
                 is_final: self.avalanche.is_final_height(db_tx.block_height),
+                is_mature: is_output_mature(
+                    db_tx.entry.is_coinbase,
+                    db_tx.block_height,
+                    tip_height,
+                ),
             });
         }
 
         // Add DB UTXOs
         for &mempool_outpoint in mempool_utxos {
+            if utxos.len() >= max_utxos {
+                break;
+            }
             let mempool_tx = self
                 .mempool
                 .tx(&mempool_outpoint.txid)
@@ -134,6 +176,7 @@ impl<'a, G: Group> QueryGroupUtxos<'a, G> {
                 is_coinbase: false,
                 value: output.value,
                 is_final: false,
+                is_mature: true,
             });
         }
 
@@ -6,13 +6,14 @@
 
 use abc_rust_error::{Result, WrapErr};
 use bitcoinsuite_core::{
+    block::BlockHash,
     ser::BitcoinSer,
-    tx::{Tx, TxId},
+    tx::{OutPoint, SpentBy, Tx, TxId},
 };
 use chronik_bridge::ffi;
 use chronik_db::{
     db::Db,
-    io::{BlockReader, SpentByReader, TxReader},
+    io::{BlockHeight, BlockReader, DbBlock, SpentByReader, TxReader},
     mem::Mempool,
 };
 use chronik_proto::proto;
@@ -34,6 +35,15 @@ pub struct QueryTxs<'a> {
     pub mempool: &'a Mempool,
 }
 
+/// Which tx spent an output, see [`QueryTxs::spent_info`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SpentInfo {
+    /// Tx and input spending the output.
+    pub spent_by: SpentBy,
+    /// Whether the spending tx is in the mempool, otherwise it's mined.
+    pub is_mempool: bool,
+}
+
 /// Errors indicating something went wrong with reading txs.
 #[derive(Debug, Error, PartialEq)]
 pub enum QueryTxError {
@@ -48,6 +58,10 @@ pub enum QueryTxError {
     /// Reading failed, likely corrupted block data
     #[error("500: Reading {0} failed")]
     ReadFailure(TxId),
+
+    /// Transaction is in the mempool, but not in a block yet.
+    #[error("404: Transaction {0} not confirmed yet")]
+    TxNotConfirmed(TxId),
 }
 
 use self::QueryTxError::*;
@@ -101,6 +115,12 @@ impl<'a> QueryTxs<'a> {
         }
     }
 
+    /// Unix timestamp of when the given tx was first seen in the mempool, or
+    /// [`None`], if the tx is not in the mempool.
+    pub fn mempool_time_first_seen(&self, txid: &TxId) -> Option<i64> {
+        self.mempool.time_first_seen(txid)
+    }
+
     /// Query the raw serialized tx by txid.
     ///
     /// Serializes the tx if it's in the mempool, or reads the tx data from the
@@ -122,4 +142,99 @@ impl<'a> QueryTxs<'a> {
         };
         Ok(proto::RawTx { raw_tx })
     }
+
+    /// Query the hash and height of the block the given tx has been mined in.
+    ///
+    /// Returns [`None`] if the tx is in the mempool or not in the index at all.
+    /// This only needs a lookup in the index, and doesn't read the tx data.
+    pub fn tx_block(
+        &self,
+        txid: &TxId,
+    ) -> Result<Option<(BlockHash, BlockHeight)>> {
+        let block = self.db_block_by_txid(txid)?;
+        Ok(block.map(|block| (block.hash, block.height)))
+    }
+
+    /// Query the block metadata of the block the given tx has been mined in,
+    /// e.g. to track the number of confirmations of a tx.
+    pub fn tx_block_metadata(
+        &self,
+        txid: &TxId,
+    ) -> Result<proto::BlockMetadata> {
+        let Some(block) = self.db_block_by_txid(txid)? else {
+            if self.mempool.tx(txid).is_some() {
+                return Err(TxNotConfirmed(*txid).into());
+            }
+            return Err(TxNotFound(*txid).into());
+        };
+        Ok(proto::BlockMetadata {
+            hash: block.hash.to_vec(),
+            height: block.height,
+            timestamp: block.timestamp,
+            is_final: self.avalanche.is_final_height(block.height),
+        })
+    }
+
+    /// Query the txs known to conflict with the given tx, i.e. spending some
+    /// of the same outputs.
+    ///
+    /// This covers mempool txs the node removed for a mined tx, and txs that
+    /// weren't indexed because they conflicted with Chronik's mempool, see
+    /// [`chronik_db::mem::MempoolConflicts`]. Conflicts are kept for a while
+    /// after being resolved. Unknown txs have no conflicts.
+    pub fn tx_conflicts(&self, txid: &TxId) -> proto::TxConflicts {
+        let conflicting_txids = self.mempool.conflicts().conflicts(txid);
+        proto::TxConflicts {
+            conflicting_txids: conflicting_txids
+                .into_iter()
+                .flatten()
+                .map(|txid| txid.to_vec())
+                .collect(),
+        }
+    }
+
+    /// Query which tx spent the given output, either in the mempool or in a
+    /// block.
+    ///
+    /// Returns [`None`] if the output is unspent, or if the tx of the output
+    /// isn't in the index. This only needs lookups in the index, and doesn't
+    /// read any tx data.
+    pub fn spent_info(&self, outpoint: &OutPoint) -> Result<Option<SpentInfo>> {
+        let spent_by_mempool = self
+            .mempool
+            .spent_by()
+            .outputs_spent(&outpoint.txid)
+            .and_then(|outputs_spent| outputs_spent.get(&outpoint.out_idx));
+        if let Some(&spent_by) = spent_by_mempool {
+            return Ok(Some(SpentInfo {
+                spent_by,
+                is_mempool: true,
+            }));
+        }
+        let tx_reader = TxReader::new(self.db)?;
+        let Some(tx_num) = tx_reader.tx_num_by_txid(&outpoint.txid)? else {
+            return Ok(None);
+        };
+        let spent_by_reader = SpentByReader::new(self.db)?;
+        let outputs_spent =
+            OutputsSpent::query(&spent_by_reader, &tx_reader, None, tx_num)?;
+        Ok(outputs_spent
+            .spent_by(outpoint.out_idx)
+            .map(|spent_by| SpentInfo {
+                spent_by,
+                is_mempool: false,
+            }))
+    }
+
+    fn db_block_by_txid(&self, txid: &TxId) -> Result<Option<DbBlock>> {
+        let tx_reader = TxReader::new(self.db)?;
+        let Some(block_tx) = tx_reader.tx_by_txid(txid)? else {
+            return Ok(None);
+        };
+        let block_reader = BlockReader::new(self.db)?;
+        let block = block_reader
+            .by_height(block_tx.block_height)?
+            .ok_or(DbTxHasNoBlock(*txid))?;
+        Ok(Some(block))
+    }
 }
@@ -43,6 +43,29 @@ pub enum QueryUtilError {
 
 use self::QueryUtilError::*;
 
+/// Number of blocks until outputs of a coinbase tx can be spent, i.e. a
+/// coinbase output of height h can first be spent in the block at height
+/// h + [`COINBASE_MATURITY`].
+pub const COINBASE_MATURITY: BlockHeight = 100;
+
+/// Whether an output created at `block_height` can be spent in the block
+/// after `tip_height`. Only coinbase outputs can be immature.
+///
+/// ```
+/// # use chronik_indexer::query::is_output_mature;
+/// assert!(is_output_mature(false, 10, 10));
+/// assert!(!is_output_mature(true, 10, 10));
+/// assert!(!is_output_mature(true, 10, 108));
+/// assert!(is_output_mature(true, 10, 109));
+/// ```
+pub fn is_output_mature(
+    is_coinbase: bool,
+    block_height: BlockHeight,
+    tip_height: BlockHeight,
+) -> bool {
+    !is_coinbase || tip_height + 1 - block_height >= COINBASE_MATURITY
+}
+
 /// Make a [`proto::Tx`].
 pub(crate) fn make_tx_proto(
     tx: &Tx,
@@ -84,6 +107,7 @@ pub(crate) fn make_tx_proto(
                 spent_by: outputs_spent
                     .spent_by(output_idx as u32)
                     .map(|spent_by| make_spent_by_proto(&spent_by)),
+                output_script_type: output.script.type_label().to_string(),
             })
             .collect(),
         lock_time: tx.locktime,
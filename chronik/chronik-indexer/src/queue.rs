@@ -0,0 +1,316 @@
+// Copyright (c) 2023 The Bitcoin developers
+// Distributed under the MIT software license, see the accompanying
+// file COPYING or http://www.opensource.org/licenses/mit-license.php.
+
+//! Module for [`event_queue`], a bounded queue of events where the sender can
+//! wait until its event has been processed.
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot, watch};
+
+/// Sending side of an [`event_queue`].
+#[derive(Debug)]
+pub struct EventSender<E> {
+    send: mpsc::Sender<(E, EventProcessed)>,
+    num_sent: Arc<AtomicU64>,
+    num_processed: Arc<watch::Sender<u64>>,
+}
+
+/// Receiving side of an [`event_queue`].
+#[derive(Debug)]
+pub struct EventReceiver<E> {
+    recv: mpsc::Receiver<(E, EventProcessed)>,
+}
+
+/// Handed out with an event by [`EventReceiver::blocking_recv`], to notify the
+/// sender once the event has been processed.
+///
+/// Dropping it counts the event as processed for [`EventSync`], even if
+/// [`EventProcessed::notify`] hasn't been called, so syncs don't hang.
+#[derive(Debug)]
+pub struct EventProcessed {
+    notify: Option<oneshot::Sender<()>>,
+    num_processed: Arc<watch::Sender<u64>>,
+}
+
+/// Handle to wait until all events sent so far have been processed, without
+/// holding on to the [`EventSender`]. Cheap to clone.
+#[derive(Clone, Debug)]
+pub struct EventSync {
+    num_sent: Arc<AtomicU64>,
+    num_processed: watch::Receiver<u64>,
+}
+
+/// Returned by [`EventSender::blocking_send`], to wait until the receiver
+/// processed the event.
+#[derive(Debug)]
+pub struct EventPending(oneshot::Receiver<()>);
+
+/// Errors for [`EventSender`] and [`EventPending`].
+#[derive(Debug, Eq, Error, PartialEq)]
+pub enum EventQueueError {
+    /// The [`EventReceiver`] has been dropped, so no events can be sent.
+    #[error("Event receiver dropped")]
+    ReceiverDropped,
+
+    /// The event was dropped without notifying that it has been processed.
+    #[error("Event dropped without being processed")]
+    EventNotProcessed,
+}
+
+use self::EventQueueError::*;
+
+/// Create a queue for events of type `E`, holding at most `capacity` events
+/// that haven't been taken by the receiver yet.
+///
+/// Events are received in the order they were sent.
+pub fn event_queue<E>(capacity: usize) -> (EventSender<E>, EventReceiver<E>) {
+    let (send, recv) = mpsc::channel(capacity);
+    let (num_processed, _) = watch::channel(0);
+    let sender = EventSender {
+        send,
+        num_sent: Arc::new(AtomicU64::new(0)),
+        num_processed: Arc::new(num_processed),
+    };
+    (sender, EventReceiver { recv })
+}
+
+impl<E> EventSender<E> {
+    /// Queue the event, blocking while the queue is full.
+    ///
+    /// Returns a handle to wait until the receiver has processed the event.
+    /// Must not be called from within an async runtime.
+    pub fn blocking_send(
+        &self,
+        event: E,
+    ) -> Result<EventPending, EventQueueError> {
+        let (processed_send, processed_recv) = oneshot::channel();
+        let processed = EventProcessed {
+            notify: Some(processed_send),
+            num_processed: Arc::clone(&self.num_processed),
+        };
+        self.send
+            .blocking_send((event, processed))
+            .map_err(|_| ReceiverDropped)?;
+        self.num_sent.fetch_add(1, Ordering::SeqCst);
+        Ok(EventPending(processed_recv))
+    }
+
+    /// Handle to wait until the events sent so far have been processed.
+    pub fn sync(&self) -> EventSync {
+        EventSync {
+            num_sent: Arc::clone(&self.num_sent),
+            num_processed: self.num_processed.subscribe(),
+        }
+    }
+}
+
+impl<E> EventReceiver<E> {
+    /// Take the next event from the queue, blocking until there is one.
+    ///
+    /// Returns [`None`] once all [`EventSender`]s have been dropped and all
+    /// events have been taken. Must not be called from within an async
+    /// runtime.
+    pub fn blocking_recv(&mut self) -> Option<(E, EventProcessed)> {
+        self.recv.blocking_recv()
+    }
+}
+
+impl EventProcessed {
+    /// Notify the sender that the event has been processed.
+    pub fn notify(mut self) {
+        if let Some(notify) = self.notify.take() {
+            // The sender may not wait for the event, which is fine
+            let _ = notify.send(());
+        }
+    }
+}
+
+impl Drop for EventProcessed {
+    fn drop(&mut self) {
+        self.num_processed
+            .send_modify(|num_processed| *num_processed += 1);
+    }
+}
+
+impl EventSync {
+    /// Wait until all events sent before calling this have been processed.
+    ///
+    /// Events are processed in order, so this is a sync point: e.g. once a
+    /// node callback queued its event and returned, a reader calling this
+    /// afterwards sees the event applied. Returns early if the
+    /// [`EventSender`] and all pending events have been dropped, as nothing
+    /// will be processed anymore.
+    ///
+    /// Which events to wait for is decided when calling this, not when first
+    /// polling the returned future.
+    pub fn wait_processed(&self) -> impl Future<Output = ()> + Send {
+        let num_sent = self.num_sent.load(Ordering::SeqCst);
+        let mut num_processed = self.num_processed.clone();
+        async move {
+            while *num_processed.borrow_and_update() < num_sent {
+                if num_processed.changed().await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl EventPending {
+    /// Block until the receiver has processed the event.
+    ///
+    /// Fails if the event has been dropped without being processed, e.g.
+    /// because the receiver has been dropped.
+    pub fn wait(self) -> Result<(), EventQueueError> {
+        self.0.blocking_recv().map_err(|_| EventNotProcessed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use crate::queue::{event_queue, EventQueueError};
+
+    #[test]
+    fn test_event_queue_order() -> Result<(), EventQueueError> {
+        let (send, mut recv) = event_queue(10);
+        let pending = (0..5)
+            .map(|event| send.blocking_send(event))
+            .collect::<Result<Vec<_>, _>>()?;
+        for expected in 0..5 {
+            let (event, processed) = recv.blocking_recv().unwrap();
+            assert_eq!(event, expected);
+            processed.notify();
+        }
+        for pending in pending {
+            pending.wait()?;
+        }
+        drop(send);
+        assert!(recv.blocking_recv().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_event_queue_backpressure() -> Result<(), EventQueueError> {
+        let (send, mut recv) = event_queue(1);
+        let num_sent = Arc::new(AtomicUsize::new(0));
+        let sender = std::thread::spawn({
+            let num_sent = Arc::clone(&num_sent);
+            move || -> Result<(), EventQueueError> {
+                let mut pending = Vec::new();
+                for event in 0..3 {
+                    pending.push(send.blocking_send(event)?);
+                    num_sent.fetch_add(1, Ordering::SeqCst);
+                }
+                for pending in pending {
+                    pending.wait()?;
+                }
+                Ok(())
+            }
+        });
+        let wait_num_sent = |expected: usize| {
+            while num_sent.load(Ordering::SeqCst) < expected {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            // Give the sender time to send more than it should
+            std::thread::sleep(Duration::from_millis(50));
+            assert_eq!(num_sent.load(Ordering::SeqCst), expected);
+        };
+
+        // Queue is full after the first event
+        wait_num_sent(1);
+        let mut processed = Vec::new();
+        for expected in 0..3 {
+            let (event, event_processed) = recv.blocking_recv().unwrap();
+            assert_eq!(event, expected);
+            processed.push(event_processed);
+            // Taking an event frees a slot for exactly one more event
+            wait_num_sent(usize::min(expected + 2, 3));
+        }
+        for event_processed in processed {
+            event_processed.notify();
+        }
+        sender.join().unwrap()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_event_queue_not_processed() {
+        let (send, mut recv) = event_queue(1);
+        let pending = send.blocking_send(1).unwrap();
+        let (_, processed) = recv.blocking_recv().unwrap();
+        drop(processed);
+        assert_eq!(pending.wait(), Err(EventQueueError::EventNotProcessed));
+
+        drop(recv);
+        assert_eq!(
+            send.blocking_send(2).unwrap_err(),
+            EventQueueError::ReceiverDropped,
+        );
+    }
+
+    #[test]
+    fn test_event_queue_sync() -> Result<(), EventQueueError> {
+        fn block_on(wait: impl Future<Output = ()>) {
+            tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap()
+                .block_on(wait)
+        }
+
+        let (send, mut recv) = event_queue(10);
+        // Nothing sent yet, so nothing to wait for
+        block_on(send.sync().wait_processed());
+
+        send.blocking_send(1)?;
+        send.blocking_send(2)?;
+        let synced = Arc::new(AtomicBool::new(false));
+        let waiter = std::thread::spawn({
+            let wait = send.sync().wait_processed();
+            let synced = Arc::clone(&synced);
+            move || {
+                block_on(wait);
+                synced.store(true, Ordering::SeqCst);
+            }
+        });
+        let assert_not_synced = || {
+            std::thread::sleep(Duration::from_millis(50));
+            assert!(!synced.load(Ordering::SeqCst));
+        };
+
+        // Events sent after the sync started aren't waited for
+        send.blocking_send(3)?;
+        assert_not_synced();
+        let (_, processed) = recv.blocking_recv().unwrap();
+        processed.notify();
+        assert_not_synced();
+        // Dropped events count as processed, so the sync doesn't hang
+        let (_, processed) = recv.blocking_recv().unwrap();
+        drop(processed);
+        waiter.join().unwrap();
+        assert!(synced.load(Ordering::SeqCst));
+
+        // Dropping the receiver drops the pending events, which unblocks syncs
+        let wait = send.sync().wait_processed();
+        drop(recv);
+        block_on(wait);
+        Ok(())
+    }
+}
@@ -5,20 +5,40 @@
 //! Rust side of the bridge; these structs and functions are exposed to C++.
 
 use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
     net::{AddrParseError, IpAddr, SocketAddr},
+    path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, Instant},
 };
 
-use abc_rust_error::Result;
+use abc_rust_error::{Result, WrapErr};
 use bitcoinsuite_core::{
+    network::Network,
     script::Script,
     tx::{Tx, TxId},
 };
-use chronik_bridge::{ffi::init_error, util::expect_unique_ptr};
-use chronik_db::mem::MempoolTx;
-use chronik_http::server::{ChronikServer, ChronikServerParams};
-use chronik_indexer::indexer::{ChronikIndexer, ChronikIndexerParams};
-use chronik_util::{log, log_chronik};
+use chronik_bridge::{
+    ffi::{init_error, BridgeTxStatus},
+    util::{expect_unique_ptr, retry_with_backoff, BridgeErrorKind},
+};
+use chronik_db::{groups::filter_scripts, mem::MempoolTx};
+use chronik_http::server::{
+    ChronikServer, ChronikServerParams, FnBroadcastTx,
+    DEFAULT_COMPRESSION_MIN_SIZE,
+};
+use chronik_indexer::{
+    debug::DebugCounters,
+    indexer::{
+        ChronikBlock, ChronikIndexer, ChronikIndexerParams,
+        DEFAULT_RESYNC_COMMIT_INTERVAL,
+    },
+    queue::{event_queue, EventReceiver, EventSender},
+};
+use chronik_util::{
+    init_log_sink, log, log_chronik, JsonLinesLogSink, LogLevel,
+};
 use thiserror::Error;
 use tokio::sync::RwLock;
 
@@ -33,10 +53,59 @@ pub enum ChronikError {
     /// Chronik host address failed to parse
     #[error("Invalid Chronik host address {0:?}: {1}")]
     InvalidChronikHost(String, AddrParseError),
+
+    /// No hosts to bind the Chronik HTTP server to, e.g. with -nochronikbind
+    #[error(
+        "Chronik is not bound to any host, specify at least one host using \
+         -chronikbind"
+    )]
+    NoChronikHosts,
+
+    /// -chronikconflictretention must not be negative
+    #[error("Invalid -chronikconflictretention {0}, must be 0 or greater")]
+    InvalidConflictRetention(i32),
+
+    /// -chronikscriptfilter is not a script in hex
+    #[error("Invalid -chronikscriptfilter {0:?}, must be a script in hex")]
+    InvalidScriptFilter(String),
+
+    /// -chronikrequesttimeout must be positive
+    #[error("Invalid -chronikrequesttimeout {0}, must be 1 or greater")]
+    InvalidRequestTimeout(i64),
+
+    /// -chronikroutetimeout is not a route followed by a positive timeout
+    #[error(
+        "Invalid -chronikroutetimeout {0:?}, must be <route>=<seconds>, e.g. \
+         /blocks/:start/:end=60"
+    )]
+    InvalidRouteTimeout(String),
+
+    /// -chroniklogfile couldn't be opened for appending
+    #[error("Failed opening -chroniklogfile {0}")]
+    OpenLogFileFailed(PathBuf),
+
+    /// The indexer task stopped, so node events can't be indexed anymore
+    #[error("Chronik indexer stopped, cannot index {0}")]
+    IndexerStopped(&'static str),
+
+    /// Bridging a mempool tx failed, even after retrying
+    #[error("Bridging tx failed with status {0:?}")]
+    BridgeTxFailed(BridgeTxStatus),
 }
 
 use self::ChronikError::*;
 
+/// How often bridging a mempool tx is retried if it fails transiently.
+const BRIDGE_TX_MAX_RETRIES: u32 = 5;
+
+/// How long to wait before the first retry of bridging a mempool tx, doubled
+/// for every retry after that, so all retries wait 310ms in total.
+const BRIDGE_TX_INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+
+/// How many node events can be queued for the indexer before node callbacks
+/// block until the indexer catches up.
+const INDEXER_QUEUE_CAPACITY: usize = 1000;
+
 /// Setup the Chronik bridge. Returns a ChronikIndexer object.
 pub fn setup_chronik(
     params: ffi::SetupParams,
@@ -52,43 +121,134 @@ pub fn setup_chronik(
     }
 }
 
+fn install_log_file(path: PathBuf, log_debug: bool) -> Result<()> {
+    let file = open_log_file(&path)
+        .wrap_err_with(|| OpenLogFileFailed(path.clone()))?;
+    let sink = JsonLinesLogSink::with_reopen(
+        file,
+        Box::new(move || open_log_file(&path)),
+    );
+    let min_level = match log_debug {
+        true => LogLevel::Debug,
+        false => LogLevel::Info,
+    };
+    init_log_sink(Box::new(sink), min_level);
+    Ok(())
+}
+
+fn open_log_file(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
 fn try_setup_chronik(
     params: ffi::SetupParams,
     config: &ffi::Config,
     node: &ffi::NodeContext,
 ) -> Result<()> {
     abc_rust_error::install();
+    if !params.log_file.is_empty() {
+        install_log_file(params.log_file.into(), params.log_debug)?;
+    }
     let hosts = params
         .hosts
         .into_iter()
         .map(|host| parse_socket_addr(host, params.default_port))
         .collect::<Result<Vec<_>>>()?;
-    log!("Starting Chronik bound to {:?}\n", hosts);
-    let bridge = chronik_bridge::ffi::make_bridge(config, node);
-    let bridge_ref = expect_unique_ptr("make_bridge", &bridge);
-    let mut indexer = ChronikIndexer::setup(ChronikIndexerParams {
+    if hosts.is_empty() {
+        return Err(NoChronikHosts.into());
+    }
+    let network = params.network.parse::<Network>()?;
+    if params.conflict_retention_blocks < 0 {
+        return Err(
+            InvalidConflictRetention(params.conflict_retention_blocks).into()
+        );
+    }
+    let script_filter = match params.script_filter.is_empty() {
+        true => None,
+        false => Some(filter_scripts(
+            params
+                .script_filter
+                .into_iter()
+                .map(|hex| {
+                    Script::from_hex(&hex).map_err(|_| InvalidScriptFilter(hex))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        )),
+    };
+    if params.request_timeout_secs < 1 {
+        return Err(InvalidRequestTimeout(params.request_timeout_secs).into());
+    }
+    let request_timeout =
+        Duration::from_secs(params.request_timeout_secs as u64);
+    let route_timeouts = params
+        .route_timeouts
+        .into_iter()
+        .map(parse_route_timeout)
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    let indexer_params = ChronikIndexerParams {
         datadir_net: params.datadir_net.into(),
+        network,
         wipe_db: params.wipe_db,
-        fn_compress_script: compress_script,
-    })?;
+        read_only: false,
+        fn_compress_script: Arc::new(compress_script),
+        resync_commit_interval: DEFAULT_RESYNC_COMMIT_INTERVAL,
+        conflict_retention_blocks: params.conflict_retention_blocks,
+        verify_merkle_root: true,
+        script_filter,
+    };
+    if params.validate_only {
+        ChronikIndexer::validate_setup(&indexer_params)?;
+        log!("Chronik config is valid, hosts: {:?}\n", hosts);
+        return Ok(());
+    }
+    log!("Starting Chronik bound to {:?}\n", hosts);
+    let bridge = Arc::new(chronik_bridge::ffi::make_bridge(config, node));
+    let bridge_ref = expect_unique_ptr("make_bridge", &bridge);
+    let mut indexer = ChronikIndexer::setup(indexer_params)?;
     indexer.resync_indexer(bridge_ref)?;
+    if chronik_bridge::ffi::shutdown_requested() {
+        // Node is shutting down during the resync, don't start serving
+        return Ok(());
+    }
+    let debug_counters = Arc::clone(indexer.debug_counters());
     let indexer = Arc::new(RwLock::new(indexer));
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()?;
+    let (events_send, events_recv) = event_queue(INDEXER_QUEUE_CAPACITY);
+    let event_sync = events_send.sync();
+    runtime.spawn_blocking({
+        let indexer = Arc::clone(&indexer);
+        let debug_counters = Arc::clone(&debug_counters);
+        move || index_events(&indexer, &debug_counters, events_recv)
+    });
     let server = runtime.block_on({
         let indexer = Arc::clone(&indexer);
+        let fn_broadcast_tx = broadcast_tx_fn(Arc::clone(&bridge));
+        let debug_counters =
+            params.debug_stats.then(|| Arc::clone(&debug_counters));
         async move {
             // try_bind requires a Runtime
-            ChronikServer::setup(ChronikServerParams { hosts, indexer })
+            ChronikServer::setup(ChronikServerParams {
+                hosts,
+                indexer,
+                fn_broadcast_tx,
+                event_sync,
+                compression_min_size: DEFAULT_COMPRESSION_MIN_SIZE,
+                request_timeout,
+                route_timeouts,
+                debug_counters,
+            })
         }
     })?;
     runtime.spawn(async move {
         ok_or_abort_node("ChronikServer::serve", server.serve().await);
     });
     let chronik = Box::new(Chronik {
-        bridge: Arc::new(bridge),
+        bridge,
+        events: events_send,
         indexer,
+        debug_counters,
         _runtime: runtime,
     });
     StartChronikValidationInterface(node, chronik);
@@ -105,16 +265,171 @@ fn parse_socket_addr(host: String, default_port: u16) -> Result<SocketAddr> {
     Ok(SocketAddr::new(ip_addr, default_port))
 }
 
+fn parse_route_timeout(
+    arg: String,
+) -> Result<(String, Duration), ChronikError> {
+    let route_timeout = arg.rsplit_once('=').and_then(|(route, secs)| {
+        let secs = secs.parse::<u64>().ok().filter(|&secs| secs > 0)?;
+        let route = Some(route).filter(|route| route.starts_with('/'))?;
+        Some((route.to_string(), Duration::from_secs(secs)))
+    });
+    route_timeout.ok_or(InvalidRouteTimeout(arg))
+}
+
+/// Broadcast txs via the node, for the HTTP server's `/tx/broadcast`.
+fn broadcast_tx_fn(
+    bridge: Arc<cxx::UniquePtr<ffi::ChronikBridge>>,
+) -> FnBroadcastTx {
+    Arc::new(move |raw_tx| {
+        let bridge = expect_unique_ptr("make_bridge", &bridge);
+        bridge
+            .broadcast_tx(raw_tx)
+            .map(TxId::from)
+            .map_err(|err| err.what().to_string())
+    })
+}
+
 fn compress_script(script: &Script) -> Vec<u8> {
     chronik_bridge::ffi::compress_script(script.as_ref())
 }
 
+/// Event from the node, queued to be applied to the indexer.
+enum IndexerEvent {
+    TxAddedToMempool(MempoolTx),
+    TxRemovedFromMempool(TxId),
+    TxRemovedForBlock(TxId),
+    BlockConnected(ChronikBlock),
+    BlockDisconnected(ChronikBlock),
+    BlockFinalized(ChronikBlock),
+}
+
+impl IndexerEvent {
+    fn func_name(&self) -> &'static str {
+        match self {
+            IndexerEvent::TxAddedToMempool(_) => "handle_tx_added_to_mempool",
+            IndexerEvent::TxRemovedFromMempool(_) => {
+                "handle_tx_removed_from_mempool"
+            }
+            IndexerEvent::TxRemovedForBlock(_) => "handle_tx_removed_for_block",
+            IndexerEvent::BlockConnected(_) => "handle_block_connected",
+            IndexerEvent::BlockDisconnected(_) => "handle_block_disconnected",
+            IndexerEvent::BlockFinalized(_) => "handle_block_finalized",
+        }
+    }
+}
+
+/// Drain the event queue, applying each event to the indexer in order, and
+/// notifying syncs waiting for the event once it's indexed.
+/// Returns once all senders are dropped, i.e. when [`Chronik`] is dropped.
+fn index_events(
+    indexer: &RwLock<ChronikIndexer>,
+    debug_counters: &DebugCounters,
+    mut events: EventReceiver<IndexerEvent>,
+) {
+    while let Some((event, processed)) = events.blocking_recv() {
+        debug_counters.event_dequeued();
+        let func_name = event.func_name();
+        let wait_start = Instant::now();
+        let mut indexer = indexer.blocking_write();
+        debug_counters.record_write_lock_wait(wait_start.elapsed());
+        ok_or_abort_node(func_name, index_event(&mut indexer, event));
+        drop(indexer);
+        processed.notify();
+    }
+}
+
+fn index_event(
+    indexer: &mut ChronikIndexer,
+    event: IndexerEvent,
+) -> Result<()> {
+    match event {
+        IndexerEvent::TxAddedToMempool(mempool_tx) => {
+            let txid = mempool_tx.tx.txid();
+            indexer.handle_tx_added_to_mempool(mempool_tx)?;
+            log_chronik!("Chronik: transaction {} added to mempool\n", txid);
+        }
+        IndexerEvent::TxRemovedFromMempool(txid) => {
+            indexer.handle_tx_removed_from_mempool(txid)?;
+            log_chronik!(
+                "Chronik: transaction {} removed from mempool\n",
+                txid,
+            );
+        }
+        IndexerEvent::TxRemovedForBlock(txid) => {
+            indexer.handle_tx_removed_for_block(txid)?;
+            log_chronik!(
+                "Chronik: transaction {} removed from mempool for conflicting \
+                 with a block\n",
+                txid,
+            );
+        }
+        IndexerEvent::BlockConnected(block) => {
+            let block_hash = block.db_block.hash.clone();
+            let num_txs = block.block_txs.txs.len();
+            indexer.handle_block_connected(block)?;
+            log_chronik!(
+                "Chronik: block {} connected with {} txs\n",
+                block_hash,
+                num_txs,
+            );
+        }
+        IndexerEvent::BlockDisconnected(block) => {
+            let block_hash = block.db_block.hash.clone();
+            let num_txs = block.block_txs.txs.len();
+            indexer.handle_block_disconnected(block)?;
+            log_chronik!(
+                "Chronik: block {} disconnected with {} txs\n",
+                block_hash,
+                num_txs,
+            );
+        }
+        IndexerEvent::BlockFinalized(block) => {
+            let block_hash = block.db_block.hash.clone();
+            let num_txs = block.block_txs.txs.len();
+            indexer.handle_block_finalized(block)?;
+            log_chronik!(
+                "Chronik: block {} finalized with {} txs\n",
+                block_hash,
+                num_txs,
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Contains all db, runtime, tpc, etc. handles needed by Chronik.
 /// This makes it so when this struct is dropped, all handles are relased
 /// cleanly.
 pub struct Chronik {
     bridge: Arc<cxx::UniquePtr<ffi::ChronikBridge>>,
+    /// Queue of node events for the indexer task.
+    ///
+    /// Node callbacks bridge the data outside of the indexer lock, enqueue it
+    /// and return, so their latency doesn't depend on indexing. If the queue
+    /// is full, callbacks block until the indexer catches up, which keeps
+    /// memory usage bounded. The HTTP server waits for the events queued
+    /// before a request (see [`EventSender::sync`]), so once e.g.
+    /// `sendrawtransaction` or a block connection returns, the tx/block can
+    /// be queried from Chronik. This is dropped before the runtime, which
+    /// stops the indexer task once the remaining events are indexed.
+    events: EventSender<IndexerEvent>,
+    /// The indexer, shared with the HTTP server.
+    ///
+    /// This lock is the main point of contention in Chronik: the indexer
+    /// task takes the write lock for every event, which blocks all HTTP/WS
+    /// readers until the DB batch for the tx/block is written. To keep query
+    /// latency low during block processing, callbacks do as much work as
+    /// possible (bridging txs/blocks from the node, building
+    /// [`ChronikBlock`]s) before the event is queued, holding only a read lock
+    /// where indexer state is needed.
+    ///
+    /// The DB write batch itself isn't moved behind a separate, finer lock:
+    /// a block's batch, the mempool and the subscribers must change together.
+    /// Otherwise readers could see a block's txs both confirmed in the DB
+    /// and still in the mempool.
     indexer: Arc<RwLock<ChronikIndexer>>,
+    /// Internal counters, e.g. how many events are queued.
+    debug_counters: Arc<DebugCounters>,
     // Having this here ensures HTTP server, outstanding requests etc. will get
     // stopped when `Chronik` is dropped.
     _runtime: tokio::runtime::Runtime,
@@ -133,15 +448,19 @@ impl Chronik {
         );
     }
 
-    /// Tx removed from the bitcoind mempool
-    pub fn handle_tx_removed_from_mempool(&self, txid: [u8; 32]) {
-        let mut indexer = self.indexer.blocking_write();
+    /// Tx removed from the bitcoind mempool; `conflicts_with_block` is set
+    /// if it was removed because a tx in a block spends the same outputs
+    pub fn handle_tx_removed_from_mempool(
+        &self,
+        txid: [u8; 32],
+        conflicts_with_block: bool,
+    ) {
         let txid = TxId::from(txid);
-        ok_or_abort_node(
-            "handle_tx_removed_from_mempool",
-            indexer.handle_tx_removed_from_mempool(txid),
-        );
-        log_chronik!("Chronik: transaction {} removed from mempool\n", txid);
+        let event = match conflicts_with_block {
+            true => IndexerEvent::TxRemovedForBlock(txid),
+            false => IndexerEvent::TxRemovedFromMempool(txid),
+        };
+        ok_or_abort_node("handle_tx_removed_from_mempool", self.enqueue(event));
     }
 
     /// Block connected to the longest chain
@@ -178,33 +497,64 @@ impl Chronik {
         ptx: &ffi::CTransaction,
         time_first_seen: i64,
     ) -> Result<()> {
-        let mut indexer = self.indexer.blocking_write();
-        let tx = self.bridge.bridge_tx(ptx)?;
-        let txid = TxId::from(tx.txid);
-        indexer.handle_tx_added_to_mempool(MempoolTx {
+        // Bridge before queueing, so retries don't block the indexer
+        let tx = self.bridge_tx_with_retry(ptx)?;
+        self.enqueue(IndexerEvent::TxAddedToMempool(MempoolTx {
             tx: Tx::from(tx),
             time_first_seen,
-        })?;
-        log_chronik!("Chronik: transaction {} added to mempool\n", txid);
+        }))
+    }
+
+    /// Queue the event for the indexer task, blocking only if the queue is
+    /// full. Doesn't wait until the event is indexed.
+    fn enqueue(&self, event: IndexerEvent) -> Result<()> {
+        let func_name = event.func_name();
+        self.debug_counters.event_queued();
+        if self.events.blocking_send(event).is_err() {
+            self.debug_counters.event_dequeued();
+            return Err(IndexerStopped(func_name).into());
+        }
         Ok(())
     }
 
+    fn bridge_tx_with_retry(
+        &self,
+        ptx: &ffi::CTransaction,
+    ) -> Result<chronik_bridge::ffi::Tx> {
+        retry_with_backoff(
+            BRIDGE_TX_MAX_RETRIES,
+            BRIDGE_TX_INITIAL_BACKOFF,
+            // Only wait for the node's locks on the last attempt
+            |wait_for_locks| {
+                let result = self
+                    .bridge
+                    .bridge_tx(ptx, wait_for_locks)
+                    .map_err(|ex| (BridgeErrorKind::Permanent, ex.into()))?;
+                match BridgeErrorKind::from_bridge_tx_status(result.status) {
+                    None => Ok(result.tx),
+                    Some(kind) => {
+                        log_chronik!(
+                            "Chronik: bridge_tx failed with status {:?}\n",
+                            result.status,
+                        );
+                        Err((kind, BridgeTxFailed(result.status).into()))
+                    }
+                }
+            },
+        )
+    }
+
     fn connect_block(
         &self,
         block: &ffi::CBlock,
         bindex: &ffi::CBlockIndex,
     ) -> Result<()> {
-        let mut indexer = self.indexer.blocking_write();
-        let block = indexer.make_chronik_block(block, bindex)?;
-        let block_hash = block.db_block.hash.clone();
-        let num_txs = block.block_txs.txs.len();
-        indexer.handle_block_connected(block)?;
-        log_chronik!(
-            "Chronik: block {} connected with {} txs\n",
-            block_hash,
-            num_txs,
-        );
-        Ok(())
+        // Only needs read access, so readers aren't blocked while bridging
+        let block = {
+            let indexer = self.indexer.blocking_read();
+            indexer.make_chronik_block(block, bindex)?
+        };
+        self.enqueue(IndexerEvent::BlockConnected(block))
     }
 
     fn disconnect_block(
@@ -212,33 +562,23 @@ impl Chronik {
         block: &ffi::CBlock,
         bindex: &ffi::CBlockIndex,
     ) -> Result<()> {
-        let mut indexer = self.indexer.blocking_write();
-        let block = indexer.make_chronik_block(block, bindex)?;
-        let block_hash = block.db_block.hash.clone();
-        let num_txs = block.block_txs.txs.len();
-        indexer.handle_block_disconnected(block)?;
-        log_chronik!(
-            "Chronik: block {} disconnected with {} txs\n",
-            block_hash,
-            num_txs,
-        );
-        Ok(())
+        // Only needs read access, so readers aren't blocked while bridging
+        let block = {
+            let indexer = self.indexer.blocking_read();
+            indexer.make_chronik_block(block, bindex)?
+        };
+        self.enqueue(IndexerEvent::BlockDisconnected(block))
     }
 
     fn finalize_block(&self, bindex: &ffi::CBlockIndex) -> Result<()> {
-        let block = self.bridge.load_block(bindex)?;
-        let block_ref = expect_unique_ptr("load_block", &block);
-        let mut indexer = self.indexer.blocking_write();
-        let block = indexer.make_chronik_block(block_ref, bindex)?;
-        let block_hash = block.db_block.hash.clone();
-        let num_txs = block.block_txs.txs.len();
-        indexer.handle_block_finalized(block)?;
-        log_chronik!(
-            "Chronik: block {} finalized with {} txs\n",
-            block_hash,
-            num_txs,
-        );
-        Ok(())
+        // Scoped so the CBlock is freed before indexing the block
+        let block = {
+            let block = self.bridge.load_block(bindex)?;
+            let block_ref = expect_unique_ptr("load_block", &block);
+            let indexer = self.indexer.blocking_read();
+            indexer.make_chronik_block(block_ref, bindex)?
+        };
+        self.enqueue(IndexerEvent::BlockFinalized(block))
     }
 }
 
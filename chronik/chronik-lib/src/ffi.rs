@@ -4,6 +4,8 @@
 
 //! Module containing the cxx definitions for the bridge from Rust to C++.
 
+use chronik_util::request_reopen_log_sink;
+
 pub use self::ffi_inner::*;
 use crate::bridge::{setup_chronik, Chronik};
 
@@ -16,12 +18,34 @@ mod ffi_inner {
         /// Where the data of the blockchain is stored, dependent on network
         /// (mainnet, testnet, regtest)
         pub datadir_net: String,
+        /// Network the node is running on ("main", "test" or "regtest")
+        pub network: String,
         /// Host addresses where the Chronik HTTP endpoint will be served
         pub hosts: Vec<String>,
         /// Default port for `hosts` if only an IP address is given
         pub default_port: u16,
         /// Whether to clear the DB before proceeding, e.g. when reindexing
         pub wipe_db: bool,
+        /// Only validate the config (hosts, datadir, DB) without indexing or
+        /// serving anything
+        pub validate_only: bool,
+        /// Number of blocks to keep resolved mempool conflicts for
+        pub conflict_retention_blocks: i32,
+        /// Hex of the scripts to index the history and UTXOs of, or empty to
+        /// index all scripts
+        pub script_filter: Vec<String>,
+        /// Seconds an HTTP request may take before it is aborted
+        pub request_timeout_secs: i64,
+        /// Per-route overrides of `request_timeout_secs`, each formatted as
+        /// `<route>=<seconds>`, e.g. "/blocks/:start/:end=60"
+        pub route_timeouts: Vec<String>,
+        /// Serve internal counters at `/debug/stats`, for debugging
+        pub debug_stats: bool,
+        /// File to write log messages to as JSON lines, or empty to log to
+        /// bitcoind's debug.log
+        pub log_file: String,
+        /// Whether debug log messages are written to `log_file`
+        pub log_debug: bool,
     }
 
     extern "Rust" {
@@ -32,12 +56,20 @@ mod ffi_inner {
             node: &NodeContext,
         ) -> bool;
 
+        /// Reopen the log file before the next log message, e.g. on SIGHUP.
+        /// Only sets a flag, so it's safe to call from a signal handler.
+        fn request_reopen_log_sink();
+
         fn handle_tx_added_to_mempool(
             &self,
             ptx: &CTransaction,
             time_first_seen: i64,
         );
-        fn handle_tx_removed_from_mempool(&self, txid: [u8; 32]);
+        fn handle_tx_removed_from_mempool(
+            &self,
+            txid: [u8; 32],
+            conflicts_with_block: bool,
+        );
         fn handle_block_connected(&self, block: &CBlock, bindex: &CBlockIndex);
         fn handle_block_disconnected(
             &self,
@@ -2,11 +2,240 @@
 // Distributed under the MIT software license, see the accompanying
 // file COPYING or http://www.opensource.org/licenses/mit-license.php.
 
-//! Macros for logging messages to bitcoind
+//! Macros for logging messages to bitcoind, or to a custom [`LogSink`].
+
+use std::{
+    fmt::Write as _,
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, RwLock,
+    },
+};
 
 pub use chronik_bridge::ffi::{log_print, log_print_chronik};
 
-/// Logs the message to bitcoind's logging system:
+/// Function name passed to bitcoind's logger, which we don't know in Rust.
+const UNKNOWN_LOGGING_FUNCTION: &str = "<chronik unknown>";
+
+/// Level of a log message, ordered by severity.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum LogLevel {
+    /// Debug message, logged using [`log_chronik!`], which bitcoind only logs
+    /// with `-debug=chronik`.
+    Debug,
+    /// General message, logged using [`log!`].
+    Info,
+}
+
+/// A single log message, as passed to a [`LogSink`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LogRecord<'a> {
+    /// Level of the message.
+    pub level: LogLevel,
+    /// Source file that logged the message.
+    pub file: &'a str,
+    /// Line in the source file that logged the message.
+    pub line: u32,
+    /// The formatted message.
+    pub msg: &'a str,
+}
+
+/// Destination of log messages, can be installed with [`init_log_sink`].
+pub trait LogSink: Send + Sync {
+    /// Write the given record to the sink.
+    fn log(&self, record: &LogRecord<'_>);
+
+    /// Close and reopen the sink's destination, e.g. after its log file has
+    /// been rotated by logrotate. Called by [`reopen_log_sink`] and after
+    /// [`request_reopen_log_sink`]; does nothing by default.
+    fn reopen(&self) {}
+}
+
+/// Default sink, logs to bitcoind's logging system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NodeLogSink;
+
+/// Sink writing every record as a JSON object on a single line, e.g.:
+/// `{"level":"info","file":"src/a.rs","line":3,"msg":"Hello\n"}`
+pub struct JsonLinesLogSink<W> {
+    writer: Mutex<W>,
+    reopen_writer: Option<ReopenWriterFn<W>>,
+}
+
+/// Callback opening a new writer for a [`JsonLinesLogSink`] when the sink is
+/// reopened, e.g. by opening its log file again after rotation.
+pub type ReopenWriterFn<W> = Box<dyn Fn() -> std::io::Result<W> + Send + Sync>;
+
+struct InstalledSink {
+    sink: Box<dyn LogSink>,
+    min_level: LogLevel,
+}
+
+static LOG_SINK: RwLock<Option<InstalledSink>> = RwLock::new(None);
+
+static REOPEN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Install a custom sink, which will receive every message logged with
+/// [`log!`] and [`log_chronik!`] with a level of at least `min_level`.
+/// Replaces any previously installed sink.
+pub fn init_log_sink(sink: Box<dyn LogSink>, min_level: LogLevel) {
+    let mut installed = LOG_SINK.write().unwrap_or_else(|err| err.into_inner());
+    *installed = Some(InstalledSink { sink, min_level });
+}
+
+/// Reopen the destination of the installed sink, see [`LogSink::reopen`].
+///
+/// Should be called when the log file has been rotated, e.g. on SIGHUP, so
+/// the sink stops writing to the rotated file. bitcoind's own logging (the
+/// default sink) already reopens its debug.log on SIGHUP by itself.
+pub fn reopen_log_sink() {
+    let installed = LOG_SINK.read().unwrap_or_else(|err| err.into_inner());
+    if let Some(installed) = installed.as_ref() {
+        installed.sink.reopen();
+    }
+}
+
+/// Request the installed sink to be reopened before the next record is
+/// logged, see [`LogSink::reopen`].
+///
+/// Unlike [`reopen_log_sink`], this only sets a flag, so it's safe to call
+/// from a signal handler. bitcoind calls it on SIGHUP.
+pub fn request_reopen_log_sink() {
+    REOPEN_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Log the record to the installed sink, or to bitcoind if none has been
+/// installed. Used by [`log!`] and [`log_chronik!`].
+pub fn log_record(record: &LogRecord<'_>) {
+    let installed = LOG_SINK.read().unwrap_or_else(|err| err.into_inner());
+    match installed.as_ref() {
+        Some(installed) => {
+            if REOPEN_REQUESTED.swap(false, Ordering::SeqCst) {
+                installed.sink.reopen();
+            }
+            if record.level >= installed.min_level {
+                installed.sink.log(record);
+            }
+        }
+        None => NodeLogSink.log(record),
+    }
+}
+
+impl LogLevel {
+    /// Lowercase name of the level, e.g. "info".
+    pub fn name(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+        }
+    }
+}
+
+impl LogSink for NodeLogSink {
+    fn log(&self, record: &LogRecord<'_>) {
+        let log_fn = match record.level {
+            LogLevel::Debug => log_print_chronik,
+            LogLevel::Info => log_print,
+        };
+        log_fn(
+            UNKNOWN_LOGGING_FUNCTION,
+            record.file,
+            record.line,
+            record.msg,
+        );
+    }
+}
+
+impl<W: Write + Send> JsonLinesLogSink<W> {
+    /// Create a sink writing JSON lines to `writer`.
+    pub fn new(writer: W) -> Self {
+        JsonLinesLogSink {
+            writer: Mutex::new(writer),
+            reopen_writer: None,
+        }
+    }
+
+    /// Create a sink writing JSON lines to `writer`, which will be replaced by
+    /// the writer returned by `reopen_writer` whenever the sink is reopened.
+    /// If `reopen_writer` fails, the sink keeps using its current writer.
+    pub fn with_reopen(writer: W, reopen_writer: ReopenWriterFn<W>) -> Self {
+        JsonLinesLogSink {
+            writer: Mutex::new(writer),
+            reopen_writer: Some(reopen_writer),
+        }
+    }
+
+    /// Take the writer out of the sink.
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<W: Write + Send> LogSink for JsonLinesLogSink<W> {
+    fn log(&self, record: &LogRecord<'_>) {
+        let line = format!(
+            "{{\"level\":\"{}\",\"file\":{},\"line\":{},\"msg\":{}}}\n",
+            record.level.name(),
+            json_string(record.file),
+            record.line,
+            json_string(record.msg),
+        );
+        let mut writer =
+            self.writer.lock().unwrap_or_else(|err| err.into_inner());
+        // Logging must never fail the caller, so write errors are dropped
+        let _ = writer.write_all(line.as_bytes());
+        let _ = writer.flush();
+    }
+
+    fn reopen(&self) {
+        let reopen_writer = match &self.reopen_writer {
+            Some(reopen_writer) => reopen_writer,
+            None => return,
+        };
+        // Keep logging to the old writer if we can't open a new one
+        if let Ok(new_writer) = reopen_writer() {
+            let mut writer =
+                self.writer.lock().unwrap_or_else(|err| err.into_inner());
+            let _ = writer.flush();
+            *writer = new_writer;
+        }
+    }
+}
+
+impl<W: std::fmt::Debug> std::fmt::Debug for JsonLinesLogSink<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonLinesLogSink")
+            .field("writer", &self.writer)
+            .field("reopen_writer", &self.reopen_writer.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                write!(json, "\\u{:04x}", c as u32).unwrap();
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Logs the message to the installed [`LogSink`], by default bitcoind's
+/// logging system:
 ///
 /// ```ignore
 /// let world = "world";
@@ -16,19 +245,19 @@ pub use chronik_bridge::ffi::{log_print, log_print_chronik};
 macro_rules! log {
     ($($arg:tt)*) => {
         #[cfg(not(test))]
-        chronik_util::log_print(
-            "<chronik unknown>",
-            file!(),
-            line!(),
-            &format!($($arg)*),
-        );
+        chronik_util::log_record(&chronik_util::LogRecord {
+            level: chronik_util::LogLevel::Info,
+            file: file!(),
+            line: line!(),
+            msg: &format!($($arg)*),
+        });
         #[cfg(test)]
         println!($($arg)*);
     };
 }
 
-/// Logs the message to bitcoind's logging system under the BCLog::Chronik
-/// category:
+/// Logs the message to the installed [`LogSink`] with [`LogLevel::Debug`], by
+/// default to bitcoind's logging system under the BCLog::Chronik category:
 ///
 /// ```ignore
 /// let world = "world";
@@ -38,13 +267,146 @@ macro_rules! log {
 macro_rules! log_chronik {
     ($($arg:tt)*) => {
         #[cfg(not(test))]
-        chronik_util::log_print_chronik(
-            "<chronik unknown>",
-            file!(),
-            line!(),
-            &format!($($arg)*),
-        );
+        chronik_util::log_record(&chronik_util::LogRecord {
+            level: chronik_util::LogLevel::Debug,
+            file: file!(),
+            line: line!(),
+            msg: &format!($($arg)*),
+        });
         #[cfg(test)]
         println!($($arg)*);
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        init_log_sink, log_record, request_reopen_log_sink, JsonLinesLogSink,
+        LogLevel, LogRecord, LogSink,
+    };
+
+    #[test]
+    fn test_json_lines_sink() {
+        let sink = JsonLinesLogSink::new(Vec::new());
+        sink.log(&LogRecord {
+            level: LogLevel::Info,
+            file: "src/a.rs",
+            line: 3,
+            msg: "Hello \"world\"\n",
+        });
+        sink.log(&LogRecord {
+            level: LogLevel::Debug,
+            file: "src\\b.rs",
+            line: 42,
+            msg: "\t\u{1}",
+        });
+        assert_eq!(
+            String::from_utf8(sink.into_inner()).unwrap(),
+            "{\"level\":\"info\",\"file\":\"src/a.rs\",\"line\":3,\
+             \"msg\":\"Hello \\\"world\\\"\\n\"}\n\
+             {\"level\":\"debug\",\"file\":\"src\\\\b.rs\",\"line\":42,\
+             \"msg\":\"\\t\\u0001\"}\n",
+        );
+    }
+
+    #[test]
+    fn test_json_lines_sink_reopen() {
+        #[derive(Clone, Default)]
+        struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+        impl std::io::Write for SharedBuf {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                std::io::Write::write(&mut *self.0.lock().unwrap(), buf)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let contents = |buf: &SharedBuf| {
+            String::from_utf8(buf.0.lock().unwrap().clone()).unwrap()
+        };
+
+        let first = SharedBuf::default();
+        let second = SharedBuf::default();
+        let num_reopens = Arc::new(Mutex::new(0));
+        let sink = JsonLinesLogSink::with_reopen(first.clone(), {
+            let second = second.clone();
+            let num_reopens = Arc::clone(&num_reopens);
+            Box::new(move || {
+                let mut num_reopens = num_reopens.lock().unwrap();
+                *num_reopens += 1;
+                match *num_reopens {
+                    1 => Ok(second.clone()),
+                    _ => Err(std::io::ErrorKind::NotFound.into()),
+                }
+            })
+        });
+        let record = |msg| LogRecord {
+            level: LogLevel::Info,
+            file: "a.rs",
+            line: 1,
+            msg,
+        };
+        let line = |msg: &str| {
+            format!(
+                "{{\"level\":\"info\",\"file\":\"a.rs\",\"line\":1,\
+                 \"msg\":\"{msg}\"}}\n"
+            )
+        };
+
+        sink.log(&record("before"));
+        sink.reopen();
+        sink.log(&record("after"));
+        assert_eq!(contents(&first), line("before"));
+        assert_eq!(contents(&second), line("after"));
+
+        // Failing to reopen keeps the current writer
+        sink.reopen();
+        sink.log(&record("failed"));
+        assert_eq!(*num_reopens.lock().unwrap(), 2);
+        assert_eq!(contents(&second), line("after") + &line("failed"));
+
+        // Sinks without reopen callback ignore reopening
+        let sink = JsonLinesLogSink::new(Vec::new());
+        sink.reopen();
+        sink.log(&record("x"));
+        assert_eq!(String::from_utf8(sink.into_inner()).unwrap(), line("x"));
+    }
+
+    #[test]
+    fn test_init_log_sink() {
+        struct RecordingSink(Arc<Mutex<Vec<String>>>);
+        impl LogSink for RecordingSink {
+            fn log(&self, record: &LogRecord<'_>) {
+                self.0.lock().unwrap().push(record.msg.to_string());
+            }
+
+            fn reopen(&self) {
+                self.0.lock().unwrap().push("reopen".to_string());
+            }
+        }
+
+        let msgs = Arc::new(Mutex::new(Vec::new()));
+        let sink = RecordingSink(Arc::clone(&msgs));
+        init_log_sink(Box::new(sink), LogLevel::Info);
+        let record = |level, msg| LogRecord {
+            level,
+            file: file!(),
+            line: line!(),
+            msg,
+        };
+        log_record(&record(LogLevel::Info, "info"));
+        log_record(&record(LogLevel::Debug, "debug"));
+        assert_eq!(*msgs.lock().unwrap(), vec!["info".to_string()]);
+
+        // Requested reopen happens right before logging the next record
+        request_reopen_log_sink();
+        log_record(&record(LogLevel::Info, "after reopen"));
+        log_record(&record(LogLevel::Info, "no reopen"));
+        assert_eq!(
+            *msgs.lock().unwrap(),
+            vec!["info", "reopen", "after reopen", "no reopen"],
+        );
+    }
+}
@@ -369,6 +369,9 @@ static void HandleSIGTERM(int) {
 
 static void HandleSIGHUP(int) {
     LogInstance().m_reopen_file = true;
+#if ENABLE_CHRONIK
+    chronik::RequestReopenLogFile();
+#endif
 }
 #else
 static BOOL WINAPI consoleCtrlHandler(DWORD dwCtrlType) {
@@ -650,6 +653,49 @@ void SetupServerArgs(NodeContext &node) {
                    "Reindex the Chronik indexer from genesis, but leave the "
                    "other indexes untouched",
                    ArgsManager::ALLOW_BOOL, OptionsCategory::CHRONIK);
+    argsman.AddArg("-chronikvalidateonly",
+                   "Only validate the Chronik configuration (bind addresses, "
+                   "datadir and database), then shut down without indexing",
+                   ArgsManager::ALLOW_BOOL, OptionsCategory::CHRONIK);
+    argsman.AddArg(
+        "-chronikconflictretention=<n>",
+        strprintf("Number of blocks for which Chronik keeps mempool conflicts "
+                  "around after they have been resolved (default: %d)",
+                  chronik::DEFAULT_CONFLICT_RETENTION_BLOCKS),
+        ArgsManager::ALLOW_INT, OptionsCategory::CHRONIK);
+    argsman.AddArg(
+        "-chronikscriptfilter=<hex>",
+        "Only index the tx history and UTXOs of the given script, in hex. "
+        "Blocks and txs are still indexed in full. This option can be "
+        "specified multiple times. Changing the scripts requires "
+        "-chronikreindex (default: index all scripts)",
+        ArgsManager::ALLOW_STRING, OptionsCategory::CHRONIK);
+    argsman.AddArg(
+        "-chronikrequesttimeout=<n>",
+        strprintf("Number of seconds a Chronik HTTP request may take before "
+                  "it is answered with 504 Gateway Timeout. Database queries "
+                  "that already started still run to completion (default: "
+                  "%d)",
+                  chronik::DEFAULT_REQUEST_TIMEOUT_SECS),
+        ArgsManager::ALLOW_INT, OptionsCategory::CHRONIK);
+    argsman.AddArg(
+        "-chronikroutetimeout=<route>=<n>",
+        "Override -chronikrequesttimeout with <n> seconds for the given "
+        "Chronik route, e.g. /blocks/:start/:end=60. This option can be "
+        "specified multiple times",
+        ArgsManager::ALLOW_STRING, OptionsCategory::CHRONIK);
+    argsman.AddArg("-chroniklogfile=<file>",
+                   "Write Chronik's log messages as JSON lines to <file> "
+                   "instead of debug.log. Relative paths will be prefixed by "
+                   "a net-specific datadir location. The file is reopened on "
+                   "SIGHUP, e.g. for log rotation (default: log to debug.log)",
+                   ArgsManager::ALLOW_STRING,
+                   OptionsCategory::CHRONIK);
+    argsman.AddArg("-chronikdebugstats",
+                   "Serve internal counters of Chronik for debugging at "
+                   "/debug/stats (default: 0)",
+                   ArgsManager::ALLOW_BOOL | ArgsManager::DEBUG_ONLY,
+                   OptionsCategory::CHRONIK);
 #endif
     argsman.AddArg(
         "-blockfilterindex=<type>",
@@ -2667,6 +2713,9 @@ bool AppInitMain(Config &config, RPCServer &rpcServer,
         if (!chronik::Start(config, node, fReindexChronik)) {
             return false;
         }
+        if (args.GetBoolArg("-chronikvalidateonly", false)) {
+            StartShutdown();
+        }
     }
 #endif
 
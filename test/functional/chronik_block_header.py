@@ -0,0 +1,87 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik's /block-header endpoint.
+"""
+
+from test_framework.address import ADDRESS_ECREG_P2SH_OP_TRUE, ADDRESS_ECREG_UNSPENDABLE
+from test_framework.blocktools import GENESIS_BLOCK_HASH
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal
+
+
+class ChronikBlockHeaderTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient, pb
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        def expected_header(block_hash, height, tip_height):
+            return pb.BlockHeader(
+                raw_header=bytes.fromhex(node.getblockheader(block_hash, False)),
+                height=height,
+                num_confirmations=tip_height - height + 1,
+                is_final=False,
+            )
+
+        # Not a valid hash or height
+        assert_equal(
+            chronik.block_header("1234f").err(400).msg,
+            "400: Not a hash or height: 1234f",
+        )
+
+        # Genesis block by height or hash
+        genesis_header = expected_header(GENESIS_BLOCK_HASH, 0, 0)
+        assert_equal(len(genesis_header.raw_header), 80)
+        assert_equal(chronik.block_header(0).ok(), genesis_header)
+        assert_equal(chronik.block_header(GENESIS_BLOCK_HASH).ok(), genesis_header)
+
+        # Unknown blocks
+        assert_equal(chronik.block_header(1).err(404).msg, "404: Block not found: 1")
+        assert_equal(
+            chronik.block_header("00" * 32).err(404).msg,
+            f'404: Block not found: {"00"*32}',
+        )
+
+        block_hashes = [GENESIS_BLOCK_HASH] + self.generatetoaddress(
+            node, 10, ADDRESS_ECREG_P2SH_OP_TRUE
+        )
+        for height, block_hash in enumerate(block_hashes):
+            header = expected_header(block_hash, height, 10)
+            assert_equal(chronik.block_header(height).ok(), header)
+            assert_equal(chronik.block_header(block_hash).ok(), header)
+
+        # Orphaned blocks are not found anymore, neither by height nor by hash
+        node.invalidateblock(block_hashes[5])
+        for height in range(5, 11):
+            assert_equal(
+                chronik.block_header(height).err(404).msg,
+                f"404: Block not found: {height}",
+            )
+            assert_equal(
+                chronik.block_header(block_hashes[height]).err(404).msg,
+                f"404: Block not found: {block_hashes[height]}",
+            )
+
+        # Fork block replaces the orphaned block at its height
+        fork_hash = self.generatetoaddress(node, 1, ADDRESS_ECREG_UNSPENDABLE)[0]
+        assert_equal(chronik.block_header(5).ok(), expected_header(fork_hash, 5, 5))
+        assert_equal(
+            chronik.block_header(0).ok(),
+            expected_header(GENESIS_BLOCK_HASH, 0, 5),
+        )
+
+
+if __name__ == "__main__":
+    ChronikBlockHeaderTest().main()
@@ -150,9 +150,11 @@ class ChronikBlockTxsTest(BitcoinTestFramework):
                 pb.TxOutput(
                     value=coinvalue,
                     output_script=bytes(tx_coinbase.vout[0].scriptPubKey),
+                    output_script_type="p2pk",
                 ),
                 pb.TxOutput(
                     output_script=bytes(CScript([OP_RETURN])),
+                    output_script_type="nulldata",
                 ),
             ],
             lock_time=0,
@@ -177,6 +179,7 @@ class ChronikBlockTxsTest(BitcoinTestFramework):
                 pb.TxOutput(
                     value=coinvalue - 10000,
                     output_script=bytes(P2SH_OP_TRUE),
+                    output_script_type="p2sh",
                     spent_by=pb.SpentBy(
                         txid=bytes.fromhex(tx2.hash)[::-1],
                         input_idx=0,
@@ -185,6 +188,7 @@ class ChronikBlockTxsTest(BitcoinTestFramework):
                 pb.TxOutput(
                     value=1000,
                     output_script=bytes(CScript([OP_RETURN, b"test"])),
+                    output_script_type="nulldata",
                 ),
             ],
             lock_time=0,
@@ -208,10 +212,12 @@ class ChronikBlockTxsTest(BitcoinTestFramework):
                 pb.TxOutput(
                     value=3000,
                     output_script=bytes(CScript([OP_RETURN, b"test"])),
+                    output_script_type="nulldata",
                 ),
                 pb.TxOutput(
                     value=coinvalue - 20000,
                     output_script=bytes(P2SH_OP_TRUE),
+                    output_script_type="p2sh",
                 ),
             ],
             lock_time=0,
@@ -0,0 +1,89 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik's /tx/broadcast endpoint.
+"""
+
+from test_framework.address import (
+    ADDRESS_ECREG_P2SH_OP_TRUE,
+    ADDRESS_ECREG_UNSPENDABLE,
+    P2SH_OP_TRUE,
+    SCRIPTSIG_OP_TRUE,
+)
+from test_framework.messages import COutPoint, CTransaction, CTxIn, CTxOut
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal
+
+
+class ChronikBroadcastTxTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        # Not a tx
+        error = chronik.broadcast_tx(b"\x01\x02").err(400)
+        assert error.msg.startswith("400: Broadcast failed: "), error.msg
+
+        coinblockhash = self.generatetoaddress(node, 1, ADDRESS_ECREG_P2SH_OP_TRUE)[0]
+        coinblock = node.getblock(coinblockhash)
+        cointx = coinblock["tx"][0]
+        self.generatetoaddress(node, 100, ADDRESS_ECREG_UNSPENDABLE)
+
+        tx = CTransaction()
+        tx.vin = [
+            CTxIn(
+                outpoint=COutPoint(int(cointx, 16), 0),
+                scriptSig=SCRIPTSIG_OP_TRUE,
+            )
+        ]
+        tx.vout = [CTxOut(5000000000 - 10000, P2SH_OP_TRUE)]
+        tx.rehash()
+
+        # Trailing data is rejected
+        error = chronik.broadcast_tx(tx.serialize() + b"\x00").err(400)
+        assert_equal(
+            error.msg, "400: Broadcast failed: Unexpected trailing data after tx"
+        )
+
+        # Spending a non-existent coin
+        bad_tx = CTransaction()
+        bad_tx.vin = [
+            CTxIn(outpoint=COutPoint(0xDEADBEEF, 0), scriptSig=SCRIPTSIG_OP_TRUE)
+        ]
+        bad_tx.vout = [CTxOut(1000, P2SH_OP_TRUE)]
+        error = chronik.broadcast_tx(bad_tx.serialize()).err(400)
+        assert error.msg.startswith("400: Broadcast failed: Missing inputs"), error.msg
+
+        # Valid tx is accepted and shows up in the node's mempool and in Chronik
+        response = chronik.broadcast_tx(tx.serialize()).ok()
+        assert_equal(response.txid[::-1].hex(), tx.hash)
+        assert_equal(node.getrawmempool(), [tx.hash])
+        node.syncwithvalidationinterfacequeue()
+        assert_equal(chronik.tx(tx.hash).ok().txid[::-1].hex(), tx.hash)
+
+        # Broadcasting it again is fine while it's still in the mempool
+        response = chronik.broadcast_tx(tx.serialize()).ok()
+        assert_equal(response.txid[::-1].hex(), tx.hash)
+
+        # Once mined, it's rejected
+        self.generatetoaddress(node, 1, ADDRESS_ECREG_UNSPENDABLE)
+        assert_equal(
+            chronik.broadcast_tx(tx.serialize()).err(400).msg,
+            "400: Broadcast failed: Transaction already in block chain",
+        )
+
+
+if __name__ == "__main__":
+    ChronikBroadcastTxTest().main()
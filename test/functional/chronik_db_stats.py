@@ -0,0 +1,60 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik's /db-stats endpoint.
+"""
+
+from test_framework.address import ADDRESS_ECREG_P2SH_OP_TRUE
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal, assert_greater_than
+
+
+class ChronikDbStatsTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        stats = chronik.db_stats().ok()
+        assert_equal(
+            sorted(stats.num_keys_per_column.keys()),
+            sorted(
+                [
+                    "blk",
+                    "blk_by_first_tx",
+                    "blk_stats",
+                    "first_tx_by_blk",
+                    "lookup_blk_by_hash",
+                    "lookup_tx_by_hash",
+                    "meta",
+                    "script_history",
+                    "script_utxo",
+                    "spent_by",
+                    "tx",
+                ]
+            ),
+        )
+        # Genesis block is indexed
+        assert_greater_than(stats.num_keys_per_column["blk"], 0)
+        assert_greater_than(stats.approx_size_bytes, 0)
+
+        self.generatetoaddress(node, 10, ADDRESS_ECREG_P2SH_OP_TRUE)
+        new_stats = chronik.db_stats().ok()
+        assert_greater_than(
+            new_stats.num_keys_per_column["tx"], stats.num_keys_per_column["tx"]
+        )
+
+
+if __name__ == "__main__":
+    ChronikDbStatsTest().main()
@@ -0,0 +1,56 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik's /debug/stats endpoint, which is only served with
+-chronikdebugstats.
+"""
+
+from test_framework.address import ADDRESS_ECREG_P2SH_OP_TRUE
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal, assert_greater_than
+
+
+class ChronikDebugStatsTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        # Disabled by default
+        assert_equal(
+            chronik.debug_stats().err(404).msg, "404: Not found: /debug/stats"
+        )
+
+        self.generatetoaddress(node, 10, ADDRESS_ECREG_P2SH_OP_TRUE)
+        self.restart_node(0, ["-chronik", "-chronikdebugstats"])
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        # Blocks were already indexed, so the resync had nothing to do
+        stats = chronik.debug_stats().ok()
+        assert_equal(stats.resync_start_height, 10)
+        assert_equal(stats.resync_height, 10)
+        assert_equal(stats.resync_tip_height, 10)
+        assert_equal(stats.num_queued_events, 0)
+        assert_greater_than(stats.size_bytes_per_column["blk"], 0)
+
+        # Each connected block takes the write lock once indexed
+        self.generatetoaddress(node, 2, ADDRESS_ECREG_P2SH_OP_TRUE)
+        self.wait_until(lambda: chronik.debug_stats().ok().num_write_lock_waits >= 2)
+        stats = chronik.debug_stats().ok()
+        assert_greater_than(stats.max_queued_events, 0)
+        assert_equal(stats.resync_tip_height, 10)
+
+
+if __name__ == "__main__":
+    ChronikDebugStatsTest().main()
@@ -0,0 +1,77 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik writes its log messages as JSON lines to -chroniklogfile, and
+reopens the file on SIGHUP so it can be rotated.
+"""
+
+import json
+import os
+import signal
+
+from test_framework.test_framework import BitcoinTestFramework
+
+
+def read_log_msgs(path):
+    with open(path, encoding="utf-8") as f:
+        return [json.loads(line)["msg"] for line in f]
+
+
+class ChronikLogFileTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [
+            ["-chronik", "-chroniklogfile=chronik.log", "-debug=chronik"]
+        ]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+        log_path = os.path.join(node.datadir, self.chain, "chronik.log")
+        rotated_path = log_path + ".1"
+
+        # Relative paths are placed in the net-specific datadir
+        assert any(
+            msg.startswith("Starting Chronik bound to")
+            for msg in read_log_msgs(log_path)
+        )
+
+        # Rotate the log file, Chronik keeps writing to the renamed file
+        # until it gets a SIGHUP
+        os.rename(log_path, rotated_path)
+        ws = chronik.ws(timeout=30)
+        ws.sub_to_blocks()
+        self.wait_until(
+            lambda: "WS subscribe to blocks\n" in read_log_msgs(rotated_path)
+        )
+        assert not os.path.exists(log_path)
+
+        node.process.send_signal(signal.SIGHUP)
+        ws.sub_to_blocks(is_unsub=True)
+        self.wait_until(lambda: os.path.exists(log_path))
+        self.wait_until(
+            lambda: read_log_msgs(log_path) == ["WS unsubscribe from blocks\n"]
+        )
+        assert "WS unsubscribe from blocks\n" not in read_log_msgs(rotated_path)
+
+        # Without -debug=chronik, debug messages are not written
+        self.restart_node(0, ["-chronik", "-chroniklogfile=chronik.log"])
+        ws = chronik.ws(timeout=30)
+        ws.sub_to_blocks()
+        ws.sub_to_blocks(is_unsub=True)
+        self.generate(node, 1)
+        msgs = read_log_msgs(log_path)
+        assert "WS subscribe to blocks\n" not in msgs
+        assert "WS unsubscribe from blocks\n" not in msgs
+
+
+if __name__ == "__main__":
+    ChronikLogFileTest().main()
@@ -0,0 +1,74 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik's /mempool-info endpoint.
+"""
+
+from test_framework.address import (
+    ADDRESS_ECREG_P2SH_OP_TRUE,
+    ADDRESS_ECREG_UNSPENDABLE,
+    P2SH_OP_TRUE,
+    SCRIPTSIG_OP_TRUE,
+)
+from test_framework.messages import COutPoint, CTransaction, CTxIn, CTxOut
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal
+
+
+class ChronikMempoolInfoTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient, pb
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        assert_equal(
+            chronik.mempool_info().ok(), pb.MempoolInfo(tx_count=0, total_size=0)
+        )
+
+        coinblockhash = self.generatetoaddress(node, 1, ADDRESS_ECREG_P2SH_OP_TRUE)[0]
+        coinblock = node.getblock(coinblockhash)
+        cointx = coinblock["tx"][0]
+        self.generatetoaddress(node, 100, ADDRESS_ECREG_UNSPENDABLE)
+
+        # Chain of two txs, the second spending the first
+        prev_txid = cointx
+        value = 5000000000
+        total_size = 0
+        for tx_count in range(1, 3):
+            value -= 10000
+            tx = CTransaction()
+            tx.vin = [
+                CTxIn(
+                    outpoint=COutPoint(int(prev_txid, 16), 0),
+                    scriptSig=SCRIPTSIG_OP_TRUE,
+                )
+            ]
+            tx.vout = [CTxOut(value, P2SH_OP_TRUE)]
+            raw_tx = tx.serialize()
+            prev_txid = node.sendrawtransaction(raw_tx.hex())
+            total_size += len(raw_tx)
+            assert_equal(
+                chronik.mempool_info().ok(),
+                pb.MempoolInfo(tx_count=tx_count, total_size=total_size),
+            )
+
+        # Mining the txs clears the mempool
+        self.generatetoaddress(node, 1, ADDRESS_ECREG_UNSPENDABLE)
+        assert_equal(
+            chronik.mempool_info().ok(), pb.MempoolInfo(tx_count=0, total_size=0)
+        )
+
+
+if __name__ == "__main__":
+    ChronikMempoolInfoTest().main()
@@ -0,0 +1,105 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik's /raw-block/:hash_or_height endpoint, including byte ranges.
+"""
+
+import http.client
+
+from test_framework.address import ADDRESS_ECREG_P2SH_OP_TRUE
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal
+
+
+class ChronikRawBlockTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import pb
+
+        node = self.nodes[0]
+
+        def query_raw_block(hash_or_height, byte_range=None):
+            client = http.client.HTTPConnection(
+                "127.0.0.1", node.chronik_port, timeout=30
+            )
+            headers = {}
+            if byte_range is not None:
+                headers["Range"] = byte_range
+            client.request("GET", f"/raw-block/{hash_or_height}", headers=headers)
+            response = client.getresponse()
+            return response, response.read()
+
+        block_hash = self.generatetoaddress(node, 1, ADDRESS_ECREG_P2SH_OP_TRUE)[0]
+        raw_block = bytes.fromhex(node.getblock(block_hash, 0))
+        size = len(raw_block)
+
+        # Full block, by hash and by height
+        for hash_or_height in [block_hash, 1]:
+            response, body = query_raw_block(hash_or_height)
+            assert_equal(response.status, 200)
+            assert_equal(response.getheader("Content-Type"), "application/octet-stream")
+            assert_equal(response.getheader("Accept-Ranges"), "bytes")
+            assert_equal(response.getheader("Content-Range"), None)
+            assert_equal(body, raw_block)
+
+        # Genesis block
+        response, body = query_raw_block(0)
+        assert_equal(response.status, 200)
+        assert_equal(body, bytes.fromhex(node.getblock(node.getblockhash(0), 0)))
+
+        # Partial responses for byte ranges
+        for byte_range, start, end in [
+            # Only the header
+            ("bytes=0-79", 0, 80),
+            # Everything after the header
+            ("bytes=80-", 80, size),
+            # Last 10 bytes
+            ("bytes=-10", size - 10, size),
+            # End past the size is clamped
+            (f"bytes={size - 5}-{size + 100}", size - 5, size),
+        ]:
+            response, body = query_raw_block(block_hash, byte_range)
+            assert_equal(response.status, 206)
+            assert_equal(
+                response.getheader("Content-Range"),
+                f"bytes {start}-{end - 1}/{size}",
+            )
+            assert_equal(response.getheader("Accept-Ranges"), "bytes")
+            assert_equal(body, raw_block[start:end])
+
+        # Ranges that can't be served
+        for byte_range in [
+            f"bytes={size}-",
+            f"bytes={size + 10}-{size + 20}",
+            "bytes=-0",
+        ]:
+            response, body = query_raw_block(block_hash, byte_range)
+            assert_equal(response.status, 416)
+            assert_equal(response.getheader("Content-Range"), f"bytes */{size}")
+            assert_equal(body, b"")
+
+        # Multiple or invalid ranges are ignored and the full block is served
+        for byte_range in ["bytes=0-1,5-6", "bytes=abc", "items=0-1", "bytes=5-1"]:
+            response, body = query_raw_block(block_hash, byte_range)
+            assert_equal(response.status, 200)
+            assert_equal(body, raw_block)
+
+        # Unknown block
+        response, body = query_raw_block("00" * 32)
+        assert_equal(response.status, 404)
+        error = pb.Error()
+        error.ParseFromString(body)
+        assert_equal(error.msg, f"404: Block not found: {'00' * 32}")
+
+
+if __name__ == "__main__":
+    ChronikRawBlockTest().main()
@@ -0,0 +1,56 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik's /recent-blocks/:num_blocks endpoint.
+"""
+
+from test_framework.address import ADDRESS_ECREG_P2SH_OP_TRUE
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal
+
+
+class ChronikRecentBlocksTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        # Only genesis is indexed
+        blocks = chronik.recent_blocks(10).ok().blocks
+        assert_equal([block.height for block in blocks], [0])
+
+        self.generatetoaddress(node, 5, ADDRESS_ECREG_P2SH_OP_TRUE)
+
+        # Tip first, walking back
+        blocks = chronik.recent_blocks(3).ok().blocks
+        assert_equal([block.height for block in blocks], [5, 4, 3])
+        assert_equal(blocks[0].hash[::-1].hex(), node.getbestblockhash())
+        assert_equal(blocks[0].prev_hash, blocks[1].hash)
+        assert_equal(blocks[0].num_txs, 1)
+
+        # Same blocks as /blocks, in reverse
+        assert_equal(
+            list(chronik.recent_blocks(6).ok().blocks),
+            list(reversed(chronik.blocks(0, 5).ok().blocks)),
+        )
+        assert_equal(len(chronik.recent_blocks(0).ok().blocks), 0)
+
+        assert_equal(
+            chronik.recent_blocks(501).err(400).msg,
+            "400: Blocks page size too large, may not be above 500 but got 501",
+        )
+
+
+if __name__ == "__main__":
+    ChronikRecentBlocksTest().main()
@@ -0,0 +1,129 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik's /script/:type/:payload/confirmed-txs-cursor endpoint.
+"""
+
+from test_framework.address import (
+    ADDRESS_ECREG_P2SH_OP_TRUE,
+    ADDRESS_ECREG_UNSPENDABLE,
+    P2SH_OP_TRUE,
+)
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal
+
+
+class ChronikScriptConfirmedTxsCursorTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient, pb
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+        script = chronik.script("p2sh", P2SH_OP_TRUE[2:22].hex())
+
+        assert_equal(
+            script.confirmed_txs_cursor().ok(),
+            pb.TxHistoryCursorPage(txs=[], next_cursor=""),
+        )
+        assert_equal(
+            script.confirmed_txs_cursor(cursor="abc").err(400).msg,
+            "400: Invalid param cursor: abc, expected <tx num>:<block hash>",
+        )
+        assert_equal(
+            script.confirmed_txs_cursor(cursor="abc:def").err(400).msg,
+            "400: Invalid param cursor: abc:def, invalid tx num, invalid digit "
+            + "found in string",
+        )
+        assert_equal(
+            script.confirmed_txs_cursor(cursor="0:abcd").err(400).msg,
+            "400: Invalid param cursor: 0:abcd, invalid block hash, Invalid "
+            + "length, expected 32 bytes but got 2 bytes",
+        )
+        assert_equal(
+            script.confirmed_txs_cursor(page_size=0).err(400).msg,
+            "400: Requested page size 0 is too small, minimum is 1",
+        )
+        assert_equal(
+            script.confirmed_txs_cursor(page_size=201).err(400).msg,
+            "400: Requested page size 201 is too big, maximum is 200",
+        )
+
+        first_blockhashes = self.generatetoaddress(
+            node, 20, ADDRESS_ECREG_P2SH_OP_TRUE
+        )
+        cb_txids = [
+            node.getblock(blockhash)["tx"][0] for blockhash in first_blockhashes
+        ]
+
+        def txids(page):
+            return [tx.txid[::-1].hex() for tx in page.txs]
+
+        # Fetch the first two pages
+        page = script.confirmed_txs_cursor(page_size=7).ok()
+        assert_equal(txids(page), cb_txids[:7])
+        page = script.confirmed_txs_cursor(cursor=page.next_cursor, page_size=7).ok()
+        assert_equal(txids(page), cb_txids[7:14])
+
+        # New blocks don't affect the pages following the cursor
+        blockhashes = self.generatetoaddress(node, 5, ADDRESS_ECREG_P2SH_OP_TRUE)
+        cb_txids += [node.getblock(blockhash)["tx"][0] for blockhash in blockhashes]
+        page = script.confirmed_txs_cursor(cursor=page.next_cursor, page_size=7).ok()
+        assert_equal(txids(page), cb_txids[14:21])
+        page = script.confirmed_txs_cursor(cursor=page.next_cursor, page_size=7).ok()
+        assert_equal(txids(page), cb_txids[21:25])
+        assert_equal(page.next_cursor, "")
+
+        # Page ending exactly at the last tx has no next cursor
+        page = script.confirmed_txs_cursor(page_size=25).ok()
+        assert_equal(txids(page), cb_txids)
+        assert_equal(page.next_cursor, "")
+
+        # Paging through with different page sizes returns all txs exactly once
+        for page_size in [1, 2, 3, 24, 200]:
+            all_txids = []
+            cursor = None
+            while True:
+                page = script.confirmed_txs_cursor(
+                    cursor=cursor, page_size=page_size
+                ).ok()
+                all_txids += txids(page)
+                if page.next_cursor == "":
+                    break
+                cursor = page.next_cursor
+            assert_equal(all_txids, cb_txids)
+
+        # The cursor is the tx num and block hash of the last tx of the page,
+        # here the coinbase at height 20 (tx num 0 is the genesis coinbase)
+        page = script.confirmed_txs_cursor(page_size=20).ok()
+        assert_equal(txids(page), cb_txids[:20])
+        assert_equal(page.next_cursor, f"20:{first_blockhashes[19]}")
+
+        # Cursors into blocks that have been reorged out are rejected
+        node.invalidateblock(first_blockhashes[19])
+        self.generatetoaddress(node, 10, ADDRESS_ECREG_UNSPENDABLE)
+        assert_equal(
+            script.confirmed_txs_cursor(cursor=page.next_cursor).err(400).msg,
+            f"400: Cursor {page.next_cursor} is stale, its block is not on the "
+            + "chain anymore; restart from the first page",
+        )
+
+        # Cursors into blocks that are still on the chain remain valid
+        page = script.confirmed_txs_cursor(page_size=10).ok()
+        assert_equal(page.next_cursor, f"10:{first_blockhashes[9]}")
+        page = script.confirmed_txs_cursor(cursor=page.next_cursor).ok()
+        assert_equal(txids(page), cb_txids[10:19])
+        assert_equal(page.next_cursor, "")
+
+
+if __name__ == "__main__":
+    ChronikScriptConfirmedTxsCursorTest().main()
@@ -0,0 +1,111 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik only indexes the scripts given with -chronikscriptfilter, and
+refuses to open an index built with a different filter.
+"""
+
+import hashlib
+
+from test_framework.address import (
+    ADDRESS_ECREG_P2SH_OP_TRUE,
+    ADDRESS_ECREG_UNSPENDABLE,
+    P2SH_OP_TRUE,
+)
+from test_framework.messages import ser_string_vector
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal
+
+P2PKH_UNSPENDABLE = "76a914" + "00" * 20 + "88ac"
+
+
+def fingerprint(*scripts_hex):
+    scripts = sorted({bytes.fromhex(script) for script in scripts_hex})
+    return hashlib.sha256(ser_string_vector(scripts)).hexdigest()
+
+
+class ChronikScriptFilterTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik", f"-chronikscriptfilter={P2SH_OP_TRUE.hex()}"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        self.generatetoaddress(node, 10, ADDRESS_ECREG_P2SH_OP_TRUE)
+        self.generatetoaddress(node, 5, ADDRESS_ECREG_UNSPENDABLE)
+
+        # Only the history of the filtered script is indexed
+        p2sh_hash = P2SH_OP_TRUE.hex()[4:44]
+        assert_equal(chronik.script("p2sh", p2sh_hash).history().ok().num_txs, 10)
+        assert_equal(
+            chronik.script("p2pkh", "00" * 20).history().ok().num_txs,
+            0,
+        )
+
+        # Blocks and txs are still indexed in full
+        chronik.block(15).ok()
+
+        node.stop_node()
+
+        # Invalid scripts are rejected
+        node.assert_start_raises_init_error(
+            ["-chronik", "-chronikscriptfilter=zz"],
+            'Error: Invalid -chronikscriptfilter "zz", must be a script in hex',
+        )
+
+        # Opening the index with a different filter fails
+        node.assert_start_raises_init_error(
+            ["-chronik", f"-chronikscriptfilter={P2PKH_UNSPENDABLE}"],
+            "Error: Chronik database has been built with script filter "
+            + f"{fingerprint(P2SH_OP_TRUE.hex())}, but the node uses script "
+            + f"filter {fingerprint(P2PKH_UNSPENDABLE)}. Check the script "
+            + "filter, or -reindex/-chronikreindex to rebuild the database.",
+        )
+        node.assert_start_raises_init_error(
+            ["-chronik"],
+            "Error: Chronik database has been built with script filter "
+            + f"{fingerprint(P2SH_OP_TRUE.hex())}, but the node uses script "
+            + "filter none. Check the script filter, or -reindex/"
+            + "-chronikreindex to rebuild the database.",
+        )
+
+        # The order and duplicates of the scripts don't matter
+        self.start_node(
+            0,
+            [
+                "-chronik",
+                f"-chronikscriptfilter={P2SH_OP_TRUE.hex()}",
+                f"-chronikscriptfilter={P2SH_OP_TRUE.hex()}",
+            ],
+        )
+        node.stop_node()
+
+        # Reindexing allows changing the filter
+        self.start_node(
+            0,
+            [
+                "-chronik",
+                "-chronikreindex",
+                f"-chronikscriptfilter={P2PKH_UNSPENDABLE}",
+            ],
+        )
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+        assert_equal(chronik.script("p2sh", p2sh_hash).history().ok().num_txs, 0)
+        assert_equal(
+            chronik.script("p2pkh", "00" * 20).history().ok().num_txs,
+            5,
+        )
+
+
+if __name__ == "__main__":
+    ChronikScriptFilterTest().main()
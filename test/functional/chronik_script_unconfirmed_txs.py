@@ -164,10 +164,12 @@ class ChronikScriptUnconfirmedTxsTest(BitcoinTestFramework):
                         pb.TxOutput(
                             value=coinvalue - 1000,
                             output_script=bytes(P2SH_OP_TRUE),
+                            output_script_type="p2sh",
                         ),
                         pb.TxOutput(
                             value=0,
                             output_script=bytes(pad_script),
+                            output_script_type="nulldata",
                         ),
                     ],
                     lock_time=1,
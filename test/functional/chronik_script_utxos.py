@@ -96,6 +96,7 @@ class ChronikScriptUtxosTest(BitcoinTestFramework):
                         is_coinbase=True,
                         value=coinvalue,
                         is_final=False,
+                        is_mature=False,
                     )
                 ],
             ),
@@ -123,12 +124,24 @@ class ChronikScriptUtxosTest(BitcoinTestFramework):
                         is_coinbase=True,
                         value=coinvalue,
                         is_final=False,
+                        is_mature=False,
                     )
                 ],
             ),
         )
 
-        self.generatetoaddress(node, 100, ADDRESS_ECREG_UNSPENDABLE)
+        # Coinbase UTXO is mature after 100 blocks (can be spent in the next)
+        self.generatetoaddress(node, 98, ADDRESS_ECREG_UNSPENDABLE)
+        assert_equal(
+            chronik.script(script_type, payload_hex).utxos().ok().utxos[0].is_mature,
+            False,
+        )
+        self.generatetoaddress(node, 1, ADDRESS_ECREG_UNSPENDABLE)
+        assert_equal(
+            chronik.script(script_type, payload_hex).utxos().ok().utxos[0].is_mature,
+            True,
+        )
+        self.generatetoaddress(node, 1, ADDRESS_ECREG_UNSPENDABLE)
 
         # Make tx creating 4 UTXOs, spending the coinbase UTXO
         send_values = [coinvalue - 10000, 1000, 2000, 3000]
@@ -149,6 +162,7 @@ class ChronikScriptUtxosTest(BitcoinTestFramework):
                 is_coinbase=False,
                 value=value,
                 is_final=False,
+                is_mature=True,
             )
             for i, value in enumerate(send_values)
         ]
@@ -187,6 +201,7 @@ class ChronikScriptUtxosTest(BitcoinTestFramework):
                 is_coinbase=False,
                 value=2500,
                 is_final=False,
+                is_mature=True,
             )
         )
 
@@ -45,6 +45,37 @@ class ChronikServeTest(BitcoinTestFramework):
             test_host("127.0.0.1", 12345)
             test_host("::1", 23456)
 
+        # Requests are served with custom timeouts
+        self.restart_node(
+            0,
+            [
+                "-chronik",
+                "-chronikrequesttimeout=10",
+                "-chronikroutetimeout=/blockchain-info=5",
+            ],
+        )
+        test_host("127.0.0.1", self.nodes[0].chronik_port)
+
+        # Chronik refuses to start without any host to bind to
+        self.stop_node(0)
+        self.nodes[0].assert_start_raises_init_error(
+            ["-chronik", "-nochronikbind"],
+            "Error: Chronik is not bound to any host, specify at least one host "
+            + "using -chronikbind",
+        )
+
+        # Timeouts must be positive and routes must come with a timeout
+        self.nodes[0].assert_start_raises_init_error(
+            ["-chronik", "-chronikrequesttimeout=0"],
+            "Error: Invalid -chronikrequesttimeout 0, must be 1 or greater",
+        )
+        for arg in ["/blockchain-info", "/blockchain-info=0", "blockchain-info=5"]:
+            self.nodes[0].assert_start_raises_init_error(
+                ["-chronik", f"-chronikroutetimeout={arg}"],
+                f'Error: Invalid -chronikroutetimeout "{arg}", must be '
+                + "<route>=<seconds>, e.g. /blocks/:start/:end=60",
+            )
+
 
 if __name__ == "__main__":
     ChronikServeTest().main()
@@ -103,6 +103,7 @@ class ChronikTxTest(BitcoinTestFramework):
                 pb.TxOutput(
                     value=value,
                     output_script=bytes(script),
+                    output_script_type="p2sh",
                 )
                 for value, script in zip(send_values, send_scripts)
             ],
@@ -162,6 +163,7 @@ class ChronikTxTest(BitcoinTestFramework):
                 pb.TxOutput(
                     value=tx2.vout[0].nValue,
                     output_script=bytes(tx2.vout[0].scriptPubKey),
+                    output_script_type="p2sh",
                 )
             ],
             lock_time=12,
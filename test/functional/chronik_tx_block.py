@@ -0,0 +1,98 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik's /tx-block/:txid endpoint.
+"""
+
+from test_framework.address import (
+    ADDRESS_ECREG_P2SH_OP_TRUE,
+    ADDRESS_ECREG_UNSPENDABLE,
+    P2SH_OP_TRUE,
+    SCRIPTSIG_OP_TRUE,
+)
+from test_framework.blocktools import (
+    GENESIS_BLOCK_HASH,
+    GENESIS_CB_TXID,
+    TIME_GENESIS_BLOCK,
+)
+from test_framework.messages import COutPoint, CTransaction, CTxIn, CTxOut
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal
+
+
+class ChronikTxBlockTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient, pb
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        assert_equal(chronik.tx_block("0").err(400).msg, "400: Not a txid: 0")
+        assert_equal(
+            chronik.tx_block("00" * 32).err(404).msg,
+            f'404: Transaction {"00"*32} not found in the index',
+        )
+
+        assert_equal(
+            chronik.tx_block(GENESIS_CB_TXID).ok(),
+            pb.BlockMetadata(
+                height=0,
+                hash=bytes.fromhex(GENESIS_BLOCK_HASH)[::-1],
+                timestamp=TIME_GENESIS_BLOCK,
+            ),
+        )
+
+        coinblockhash = self.generatetoaddress(node, 1, ADDRESS_ECREG_P2SH_OP_TRUE)[0]
+        coinblock = node.getblock(coinblockhash)
+        cointx = coinblock["tx"][0]
+        self.generatetoaddress(node, 100, ADDRESS_ECREG_UNSPENDABLE)
+
+        tx = CTransaction()
+        tx.vin = [
+            CTxIn(
+                outpoint=COutPoint(int(cointx, 16), 0),
+                scriptSig=SCRIPTSIG_OP_TRUE,
+            )
+        ]
+        tx.vout = [CTxOut(5000000000 - 10000, P2SH_OP_TRUE)]
+        txid = node.sendrawtransaction(tx.serialize().hex())
+
+        # Mempool tx has no block yet
+        assert_equal(
+            chronik.tx_block(txid).err(404).msg,
+            f"404: Transaction {txid} not confirmed yet",
+        )
+
+        # Mine the tx
+        blockhash = self.generatetoaddress(node, 1, ADDRESS_ECREG_UNSPENDABLE)[0]
+        block = node.getblock(blockhash)
+        assert_equal(
+            chronik.tx_block(txid).ok(),
+            pb.BlockMetadata(
+                height=102,
+                hash=bytes.fromhex(blockhash)[::-1],
+                timestamp=block["time"],
+            ),
+        )
+
+        # Disconnecting the block moves the tx back into the mempool
+        node.invalidateblock(blockhash)
+        node.syncwithvalidationinterfacequeue()
+        assert_equal(
+            chronik.tx_block(txid).err(404).msg,
+            f"404: Transaction {txid} not confirmed yet",
+        )
+
+
+if __name__ == "__main__":
+    ChronikTxBlockTest().main()
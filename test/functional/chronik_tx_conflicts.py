@@ -0,0 +1,123 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik's /tx-conflicts/:txid endpoint.
+"""
+
+from test_framework.address import (
+    ADDRESS_ECREG_P2SH_OP_TRUE,
+    ADDRESS_ECREG_UNSPENDABLE,
+    P2SH_OP_TRUE,
+    SCRIPTSIG_OP_TRUE,
+)
+from test_framework.blocktools import (
+    create_block,
+    create_coinbase,
+    make_conform_to_ctor,
+)
+from test_framework.messages import COutPoint, CTransaction, CTxIn, CTxOut
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal, assert_raises_rpc_error
+
+
+class ChronikTxConflictsTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik", "-chronikconflictretention=2"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient, pb
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        assert_equal(chronik.tx_conflicts("0").err(400).msg, "400: Not a txid: 0")
+        # Unknown txs have no known conflicts
+        assert_equal(chronik.tx_conflicts("00" * 32).ok(), pb.TxConflicts())
+
+        coinblockhash = self.generatetoaddress(node, 1, ADDRESS_ECREG_P2SH_OP_TRUE)[0]
+        cointx = node.getblock(coinblockhash)["tx"][0]
+        self.generatetoaddress(node, 100, ADDRESS_ECREG_UNSPENDABLE)
+
+        def make_tx(amount):
+            tx = CTransaction()
+            tx.vin = [
+                CTxIn(
+                    outpoint=COutPoint(int(cointx, 16), 0),
+                    scriptSig=SCRIPTSIG_OP_TRUE,
+                )
+            ]
+            tx.vout = [CTxOut(amount, P2SH_OP_TRUE)]
+            return tx
+
+        tx = make_tx(5000000000 - 10000)
+        txid = node.sendrawtransaction(tx.serialize().hex())
+        assert_equal(chronik.tx_conflicts(txid).ok(), pb.TxConflicts())
+
+        # The node rejects double-spends of mempool txs, so Chronik never sees
+        # them and the mempool tx has no conflicts.
+        double_spend = make_tx(5000000000 - 20000)
+        assert_raises_rpc_error(
+            -26,
+            "txn-mempool-conflict",
+            node.sendrawtransaction,
+            double_spend.serialize().hex(),
+        )
+        assert_equal(chronik.tx_conflicts(txid).ok(), pb.TxConflicts())
+        assert_equal(
+            chronik.tx_conflicts(double_spend.rehash()).ok(),
+            pb.TxConflicts(),
+        )
+
+        # Mining the double-spend makes the node remove the mempool tx, and
+        # Chronik records the conflict for both txs
+        tip = node.getbestblockhash()
+        block = create_block(
+            int(tip, 16),
+            create_coinbase(node.getblockcount() + 1),
+            node.getblock(tip)["time"] + 1,
+        )
+        block.vtx.append(double_spend)
+        make_conform_to_ctor(block)
+        block.hashMerkleRoot = block.calc_merkle_root()
+        block.solve()
+        assert_equal(node.submitblock(block.serialize().hex()), None)
+        assert_equal(node.getbestblockhash(), block.hash)
+        assert txid not in node.getrawmempool()
+
+        def txid_bytes(txid_hex):
+            return bytes.fromhex(txid_hex)[::-1]
+
+        self.wait_until(
+            lambda: chronik.tx_conflicts(txid).ok()
+            == pb.TxConflicts(conflicting_txids=[txid_bytes(double_spend.hash)])
+        )
+        assert_equal(
+            chronik.tx_conflicts(double_spend.hash).ok(),
+            pb.TxConflicts(conflicting_txids=[txid_bytes(txid)]),
+        )
+
+        # Chronik kept running and indexed the block
+        assert_equal(
+            chronik.blockchain_info().ok().tip_hash, txid_bytes(block.hash)
+        )
+
+        # Resolved conflicts are kept for -chronikconflictretention blocks
+        self.generatetoaddress(node, 1, ADDRESS_ECREG_UNSPENDABLE)
+        assert_equal(
+            chronik.tx_conflicts(txid).ok(),
+            pb.TxConflicts(conflicting_txids=[txid_bytes(double_spend.hash)]),
+        )
+        self.generatetoaddress(node, 1, ADDRESS_ECREG_UNSPENDABLE)
+        assert_equal(chronik.tx_conflicts(txid).ok(), pb.TxConflicts())
+        assert_equal(chronik.tx_conflicts(double_spend.hash).ok(), pb.TxConflicts())
+
+
+if __name__ == "__main__":
+    ChronikTxConflictsTest().main()
@@ -0,0 +1,75 @@
+#!/usr/bin/env python3
+# Copyright (c) 2023 The Bitcoin developers
+# Distributed under the MIT software license, see the accompanying
+# file COPYING or http://www.opensource.org/licenses/mit-license.php.
+"""
+Test Chronik sends a snapshot of a script when subscribing with `snapshot` set.
+"""
+
+from test_framework.address import ADDRESS_ECREG_P2SH_OP_TRUE, P2SH_OP_TRUE
+from test_framework.test_framework import BitcoinTestFramework
+from test_framework.util import assert_equal
+
+
+class ChronikWsScriptSnapshotTest(BitcoinTestFramework):
+    def set_test_params(self):
+        self.setup_clean_chain = True
+        self.num_nodes = 1
+        self.extra_args = [["-chronik"]]
+
+    def skip_test_if_missing_module(self):
+        self.skip_if_no_chronik()
+
+    def run_test(self):
+        from test_framework.chronik.client import ChronikClient, pb
+
+        node = self.nodes[0]
+        chronik = ChronikClient("127.0.0.1", node.chronik_port)
+
+        script_hash = P2SH_OP_TRUE[2:22]
+        script_client = chronik.script("p2sh", script_hash.hex())
+
+        self.generatetoaddress(node, 3, ADDRESS_ECREG_P2SH_OP_TRUE)
+
+        # Snapshot is the same as querying the endpoints
+        ws = chronik.ws(timeout=30)
+        ws.sub_script("p2sh", script_hash, snapshot=True)
+        snapshot = ws.recv()
+        assert_equal(
+            snapshot,
+            pb.WsMsg(
+                script_snapshot=pb.MsgScriptSnapshot(
+                    script=pb.WsSubScript(
+                        script_type="p2sh", payload=script_hash, snapshot=True
+                    ),
+                    utxos=script_client.utxos().ok().utxos,
+                    history=script_client.history().ok(),
+                )
+            ),
+        )
+        assert_equal(len(snapshot.script_snapshot.utxos), 3)
+        assert_equal(snapshot.script_snapshot.utxos_truncated, False)
+        assert_equal(snapshot.script_snapshot.history.num_txs, 3)
+
+        # Updates are streamed after the snapshot
+        block_hash = self.generatetoaddress(node, 1, ADDRESS_ECREG_P2SH_OP_TRUE)[0]
+        coinbase_txid = node.getblock(block_hash)["tx"][0]
+        assert_equal(
+            ws.recv(),
+            pb.WsMsg(
+                tx=pb.MsgTx(
+                    msg_type=pb.TX_CONFIRMED,
+                    txid=bytes.fromhex(coinbase_txid)[::-1],
+                )
+            ),
+        )
+
+        # Without `snapshot`, no snapshot is sent
+        ws2 = chronik.ws(timeout=30)
+        ws2.sub_script("p2sh", script_hash)
+        self.generatetoaddress(node, 1, ADDRESS_ECREG_P2SH_OP_TRUE)
+        assert_equal(ws2.recv().WhichOneof("msg_type"), "tx")
+
+
+if __name__ == "__main__":
+    ChronikWsScriptSnapshotTest().main()
@@ -59,6 +59,18 @@ class ChronikScriptClient:
             pb.TxHistoryPage,
         )
 
+    def confirmed_txs_cursor(self, cursor=None, page_size=None):
+        params = []
+        if cursor is not None:
+            params.append(f"cursor={cursor}")
+        if page_size is not None:
+            params.append(f"page_size={page_size}")
+        query = "?" + "&".join(params) if params else ""
+        return self.client._request_get(
+            f"/script/{self.script_type}/{self.script_payload}/confirmed-txs-cursor{query}",
+            pb.TxHistoryCursorPage,
+        )
+
     def history(self, page=None, page_size=None):
         query = _page_query_params(page, page_size)
         return self.client._request_get(
@@ -95,10 +107,14 @@ class ChronikWs:
         sub = pb.WsSub(is_unsub=is_unsub, blocks=pb.WsSubBlocks())
         self.send_bytes(sub.SerializeToString())
 
-    def sub_script(self, script_type: str, payload: bytes, *, is_unsub=False) -> None:
+    def sub_script(
+        self, script_type: str, payload: bytes, *, is_unsub=False, snapshot=False
+    ) -> None:
         sub = pb.WsSub(
             is_unsub=is_unsub,
-            script=pb.WsSubScript(script_type=script_type, payload=payload),
+            script=pb.WsSubScript(
+                script_type=script_type, payload=payload, snapshot=snapshot
+            ),
         )
         self.send_bytes(sub.SerializeToString())
 
@@ -112,11 +128,20 @@ class ChronikClient:
         self.timeout = timeout
 
     def _request_get(self, path: str, pb_type):
+        return self._request("GET", path, None, pb_type)
+
+    def _request_post(self, path: str, body_proto, pb_type):
+        return self._request("POST", path, body_proto.SerializeToString(), pb_type)
+
+    def _request(self, method: str, path: str, body, pb_type):
         kwargs = {}
         if self.timeout is not None:
             kwargs["timeout"] = self.timeout
         client = http.client.HTTPConnection(self.host, self.port, **kwargs)
-        client.request("GET", path)
+        headers = {}
+        if body is not None:
+            headers["Content-Type"] = self.CONTENT_TYPE
+        client.request(method, path, body=body, headers=headers)
         response = client.getresponse()
         content_type = response.getheader("Content-Type")
         body = response.read()
@@ -139,9 +164,21 @@ class ChronikClient:
     def blockchain_info(self) -> ChronikResponse:
         return self._request_get("/blockchain-info", pb.BlockchainInfo)
 
+    def mempool_info(self) -> ChronikResponse:
+        return self._request_get("/mempool-info", pb.MempoolInfo)
+
+    def db_stats(self) -> ChronikResponse:
+        return self._request_get("/db-stats", pb.DbStats)
+
+    def debug_stats(self) -> ChronikResponse:
+        return self._request_get("/debug/stats", pb.DebugStats)
+
     def block(self, hash_or_height: Union[str, int]) -> ChronikResponse:
         return self._request_get(f"/block/{hash_or_height}", pb.Block)
 
+    def block_header(self, hash_or_height: Union[str, int]) -> ChronikResponse:
+        return self._request_get(f"/block-header/{hash_or_height}", pb.BlockHeader)
+
     def block_txs(
         self, hash_or_height: Union[str, int], page=None, page_size=None
     ) -> ChronikResponse:
@@ -153,12 +190,28 @@ class ChronikClient:
     def blocks(self, start_height: int, end_height: int) -> ChronikResponse:
         return self._request_get(f"/blocks/{start_height}/{end_height}", pb.Blocks)
 
+    def recent_blocks(self, num_blocks: int) -> ChronikResponse:
+        return self._request_get(f"/recent-blocks/{num_blocks}", pb.Blocks)
+
     def tx(self, txid: str) -> ChronikResponse:
         return self._request_get(f"/tx/{txid}", pb.Tx)
 
+    def broadcast_tx(self, raw_tx: bytes) -> ChronikResponse:
+        return self._request_post(
+            "/tx/broadcast",
+            pb.BroadcastTxRequest(raw_tx=raw_tx),
+            pb.BroadcastTxResponse,
+        )
+
     def raw_tx(self, txid: str) -> bytes:
         return self._request_get(f"/raw-tx/{txid}", pb.RawTx)
 
+    def tx_block(self, txid: str) -> ChronikResponse:
+        return self._request_get(f"/tx-block/{txid}", pb.BlockMetadata)
+
+    def tx_conflicts(self, txid: str) -> ChronikResponse:
+        return self._request_get(f"/tx-conflicts/{txid}", pb.TxConflicts)
+
     def script(self, script_type: str, script_payload: str) -> ChronikScriptClient:
         return ChronikScriptClient(self, script_type, script_payload)
 
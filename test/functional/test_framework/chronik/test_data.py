@@ -28,6 +28,7 @@ def genesis_cb_tx():
             pb.TxOutput(
                 value=5000000000,
                 output_script=bytes(GENESIS_CB_SCRIPT_PUBKEY),
+                output_script_type="p2pk",
             )
         ],
         lock_time=0,